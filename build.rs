@@ -0,0 +1,58 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    #[cfg(feature = "speex")]
+    speex();
+}
+
+/// Builds the floating point decoder and encoder of libspeex 1.2.1 from `vendor/speex`.
+#[cfg(feature = "speex")]
+fn speex() {
+    const SOURCES: &[&str] = &[
+        "bits.c",
+        "cb_search.c",
+        "exc_10_16_table.c",
+        "exc_10_32_table.c",
+        "exc_20_32_table.c",
+        "exc_5_256_table.c",
+        "exc_5_64_table.c",
+        "exc_8_128_table.c",
+        "filters.c",
+        "gain_table.c",
+        "gain_table_lbr.c",
+        "hexc_10_32_table.c",
+        "hexc_table.c",
+        "high_lsp_tables.c",
+        "lpc.c",
+        "lsp.c",
+        "lsp_tables_nb.c",
+        "ltp.c",
+        "modes.c",
+        "modes_wb.c",
+        "nb_celp.c",
+        "quant_lsp.c",
+        "sb_celp.c",
+        "speex.c",
+        "speex_callbacks.c",
+        "speex_header.c",
+        "stereo.c",
+        "vbr.c",
+        "vq.c",
+        "window.c",
+    ];
+
+    println!("cargo:rerun-if-changed=vendor/speex");
+
+    cc::Build::new()
+        .files(
+            SOURCES
+                .iter()
+                .map(|file| format!("vendor/speex/libspeex/{file}")),
+        )
+        .include("vendor/speex/include")
+        .include("vendor/speex/libspeex")
+        .define("FLOATING_POINT", None)
+        .define("EXPORT", Some(""))
+        .warnings(false)
+        .compile("speex");
+}
@@ -0,0 +1,879 @@
+//! A compressed, column-oriented archive of a demo, for storing many demos and querying their
+//! movement and messages without reading whole files.
+//!
+//! Each kind of data is its own deflate-compressed column: frame types, times and numbers, the
+//! [`crate::types::DemoInfo`] of every network frame, the data of the other frames, and the
+//! network messages. [`DemoInfo`] rows are stored byte by byte across frames, so values that
+//! change slowly such as origins and angles compress well.
+//!
+//! How network messages are kept follows [`MessageDataParseMode`]:
+//!
+//! - [`MessageDataParseMode::Parse`] splits parsed messages into a column for each message type,
+//!   so messages of one type can be read without the rest.
+//! - [`MessageDataParseMode::Raw`] keeps the bytes of each frame as they are written.
+//! - [`MessageDataParseMode::None`] leaves messages out for the smallest archive.
+//!
+//! Frames whose messages were not parsed are kept raw in the first mode. Unless messages are
+//! left out, [`Archive::to_dem_bytes`] gives back exactly what [`Demo::write_to_bytes`] writes.
+//!
+//! The archive starts with an uncompressed [`ArchiveIndex`] of the header, entries, message
+//! counts and columns, which is all that has to be read to filter demos.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let demo = open_demo("./src/tests/demotest.dem").unwrap();
+//! let bytes = demo.to_archive(&ArchiveOptions::default()).unwrap();
+//!
+//! let archive = Archive::parse_from_bytes(&bytes).unwrap();
+//! println!("{:?}", archive.index().message_counts);
+//!
+//! let origins: Vec<_> = archive
+//!     .demo_infos()
+//!     .unwrap()
+//!     .into_iter()
+//!     .map(|info| info.refparams.sim_org)
+//!     .collect();
+//!
+//! let demo = archive.to_demo(MessageDataParseMode::Parse).unwrap();
+//! ```
+use std::{
+    collections::{BTreeMap, btree_map::Entry},
+    io::{Read, Write},
+};
+
+use flate2::{Compression, read::DeflateDecoder, write::DeflateEncoder};
+
+use crate::{
+    byte_writer::ByteWriter,
+    demo_parser::{parse_frame, parse_network_messages_info},
+    demo_writer::{frame_type, write_frame_data, write_messages},
+    error::DemoError,
+    types::{
+        ByteString, Demo, DemoInfo, DemoState, Directory, DirectoryEntry, FrameData, Header,
+        MessageData, MessageDataParseMode, NetworkMessageType,
+    },
+};
+
+const ARCHIVE_MAGIC: &[u8; 8] = b"HLDEMARC";
+const ARCHIVE_VERSION: u32 = 2;
+
+/// How the messages of a network frame are stored.
+const MESSAGES_NONE: u8 = 0;
+const MESSAGES_RAW: u8 = 1;
+const MESSAGES_SPLIT: u8 = 2;
+
+#[derive(Debug, Clone)]
+pub struct ArchiveOptions {
+    pub messages: MessageDataParseMode,
+    /// Deflate level from 0 to 9.
+    pub level: u32,
+}
+
+impl Default for ArchiveOptions {
+    fn default() -> Self {
+        Self {
+            messages: MessageDataParseMode::Parse,
+            level: 6,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ArchiveEntry {
+    pub type_: i32,
+    pub description: ByteString,
+    pub flags: i32,
+    pub cd_track: i32,
+    pub track_time: f32,
+    /// Frames stored for the entry.
+    pub frame_count: u32,
+    pub network_frame_count: u32,
+    /// [`DirectoryEntry::frame_count`] as read from the file, which can differ from the frames.
+    pub directory_frame_count: i32,
+    /// [`crate::types::Frame::time`] of the first and last frame.
+    pub start_time: f32,
+    pub end_time: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct ArchiveColumn {
+    pub name: String,
+    /// From the end of the index.
+    pub offset: u32,
+    pub compressed_length: u32,
+    pub length: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct ArchiveIndex {
+    pub messages: MessageDataParseMode,
+    pub header: Header,
+    pub entries: Vec<ArchiveEntry>,
+    /// Message type to how many there are in the whole demo. Only counted when messages are
+    /// split by type.
+    pub message_counts: BTreeMap<u8, u32>,
+    pub columns: Vec<ArchiveColumn>,
+}
+
+/// An archive read from bytes. Columns are decompressed when they are asked for.
+#[derive(Debug, Clone)]
+pub struct Archive<'a> {
+    index: ArchiveIndex,
+    data: &'a [u8],
+}
+
+/// Column contents before compression.
+#[derive(Default)]
+struct Columns {
+    columns: BTreeMap<String, Vec<u8>>,
+}
+
+impl Columns {
+    fn get(&mut self, name: &str) -> &mut Vec<u8> {
+        self.columns.entry(name.to_owned()).or_default()
+    }
+
+    fn push_blob(&mut self, name: &str, bytes: &[u8]) {
+        self.get(name).extend(bytes);
+        self.get(&format!("{name}_lengths"))
+            .extend((bytes.len() as u32).to_le_bytes());
+    }
+}
+
+impl Demo {
+    /// Writes the demo as an archive. See the [module docs](self) for the layout.
+    pub fn to_archive(&self, options: &ArchiveOptions) -> Result<Vec<u8>, DemoError> {
+        let mut columns = Columns::default();
+        let mut info_rows: Vec<Vec<u8>> = vec![];
+        let mut message_counts: BTreeMap<u8, u32> = BTreeMap::new();
+        let mut entries = vec![];
+        let mut state = DemoState::new_raw();
+
+        for entry in &self.directory.entries {
+            let mut network_frame_count = 0;
+
+            for frame in &entry.frames {
+                columns
+                    .get("frame_types")
+                    .push(frame_type(&frame.frame_data));
+                columns.get("frame_times").extend(frame.time.to_le_bytes());
+                columns
+                    .get("frame_numbers")
+                    .extend(frame.frame.to_le_bytes());
+
+                let mut writer = ByteWriter::new();
+                write_frame_data(&mut writer, &frame.frame_data);
+
+                let FrameData::NetworkMessage(box_type) = &frame.frame_data else {
+                    columns.push_blob("frame_data", &writer.data);
+                    continue;
+                };
+
+                network_frame_count += 1;
+                info_rows.push(writer.data);
+
+                let kind = match (&box_type.1.messages, options.messages) {
+                    (_, MessageDataParseMode::None) | (MessageData::None, _) => MESSAGES_NONE,
+                    (MessageData::Parsed(_), MessageDataParseMode::Parse) => MESSAGES_SPLIT,
+                    _ => MESSAGES_RAW,
+                };
+
+                columns.get("message_kinds").push(kind);
+
+                match (kind, &box_type.1.messages) {
+                    (MESSAGES_SPLIT, MessageData::Parsed(messages)) => {
+                        columns
+                            .get("message_counts")
+                            .extend((messages.len() as u32).to_le_bytes());
+
+                        for message in messages {
+                            let bytes = message.write(&state);
+                            state.apply(message);
+
+                            columns.get("message_types").push(bytes[0]);
+                            columns
+                                .get("message_lengths")
+                                .extend((bytes.len() as u32 - 1).to_le_bytes());
+                            columns
+                                .get(&format!("messages/{}", bytes[0]))
+                                .extend(&bytes[1..]);
+
+                            *message_counts.entry(bytes[0]).or_default() += 1;
+                        }
+                    }
+                    (MESSAGES_RAW, messages) => {
+                        let mut writer = ByteWriter::new();
+                        write_messages(&mut writer, messages, &mut state);
+
+                        // without the length in front
+                        columns.push_blob("message_blobs", &writer.data[4..]);
+                    }
+                    _ => (),
+                }
+            }
+
+            entries.push(ArchiveEntry {
+                type_: entry.type_,
+                description: entry.description.clone(),
+                flags: entry.flags,
+                cd_track: entry.cd_track,
+                track_time: entry.track_time,
+                frame_count: entry.frames.len() as u32,
+                network_frame_count,
+                directory_frame_count: entry.frame_count,
+                start_time: entry
+                    .frames
+                    .first()
+                    .map(|frame| frame.time)
+                    .unwrap_or_default(),
+                end_time: entry
+                    .frames
+                    .last()
+                    .map(|frame| frame.time)
+                    .unwrap_or_default(),
+            });
+        }
+
+        for row in &info_rows {
+            columns
+                .get("info_lengths")
+                .extend((row.len() as u32).to_le_bytes());
+        }
+
+        *columns.get("info") = transpose(&info_rows);
+
+        // compress every column
+        let mut data = vec![];
+        let mut index_columns = vec![];
+
+        for (name, bytes) in columns.columns {
+            let mut encoder = DeflateEncoder::new(vec![], Compression::new(options.level));
+            encoder.write_all(&bytes)?;
+            let compressed = encoder.finish()?;
+
+            index_columns.push(ArchiveColumn {
+                name,
+                offset: data.len() as u32,
+                compressed_length: compressed.len() as u32,
+                length: bytes.len() as u32,
+            });
+
+            data.extend(compressed);
+        }
+
+        let index = ArchiveIndex {
+            messages: options.messages,
+            header: self.header.clone(),
+            entries,
+            message_counts,
+            columns: index_columns,
+        };
+
+        let index = index.write_to_bytes();
+
+        let mut writer = ByteWriter::new();
+        writer.append_u8_slice(ARCHIVE_MAGIC);
+        writer.append_u32(ARCHIVE_VERSION);
+        writer.append_u32(index.len() as u32);
+        writer.append_u8_slice(&index);
+        writer.append_u8_slice(&data);
+
+        Ok(writer.data)
+    }
+}
+
+impl ArchiveIndex {
+    fn write_to_bytes(&self) -> Vec<u8> {
+        let mut writer = ByteWriter::new();
+
+        writer.append_u8(match self.messages {
+            MessageDataParseMode::Parse => 0,
+            MessageDataParseMode::Raw => 1,
+            MessageDataParseMode::None => 2,
+        });
+
+        let mut magic = self.header.magic.clone();
+        magic.resize(8, 0);
+
+        writer.append_u8_slice(&magic);
+        writer.append_i32(self.header.demo_protocol);
+        writer.append_i32(self.header.network_protocol);
+        writer.append_u8_slice(self.header.map_name.padded(260).as_slice());
+        writer.append_u8_slice(self.header.game_directory.padded(260).as_slice());
+        writer.append_u32(self.header.map_checksum);
+        writer.append_i32(self.header.directory_offset);
+
+        writer.append_u32(self.entries.len() as u32);
+
+        for entry in &self.entries {
+            writer.append_i32(entry.type_);
+            writer.append_u8_slice(entry.description.padded(64).as_slice());
+            writer.append_i32(entry.flags);
+            writer.append_i32(entry.cd_track);
+            writer.append_f32(entry.track_time);
+            writer.append_u32(entry.frame_count);
+            writer.append_u32(entry.network_frame_count);
+            writer.append_i32(entry.directory_frame_count);
+            writer.append_f32(entry.start_time);
+            writer.append_f32(entry.end_time);
+        }
+
+        writer.append_u32(self.message_counts.len() as u32);
+
+        for (&type_, &count) in &self.message_counts {
+            writer.append_u8(type_);
+            writer.append_u32(count);
+        }
+
+        writer.append_u32(self.columns.len() as u32);
+
+        for column in &self.columns {
+            writer.append_u8(column.name.len() as u8);
+            writer.append_u8_slice(column.name.as_bytes());
+            writer.append_u32(column.offset);
+            writer.append_u32(column.compressed_length);
+            writer.append_u32(column.length);
+        }
+
+        writer.data
+    }
+
+    fn parse(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader { bytes, offset: 0 };
+
+        let messages = match reader.u8()? {
+            0 => MessageDataParseMode::Parse,
+            1 => MessageDataParseMode::Raw,
+            2 => MessageDataParseMode::None,
+            _ => return None,
+        };
+
+        let header = Header {
+            magic: reader.take(8)?.to_vec(),
+            demo_protocol: reader.i32()?,
+            network_protocol: reader.i32()?,
+            map_name: reader.take(260)?.to_vec().into(),
+            game_directory: reader.take(260)?.to_vec().into(),
+            map_checksum: reader.u32()?,
+            directory_offset: reader.i32()?,
+        };
+
+        let entries = (0..reader.u32()?)
+            .map(|_| {
+                Some(ArchiveEntry {
+                    type_: reader.i32()?,
+                    description: reader.take(64)?.to_vec().into(),
+                    flags: reader.i32()?,
+                    cd_track: reader.i32()?,
+                    track_time: reader.f32()?,
+                    frame_count: reader.u32()?,
+                    network_frame_count: reader.u32()?,
+                    directory_frame_count: reader.i32()?,
+                    start_time: reader.f32()?,
+                    end_time: reader.f32()?,
+                })
+            })
+            .collect::<Option<_>>()?;
+
+        let message_counts = (0..reader.u32()?)
+            .map(|_| Some((reader.u8()?, reader.u32()?)))
+            .collect::<Option<_>>()?;
+
+        let columns = (0..reader.u32()?)
+            .map(|_| {
+                let name_length = reader.u8()? as usize;
+
+                Some(ArchiveColumn {
+                    name: String::from_utf8(reader.take(name_length)?.to_vec()).ok()?,
+                    offset: reader.u32()?,
+                    compressed_length: reader.u32()?,
+                    length: reader.u32()?,
+                })
+            })
+            .collect::<Option<_>>()?;
+
+        Some(Self {
+            messages,
+            header,
+            entries,
+            message_counts,
+            columns,
+        })
+    }
+}
+
+impl<'a> Archive<'a> {
+    /// Reads the index. Nothing is decompressed yet.
+    pub fn parse_from_bytes(bytes: &'a [u8]) -> Result<Self, DemoError> {
+        let mut reader = Reader { bytes, offset: 0 };
+
+        if reader.take(8) != Some(ARCHIVE_MAGIC) || reader.u32() != Some(ARCHIVE_VERSION) {
+            return Err(DemoError::InvalidArchive);
+        }
+
+        let index_length = reader.u32().ok_or(DemoError::InvalidArchive)? as usize;
+        let index = reader
+            .take(index_length)
+            .and_then(ArchiveIndex::parse)
+            .ok_or(DemoError::InvalidArchive)?;
+
+        Ok(Self {
+            index,
+            data: &bytes[reader.offset..],
+        })
+    }
+
+    pub fn index(&self) -> &ArchiveIndex {
+        &self.index
+    }
+
+    /// Decompressed contents of a column. Columns that are not in the archive are empty.
+    pub fn column(&self, name: &str) -> Result<Vec<u8>, DemoError> {
+        let Some(column) = self.index.columns.iter().find(|column| column.name == name) else {
+            return Ok(vec![]);
+        };
+
+        let offset = column.offset as usize;
+        let compressed = self
+            .data
+            .get(offset..offset + column.compressed_length as usize)
+            .ok_or(DemoError::InvalidArchive)?;
+
+        // the lengths come from the file, so only one byte more than promised is decompressed and
+        // nothing is allocated up front
+        let mut bytes = vec![];
+        DeflateDecoder::new(compressed)
+            .take(column.length as u64 + 1)
+            .read_to_end(&mut bytes)?;
+
+        if bytes.len() != column.length as usize {
+            return Err(DemoError::InvalidArchive);
+        }
+
+        Ok(bytes)
+    }
+
+    /// [`crate::types::Frame::time`] of every frame of every entry.
+    pub fn frame_times(&self) -> Result<Vec<f32>, DemoError> {
+        Ok(self
+            .column("frame_times")?
+            .chunks_exact(4)
+            .map(|bytes| f32::from_le_bytes(bytes.try_into().unwrap()))
+            .collect())
+    }
+
+    /// [`DemoInfo`] of every network frame, without reading any messages.
+    pub fn demo_infos(&self) -> Result<Vec<DemoInfo>, DemoError> {
+        self.info_rows()?
+            .iter()
+            .map(|row| {
+                parse_network_messages_info(row)
+                    .map(|(_, info)| info)
+                    .map_err(|_| DemoError::InvalidArchive)
+            })
+            .collect()
+    }
+
+    /// Every message of the type in order, type byte included. Messages are only split by type
+    /// when the archive was written with [`MessageDataParseMode::Parse`].
+    pub fn messages_of_type(&self, type_: u8) -> Result<Vec<Vec<u8>>, DemoError> {
+        let types = self.column("message_types")?;
+        let lengths = u32_values(&self.column("message_lengths")?);
+        let bodies = self.column(&format!("messages/{type_}"))?;
+
+        let mut offset = 0;
+        let mut messages = vec![];
+
+        for (_, length) in types
+            .iter()
+            .zip(lengths)
+            .filter(|(other, _)| **other == type_)
+        {
+            let body = bodies
+                .get(offset..offset + length as usize)
+                .ok_or(DemoError::InvalidArchive)?;
+
+            messages.push([&[type_], body].concat());
+            offset += length as usize;
+        }
+
+        Ok(messages)
+    }
+
+    /// Builds the demo file back with [`Demo::write_to_bytes`]. Frames stored without messages get
+    /// none.
+    pub fn to_dem_bytes(&self) -> Result<Vec<u8>, DemoError> {
+        Ok(self.to_raw_demo()?.write_to_bytes())
+    }
+
+    /// Builds the demo back and parses it with the mode.
+    pub fn to_demo(&self, mode: MessageDataParseMode) -> Result<Demo, DemoError> {
+        Demo::parse_from_bytes(&self.to_dem_bytes()?, mode)
+    }
+
+    /// Puts every frame back together from the columns and reads it with raw messages.
+    fn to_raw_demo(&self) -> Result<Demo, DemoError> {
+        let invalid = || DemoError::InvalidArchive;
+
+        let frame_types = self.column("frame_types")?;
+        let frame_times = self.column("frame_times")?;
+        let frame_numbers = self.column("frame_numbers")?;
+        let mut frame_data = Blobs::new(
+            self.column("frame_data")?,
+            &self.column("frame_data_lengths")?,
+        );
+        let info_rows = self.info_rows()?;
+        let message_kinds = self.column("message_kinds")?;
+        let mut message_blobs = Blobs::new(
+            self.column("message_blobs")?,
+            &self.column("message_blobs_lengths")?,
+        );
+        let mut message_counts = u32_values(&self.column("message_counts")?).into_iter();
+        let mut message_types = self.column("message_types")?.into_iter();
+        let mut message_lengths = u32_values(&self.column("message_lengths")?).into_iter();
+        // message type to the column and how far it has been read
+        let mut message_bodies: BTreeMap<u8, (Vec<u8>, usize)> = BTreeMap::new();
+
+        let mut frame_index = 0;
+        let mut network_frame_index = 0;
+        let mut state = DemoState::new_raw();
+        let mut entries = vec![];
+
+        for entry in &self.index.entries {
+            let mut frames = vec![];
+
+            for _ in 0..entry.frame_count {
+                let type_ = *frame_types.get(frame_index).ok_or_else(invalid)?;
+                let mut writer = ByteWriter::new();
+
+                writer.append_u8(type_);
+                writer.append_u8_slice(
+                    frame_times
+                        .get(frame_index * 4..frame_index * 4 + 4)
+                        .ok_or_else(invalid)?,
+                );
+                writer.append_u8_slice(
+                    frame_numbers
+                        .get(frame_index * 4..frame_index * 4 + 4)
+                        .ok_or_else(invalid)?,
+                );
+
+                frame_index += 1;
+
+                if NetworkMessageType::try_from(type_).is_err() {
+                    writer.append_u8_slice(frame_data.next().ok_or_else(invalid)?);
+                } else {
+                    writer.append_u8_slice(info_rows.get(network_frame_index).ok_or_else(invalid)?);
+
+                    match message_kinds.get(network_frame_index) {
+                        Some(&MESSAGES_NONE) => writer.append_u32(0),
+                        Some(&MESSAGES_RAW) => {
+                            let blob = message_blobs.next().ok_or_else(invalid)?;
+
+                            writer.append_u32(blob.len() as u32);
+                            writer.append_u8_slice(blob);
+                        }
+                        Some(&MESSAGES_SPLIT) => {
+                            let length_pos = writer.get_offset();
+                            writer.append_u32(0);
+
+                            for _ in 0..message_counts.next().ok_or_else(invalid)? {
+                                let message_type = message_types.next().ok_or_else(invalid)?;
+                                let length = message_lengths.next().ok_or_else(invalid)? as usize;
+
+                                let (column, offset) = match message_bodies.entry(message_type) {
+                                    Entry::Occupied(entry) => entry.into_mut(),
+                                    Entry::Vacant(entry) => entry.insert((
+                                        self.column(&format!("messages/{message_type}"))?,
+                                        0,
+                                    )),
+                                };
+                                let body =
+                                    column.get(*offset..*offset + length).ok_or_else(invalid)?;
+
+                                writer.append_u8(message_type);
+                                writer.append_u8_slice(body);
+                                *offset += length;
+                            }
+
+                            let length = writer.get_offset() - length_pos - 4;
+                            writer
+                                .data
+                                .splice(length_pos..length_pos + 4, (length as u32).to_le_bytes());
+                        }
+                        _ => return Err(invalid()),
+                    }
+
+                    network_frame_index += 1;
+                }
+
+                let (_, frame) = parse_frame(&writer.data, MessageDataParseMode::Raw, &mut state)
+                    .map_err(|_| invalid())?;
+
+                frames.push(frame);
+            }
+
+            entries.push(DirectoryEntry {
+                type_: entry.type_,
+                description: entry.description.clone(),
+                flags: entry.flags,
+                cd_track: entry.cd_track,
+                track_time: entry.track_time,
+                frame_count: entry.directory_frame_count,
+                frame_offset: 0,
+                file_length: 0,
+                frames,
+            });
+        }
+
+        Ok(Demo {
+            header: self.index.header.clone(),
+            directory: Directory { entries },
+            _state: None,
+        })
+    }
+
+    fn info_rows(&self) -> Result<Vec<Vec<u8>>, DemoError> {
+        let lengths = u32_values(&self.column("info_lengths")?);
+        let info = self.column("info")?;
+
+        untranspose(&info, &lengths).ok_or(DemoError::InvalidArchive)
+    }
+}
+
+/// Little-endian reads that stop at the end.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Option<&'a [u8]> {
+        let bytes = self.bytes.get(self.offset..self.offset + length)?;
+        self.offset += length;
+
+        Some(bytes)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn i32(&mut self) -> Option<i32> {
+        Some(i32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn f32(&mut self) -> Option<f32> {
+        Some(f32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+}
+
+/// A column of byte strings with their lengths in another column.
+struct Blobs {
+    bytes: Vec<u8>,
+    lengths: std::vec::IntoIter<u32>,
+    offset: usize,
+}
+
+impl Blobs {
+    fn new(bytes: Vec<u8>, lengths: &[u8]) -> Self {
+        Self {
+            bytes,
+            lengths: u32_values(lengths).into_iter(),
+            offset: 0,
+        }
+    }
+
+    fn next(&mut self) -> Option<&[u8]> {
+        let length = self.lengths.next()? as usize;
+        let blob = self.bytes.get(self.offset..self.offset + length)?;
+        self.offset += length;
+
+        Some(blob)
+    }
+}
+
+fn u32_values(bytes: &[u8]) -> Vec<u32> {
+    bytes
+        .chunks_exact(4)
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+        .collect()
+}
+
+/// Puts the first byte of every row first, then the second byte and so on. Rows of different
+/// lengths are only joined.
+fn transpose(rows: &[Vec<u8>]) -> Vec<u8> {
+    let Some(first) = rows.first() else {
+        return vec![];
+    };
+
+    if rows.iter().any(|row| row.len() != first.len()) {
+        return rows.concat();
+    }
+
+    (0..first.len())
+        .flat_map(|column| rows.iter().map(move |row| row[column]))
+        .collect()
+}
+
+fn untranspose(bytes: &[u8], lengths: &[u32]) -> Option<Vec<Vec<u8>>> {
+    let Some(&first) = lengths.first() else {
+        return Some(vec![]);
+    };
+
+    if lengths.iter().any(|&length| length != first) {
+        let mut offset = 0;
+
+        return lengths
+            .iter()
+            .map(|&length| {
+                let row = bytes.get(offset..offset + length as usize)?.to_vec();
+                offset += length as usize;
+
+                Some(row)
+            })
+            .collect();
+    }
+
+    let row_count = lengths.len();
+
+    if bytes.len() != row_count * first as usize {
+        return None;
+    }
+
+    Some(
+        (0..row_count)
+            .map(|row| {
+                (0..first as usize)
+                    .map(|column| bytes[column * row_count + row])
+                    .collect()
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::open_demo;
+
+    #[test]
+    fn round_trip() {
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let dem_bytes = std::fs::read("./src/tests/weapon.dem").unwrap();
+
+        for messages in [MessageDataParseMode::Parse, MessageDataParseMode::Raw] {
+            let bytes = demo
+                .to_archive(&ArchiveOptions {
+                    messages,
+                    ..Default::default()
+                })
+                .unwrap();
+            let archive = Archive::parse_from_bytes(&bytes).unwrap();
+
+            assert!(bytes.len() < dem_bytes.len());
+            assert_eq!(archive.to_dem_bytes().unwrap(), dem_bytes);
+
+            // the file says 0 and 736 though there are more frames
+            let directory_frame_counts: Vec<_> = archive
+                .index()
+                .entries
+                .iter()
+                .map(|entry| entry.directory_frame_count)
+                .collect();
+
+            assert_eq!(directory_frame_counts, [0, 736]);
+        }
+    }
+
+    #[test]
+    fn query() {
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let bytes = demo.to_archive(&ArchiveOptions::default()).unwrap();
+        let archive = Archive::parse_from_bytes(&bytes).unwrap();
+        let index = archive.index();
+
+        assert_eq!(index.entries.len(), demo.directory.entries.len());
+        assert_eq!(
+            archive.frame_times().unwrap().len(),
+            demo.directory
+                .entries
+                .iter()
+                .map(|entry| entry.frames.len())
+                .sum::<usize>()
+        );
+
+        let infos = archive.demo_infos().unwrap();
+        let first_info = demo
+            .directory
+            .entries
+            .iter()
+            .flat_map(|entry| &entry.frames)
+            .find_map(|frame| frame.frame_data.get_network_message())
+            .map(|box_type| &box_type.1.info)
+            .unwrap();
+
+        assert_eq!(infos[0].refparams.sim_org, first_info.refparams.sim_org);
+        assert_eq!(infos[0].usercmd.buttons, first_info.usercmd.buttons);
+
+        // SVC_TIME is in every frame
+        let times = archive.messages_of_type(7).unwrap();
+        assert_eq!(times.len(), index.message_counts[&7] as usize);
+        assert!(times.iter().all(|message| message.len() == 5));
+
+        assert!(matches!(
+            Archive::parse_from_bytes(&bytes[..20]),
+            Err(DemoError::InvalidArchive)
+        ));
+    }
+
+    #[test]
+    fn wrong_column_length() {
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let bytes = demo.to_archive(&ArchiveOptions::default()).unwrap();
+        let mut archive = Archive::parse_from_bytes(&bytes).unwrap();
+
+        let index = archive
+            .index
+            .columns
+            .iter()
+            .position(|column| column.name == "frame_times")
+            .unwrap();
+        let length = archive.index.columns[index].length;
+
+        for wrong_length in [length - 1, length + 1, u32::MAX] {
+            archive.index.columns[index].length = wrong_length;
+
+            assert!(matches!(
+                archive.frame_times(),
+                Err(DemoError::InvalidArchive)
+            ));
+        }
+    }
+
+    #[test]
+    fn without_messages() {
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let bytes = demo
+            .to_archive(&ArchiveOptions {
+                messages: MessageDataParseMode::None,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let from_archive = Archive::parse_from_bytes(&bytes)
+            .unwrap()
+            .to_demo(MessageDataParseMode::Parse)
+            .unwrap();
+
+        assert_eq!(
+            from_archive.directory.entries[1].frames.len(),
+            demo.directory.entries[1].frames.len()
+        );
+    }
+}
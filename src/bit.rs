@@ -1,16 +1,23 @@
 use bitvec::{field::BitField, order::Lsb0, slice::BitSlice as _BitSlice};
+use nom::{bytes::complete::take, combinator::fail, error::context};
 
 use self::types::BitVec;
+use crate::nom_helper::NomResult;
 
 use super::*;
 
 pub type BitSlice = _BitSlice<u8, Lsb0>;
 
+/// Zeroes handed out for reads past the end, enough for the widest value.
+static ZEROES: [u8; 4] = [0; 4];
+
 // Wraps bytes into bits because doing this with nom is a very bad idea.
 pub struct BitReader<'a> {
     pub bytes: &'a BitSlice,
     // Bit offset, starting from starting of `bytes`.
     offset: usize,
+    // Like `msg_badread`, set when reading past the end or reading garbage.
+    bad_read: bool,
 }
 
 impl<'a> BitReader<'a> {
@@ -18,17 +25,30 @@ impl<'a> BitReader<'a> {
         BitReader {
             bytes: BitSlice::from_slice(bytes),
             offset: 0,
+            bad_read: false,
         }
     }
 
+    /// Reads past the end give 0 and mark the reader bad.
     pub fn read_1_bit(&mut self) -> bool {
-        let res = self.bytes[self.offset];
+        let Some(res) = self.bytes.get(self.offset).map(|bit| *bit) else {
+            self.mark_bad_read();
+            return false;
+        };
+
         self.offset += 1;
         res
     }
 
+    /// Reads past the end give zeroes and mark the reader bad. `n` is at most 32.
     pub fn read_n_bit(&mut self, n: usize) -> &BitSlice {
         let range = self.offset + n;
+
+        if range > self.bytes.len() || n > ZEROES.len() * 8 {
+            self.mark_bad_read();
+            return &BitSlice::from_slice(&ZEROES)[..n.min(ZEROES.len() * 8)];
+        }
+
         let res: &BitSlice = &self.bytes[self.offset..range];
         self.offset += n;
         res
@@ -57,10 +77,15 @@ impl<'a> BitReader<'a> {
     pub fn read_string(&mut self) -> &BitSlice {
         let start = self.offset;
 
-        while self.peek_byte() != 0 {
+        while self.offset + 8 <= self.bytes.len() && self.peek_byte() != 0 {
             self.offset += 8;
         }
 
+        if self.offset + 8 > self.bytes.len() {
+            self.mark_bad_read();
+            return &self.bytes[start..self.offset];
+        }
+
         // Includes the null terminator.
         self.offset += 8;
 
@@ -71,12 +96,7 @@ impl<'a> BitReader<'a> {
         let mut res = [0u8; N];
 
         for x in res.iter_mut().take(N) {
-            let start = self.offset;
-            let end = start + 8;
-
-            *x = self.bytes[start..end].load_le::<u8>();
-
-            self.offset += 8;
+            *x = self.read_n_bit(8).load_le::<u8>();
         }
 
         res
@@ -87,8 +107,30 @@ impl<'a> BitReader<'a> {
         self.peek_n_bits(8).to_u8()
     }
 
+    /// Peeks past the end give zeroes. `n` is at most 32.
     pub fn peek_n_bits(&self, n: usize) -> &BitSlice {
-        &self.bytes[self.offset..self.offset + n]
+        self.bytes
+            .get(self.offset..self.offset + n)
+            .unwrap_or(&BitSlice::from_slice(&ZEROES)[..n.min(ZEROES.len() * 8)])
+    }
+
+    /// Marks the reader bad, for values that cannot be right.
+    pub fn mark_bad_read(&mut self) {
+        self.bad_read = true;
+    }
+
+    pub fn is_bad_read(&self) -> bool {
+        self.bad_read
+    }
+
+    /// Takes the bytes read so far from `i`, the bytes the reader was made from. Fails if the
+    /// reader went bad.
+    pub fn take_consumed<'b>(&self, i: &'b [u8]) -> NomResult<'b, &'b [u8]> {
+        if self.bad_read {
+            return context("Bad bit read", fail)(i);
+        }
+
+        take(self.get_consumed_bytes())(i)
     }
 
     pub fn get_offset(&self) -> usize {
@@ -0,0 +1,290 @@
+//! Taking the HUD out of a demo for movie making.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut demo = open_demo("./src/tests/demotest.dem").unwrap();
+//!
+//! demo.cinematic_clean(&CinematicOptions {
+//!     commands: Some("crosshair 0; r_drawviewmodel 0".to_owned()),
+//!     ..Default::default()
+//! });
+//!
+//! demo.write_to_file("./src/tests/clean.dem").unwrap();
+//! ```
+use crate::types::{
+    ByteString, Demo, EngineMessage, NetMessage, SvcStuffText, TempEntity, UserMessage,
+};
+
+/// `HIDEHUD_WEAPONS`, the weapon selection and ammo.
+pub const HIDE_HUD_WEAPONS: u8 = 1 << 0;
+/// `HIDEHUD_FLASHLIGHT`
+pub const HIDE_HUD_FLASHLIGHT: u8 = 1 << 1;
+/// `HIDEHUD_ALL`, everything drawn by the HUD.
+pub const HIDE_HUD_ALL: u8 = 1 << 2;
+/// `HIDEHUD_HEALTH`, health and armor.
+pub const HIDE_HUD_HEALTH: u8 = 1 << 3;
+/// `HIDEHUD_TIMER` in Counter-Strike.
+pub const HIDE_HUD_TIMER: u8 = 1 << 4;
+/// `HIDEHUD_MONEY` in Counter-Strike.
+pub const HIDE_HUD_MONEY: u8 = 1 << 5;
+/// `HIDEHUD_CROSSHAIR` in Counter-Strike.
+pub const HIDE_HUD_CROSSHAIR: u8 = 1 << 6;
+
+/// User messages that only draw on the HUD in Half-Life and Counter-Strike.
+pub const HUD_MESSAGES: &[&str] = &[
+    "HudText",
+    "HudTextPro",
+    "HudTextArgs",
+    "TextMsg",
+    "StatusText",
+    "StatusValue",
+    "StatusIcon",
+    "DeathMsg",
+    "Damage",
+    "Geiger",
+    "ItemPickup",
+    "AmmoPickup",
+    "WeapPickup",
+    "ShowMenu",
+    "VGUIMenu",
+    "BarTime",
+    "BarTime2",
+    "ShowTimer",
+    "RoundTime",
+    "Money",
+    "BlinkAcct",
+    "Radar",
+    "BombDrop",
+    "BombPickup",
+    "HostagePos",
+    "HostageK",
+];
+
+/// Console variables and commands the server may send to turn parts of the HUD back on.
+const HUD_COMMANDS: &[&str] = &[
+    "hud_draw",
+    "hud_centerid",
+    "hud_saytext",
+    "crosshair",
+    "cl_crosshair_size",
+    "cl_showfps",
+    "net_graph",
+    "drawradar",
+    "hideradar",
+];
+
+#[derive(Debug, Clone)]
+pub struct CinematicOptions {
+    /// Removes [`crate::types::SvcCenterPrint`].
+    pub center_print: bool,
+    /// Removes [`crate::types::TeTextMessage`] temporary entities.
+    pub text_messages: bool,
+    /// Removes `SayText`.
+    pub chat: bool,
+    /// Removes user messages with these names.
+    pub hud_messages: Vec<String>,
+    /// Removes [`crate::types::SvcCrosshairAngle`].
+    pub crosshair_angle: bool,
+    /// Removes commands in [`crate::types::SvcStuffText`] that change what the HUD draws.
+    pub hud_commands: bool,
+    /// `HideWeapon` flags, such as [`HIDE_HUD_ALL`], to send at the start of playback and
+    /// whenever the HUD is reset. Existing `HideWeapon` messages get these flags instead.
+    ///
+    /// Needs the mod to register `HideWeapon`.
+    pub hide_weapon: Option<u8>,
+    /// Console commands to run at the start of playback.
+    pub commands: Option<String>,
+}
+
+impl Default for CinematicOptions {
+    fn default() -> Self {
+        Self {
+            center_print: true,
+            text_messages: true,
+            chat: true,
+            hud_messages: HUD_MESSAGES.iter().map(|name| name.to_string()).collect(),
+            crosshair_angle: true,
+            hud_commands: true,
+            hide_weapon: Some(HIDE_HUD_ALL),
+            commands: None,
+        }
+    }
+}
+
+impl Demo {
+    /// Removes HUD and overlay messages and hides what is left of the HUD. Every other message
+    /// is kept as it is.
+    pub fn cinematic_clean(&mut self, options: &CinematicOptions) {
+        let is_loading: Vec<bool> = self
+            .directory
+            .entries
+            .iter()
+            .map(|entry| entry.is_loading())
+            .collect();
+
+        let mut started_entry = None;
+
+        self.map_messages(|ctx, mut message| {
+            let mut res = vec![];
+
+            let hide_weapon = options.hide_weapon.and_then(|flags| {
+                let registered = ctx.state.find_user_message("HideWeapon")?;
+
+                Some(NetMessage::UserMessage(UserMessage {
+                    id: registered.index,
+                    name: registered.name.clone(),
+                    data: vec![flags],
+                }))
+            });
+
+            if !is_loading[ctx.entry_index] && started_entry != Some(ctx.entry_index) {
+                started_entry = Some(ctx.entry_index);
+
+                if let Some(commands) = &options.commands {
+                    res.push(stuff_text(commands));
+                }
+
+                res.extend(hide_weapon.clone());
+            }
+
+            let name = ctx.user_message_name(&message).unwrap_or_default();
+
+            match &mut message {
+                NetMessage::UserMessage(user_message) => {
+                    if (options.chat && name == "SayText")
+                        || options.hud_messages.iter().any(|hud| hud == name)
+                    {
+                        return res;
+                    }
+
+                    let is_reset = matches!(name, "ResetHUD" | "InitHUD");
+
+                    if name == "HideWeapon"
+                        && let Some(flags) = options.hide_weapon
+                    {
+                        user_message.data = vec![flags];
+                    }
+
+                    res.push(message);
+
+                    if is_reset {
+                        res.extend(hide_weapon);
+                    }
+                }
+                NetMessage::EngineMessage(engine_message) => {
+                    let is_removed = match engine_message.as_mut() {
+                        EngineMessage::SvcCenterPrint(_) => options.center_print,
+                        EngineMessage::SvcCrosshairAngle(_) => options.crosshair_angle,
+                        EngineMessage::SvcTempEntity(temp_entity) => {
+                            options.text_messages
+                                && matches!(temp_entity.entity, TempEntity::TeTextMessage(_))
+                        }
+                        EngineMessage::SvcStuffText(stuff_text) if options.hud_commands => {
+                            let command = String::from_utf8_lossy(&stuff_text.command.0);
+                            let command = command.trim_end_matches('\0');
+                            let cleaned = remove_hud_commands(command);
+
+                            if cleaned != command {
+                                stuff_text.command = format!("{cleaned}\0").as_str().into();
+                            }
+
+                            cleaned.trim().is_empty()
+                        }
+                        _ => false,
+                    };
+
+                    if !is_removed {
+                        res.push(message);
+                    }
+                }
+            }
+
+            res
+        });
+    }
+}
+
+fn stuff_text(commands: &str) -> NetMessage {
+    NetMessage::EngineMessage(Box::new(EngineMessage::SvcStuffText(SvcStuffText {
+        command: ByteString::from(format!("{commands}\n\0").as_str()),
+    })))
+}
+
+/// Drops every command of a command buffer whose name is in [`HUD_COMMANDS`].
+fn remove_hud_commands(buffer: &str) -> String {
+    buffer
+        .split_inclusive('\n')
+        .map(|line| {
+            let (line, newline) = match line.strip_suffix('\n') {
+                Some(line) => (line, "\n"),
+                None => (line, ""),
+            };
+
+            let commands: Vec<&str> = line
+                .split(';')
+                .filter(|command| {
+                    let name = command.split_whitespace().next().unwrap_or_default();
+
+                    !HUD_COMMANDS.contains(&name)
+                })
+                .collect();
+
+            if commands.iter().all(|command| command.trim().is_empty()) {
+                String::new()
+            } else {
+                commands.join(";") + newline
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{open_demo, open_demo_from_bytes, types::FrameData};
+
+    fn user_messages(demo: &Demo, name: &str) -> Vec<Vec<u8>> {
+        demo.directory
+            .entries
+            .iter()
+            .flat_map(|entry| &entry.frames)
+            .filter_map(|frame| match &frame.frame_data {
+                FrameData::NetworkMessage(box_type) => box_type.1.messages.get_parsed_messages(),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|message| match message {
+                NetMessage::UserMessage(user_message) if user_message.name_str() == name => {
+                    Some(user_message.data.clone())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn hud_commands() {
+        assert_eq!(
+            remove_hud_commands("crosshair 1; say hi\nnet_graph 3\nhud_draw 1;\nfps_max 100\n"),
+            " say hi\nfps_max 100\n"
+        );
+    }
+
+    #[test]
+    fn clean() {
+        let mut demo = open_demo("./src/tests/weapon.dem").unwrap();
+
+        assert!(!user_messages(&demo, "StatusIcon").is_empty());
+
+        demo.cinematic_clean(&CinematicOptions::default());
+
+        let demo = open_demo_from_bytes(&demo.write_to_bytes()).unwrap();
+        let hide_weapon = user_messages(&demo, "HideWeapon");
+
+        assert!(user_messages(&demo, "StatusIcon").is_empty());
+        // the original, one at the start and one after each ResetHUD and InitHUD
+        assert_eq!(hide_weapon.len(), 5);
+        assert!(hide_weapon.iter().all(|data| data == &[HIDE_HUD_ALL]));
+    }
+}
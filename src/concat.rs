@@ -0,0 +1,154 @@
+//! Merging several demos, such as the segments of a run, into one.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let segments: Vec<Demo> = ["c1a0.dem", "c1a0d.dem", "c1a1.dem"]
+//!     .iter()
+//!     .map(|path| open_demo(path).unwrap())
+//!     .collect();
+//!
+//! let run = Demo::concat(&segments, true).unwrap();
+//! run.write_to_file("run.dem").unwrap();
+//! ```
+use crate::{
+    error::DemoError,
+    retime::retime_entry,
+    types::{Demo, Directory, DirectoryEntry, Frame, FrameData},
+};
+
+impl Demo {
+    /// Puts the directory entries of every demo one after another, so each segment keeps its
+    /// LOADING and Playback entries.
+    ///
+    /// The header comes from the first demo. Each entry ends with a [`FrameData::NextSection`]
+    /// frame. Messages are written with the delta decoders and user messages of their own
+    /// segment because the writer builds up the state the same way the parser does.
+    ///
+    /// With `rebase_time`, [`Frame::time`] and [`Frame::frame`] of every Playback entry carry on
+    /// from where the previous one ended instead of starting from 0 again. The clocks in the
+    /// frames and [`DirectoryEntry::track_time`] move along like with [`Demo::retime`].
+    ///
+    /// Every demo needs the same protocols and game directory, and there has to be at least one.
+    pub fn concat(demos: &[Demo], rebase_time: bool) -> Result<Demo, DemoError> {
+        let Some(first) = demos.first() else {
+            return Err(DemoError::IncompatibleDemos);
+        };
+
+        let is_compatible = demos.iter().all(|demo| {
+            demo.header.demo_protocol == first.header.demo_protocol
+                && demo.header.network_protocol == first.header.network_protocol
+                && demo.header.game_directory.to_str() == first.header.game_directory.to_str()
+        });
+
+        if !is_compatible {
+            return Err(DemoError::IncompatibleDemos);
+        }
+
+        let mut entries: Vec<DirectoryEntry> = vec![];
+        let mut time_offset = 0.;
+        let mut frame_offset = 0;
+
+        for demo in demos {
+            for entry in &demo.directory.entries {
+                let mut entry = entry.clone();
+
+                if rebase_time && !entry.is_loading() {
+                    let (end_time, end_frame) = entry
+                        .frames
+                        .last()
+                        .map(|frame| (frame.time, frame.frame))
+                        .unwrap_or_default();
+
+                    retime_entry(&mut entry, |time| time + time_offset);
+
+                    for frame in &mut entry.frames {
+                        frame.frame += frame_offset;
+                    }
+
+                    time_offset += end_time;
+                    frame_offset += end_frame + 1;
+                }
+
+                // like the engine, NextSection is at the time of the last frame
+                if let Some(last) = entry.frames.last()
+                    && !matches!(last.frame_data, FrameData::NextSection)
+                {
+                    entry.frames.push(Frame {
+                        time: last.time,
+                        frame: last.frame,
+                        frame_data: FrameData::NextSection,
+                    });
+                }
+
+                entry.frame_count = entry.frames.len() as i32;
+                entries.push(entry);
+            }
+        }
+
+        Ok(Demo {
+            header: first.header.clone(),
+            directory: Directory { entries },
+            _state: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{open_demo, open_demo_from_bytes, types::EngineMessage};
+
+    #[test]
+    fn concat_twice() {
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let playback_end = demo.directory.entries[1]
+            .frames
+            .iter()
+            .map(|frame| frame.time)
+            .fold(0., f32::max);
+
+        let merged = Demo::concat(&[demo.clone(), demo], true).unwrap();
+        let merged = open_demo_from_bytes(&merged.write_to_bytes()).unwrap();
+
+        assert_eq!(merged.directory.entries.len(), 4);
+
+        let second_playback = &merged.directory.entries[3];
+
+        assert!(second_playback.frames[0].time >= playback_end);
+        assert!(second_playback.track_time > merged.directory.entries[1].track_time);
+
+        // the clocks of the first network frame moved by as much as the frame time
+        let first_network = |entry: &DirectoryEntry| {
+            let network_message = entry
+                .frames
+                .iter()
+                .find_map(|frame| frame.frame_data.get_network_message())
+                .map(|box_type| &box_type.1)
+                .unwrap();
+
+            let svc_time = network_message
+                .messages
+                .get_parsed_messages()
+                .unwrap()
+                .iter()
+                .find_map(|message| match message.get_engine_message() {
+                    Some(EngineMessage::SvcTime(time)) => Some(time.time),
+                    _ => None,
+                })
+                .unwrap();
+
+            (network_message.info.refparams.time, svc_time)
+        };
+
+        let (first_time, first_svc_time) = first_network(&merged.directory.entries[1]);
+        let (second_time, second_svc_time) = first_network(second_playback);
+
+        assert!((second_time - first_time - playback_end).abs() < 0.001);
+        assert!((second_svc_time - first_svc_time - playback_end).abs() < 0.001);
+        assert!(matches!(
+            second_playback.frames.last().unwrap().frame_data,
+            FrameData::NextSection
+        ));
+    }
+}
@@ -0,0 +1,303 @@
+//! Trimming a demo to a time range while keeping it playable.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let demo = open_demo("./src/tests/demotest.dem").unwrap();
+//! let clip = demo.cut(30., 40.).unwrap();
+//!
+//! clip.write_to_file("./src/tests/clip.dem").unwrap();
+//! ```
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    error::DemoError,
+    snapshot::SnapshotTracker,
+    types::{Demo, DemoState, Directory, EngineMessage, Frame, FrameData, MessageData, NetMessage},
+};
+
+impl Demo {
+    /// Keeps the frames of the playback entry between `start` and `end` seconds of
+    /// [`Frame::time`] and the whole LOADING entry.
+    ///
+    /// The clip starts from the first frame in the range with packet entities. Its entities and
+    /// client data are written in full so nothing refers to the frames that are cut. Later deltas
+    /// referring to cut frames are written in full as well. Frame time, frame number and
+    /// [`crate::types::RefParams::time`] then start from 0.
+    ///
+    /// Reliable state from the cut part, such as user info, user message registrations and light
+    /// styles, is sent again in the first frame. HUD state from user messages is not.
+    ///
+    /// Only the first playback entry is kept.
+    pub fn cut(&self, start: f32, end: f32) -> Result<Demo, DemoError> {
+        let (loading, playback) = match self.directory.entries.as_slice() {
+            [loading, playback, ..] => (loading, playback),
+            _ => return Err(DemoError::MissingDirectoryEntry),
+        };
+
+        let mut tracker = SnapshotTracker::new();
+        // state as of the cut, for writing the full updates
+        let mut state = DemoState::new_raw();
+
+        for frame in &loading.frames {
+            if let FrameData::NetworkMessage(box_type) = &frame.frame_data {
+                tracker.apply_network_message(&box_type.1);
+
+                for message in box_type
+                    .1
+                    .messages
+                    .get_parsed_messages()
+                    .into_iter()
+                    .flatten()
+                {
+                    state.apply(message);
+                }
+            }
+        }
+
+        let mut carried = CarriedMessages::default();
+        let mut frames: Vec<Frame> = vec![];
+        let mut kept_sequences: HashSet<u8> = HashSet::new();
+        // frame time, frame number and refparams time of the first kept network frame
+        let mut offset: Option<(f32, i32, f32)> = None;
+
+        for frame in &playback.frames {
+            if frame.time > end {
+                break;
+            }
+
+            let FrameData::NetworkMessage(box_type) = &frame.frame_data else {
+                // demo start is needed to start playback
+                if offset.is_some() || matches!(frame.frame_data, FrameData::DemoStart) {
+                    frames.push(frame.clone());
+                }
+
+                continue;
+            };
+
+            let network_message = &box_type.1;
+            let messages = network_message
+                .messages
+                .get_parsed_messages()
+                .ok_or(DemoError::UnparsedMessages)?;
+
+            let is_anchor = offset.is_none()
+                && frame.time >= start
+                && messages.iter().any(|message| {
+                    matches!(
+                        message.get_engine_message(),
+                        Some(
+                            EngineMessage::SvcPacketEntities(_)
+                                | EngineMessage::SvcDeltaPacketEntities(_)
+                        )
+                    )
+                });
+
+            if offset.is_none() && !is_anchor {
+                for message in messages {
+                    state.apply(message);
+
+                    if let Some(message) = message.get_engine_message() {
+                        carried.push(message);
+                    }
+                }
+
+                tracker.apply_network_message(network_message);
+                continue;
+            }
+
+            if is_anchor {
+                offset = Some((frame.time, frame.frame, network_message.info.refparams.time));
+            }
+
+            let mut new_messages: Vec<NetMessage> = if is_anchor { carried.take() } else { vec![] };
+
+            for message in messages {
+                state.apply(message);
+
+                let Some(engine_message) = message.get_engine_message() else {
+                    new_messages.push(message.clone());
+                    continue;
+                };
+
+                tracker.apply(engine_message);
+
+                let needs_full = match engine_message {
+                    EngineMessage::SvcPacketEntities(_) => is_anchor,
+                    EngineMessage::SvcDeltaPacketEntities(packet) => {
+                        is_anchor || !kept_sequences.contains(&packet.delta_sequence)
+                    }
+                    EngineMessage::SvcClientData(client_data) => {
+                        is_anchor
+                            || client_data.has_delta_update_mask
+                                && client_data
+                                    .delta_update_mask
+                                    .is_some_and(|sequence| !kept_sequences.contains(&sequence))
+                    }
+                    _ => false,
+                };
+
+                if !needs_full {
+                    new_messages.push(message.clone());
+                    continue;
+                }
+
+                let full = match engine_message {
+                    EngineMessage::SvcClientData(_) => {
+                        EngineMessage::SvcClientData(tracker.current().to_client_data(&state))
+                    }
+                    _ => EngineMessage::SvcPacketEntities(
+                        tracker.current().to_packet_entities(&state),
+                    ),
+                };
+
+                new_messages.push(NetMessage::EngineMessage(Box::new(full)));
+            }
+
+            tracker.end_frame(network_message.sequence_info.incoming_sequence);
+            kept_sequences.insert((network_message.sequence_info.incoming_sequence & 0xFF) as u8);
+
+            let mut new_box = box_type.clone();
+            new_box.1.messages = MessageData::Parsed(new_messages);
+
+            frames.push(Frame {
+                time: frame.time,
+                frame: frame.frame,
+                frame_data: FrameData::NetworkMessage(new_box),
+            });
+        }
+
+        let Some((time_offset, frame_offset, refparams_offset)) = offset else {
+            return Err(DemoError::NoSnapshotInRange);
+        };
+
+        for frame in &mut frames {
+            frame.time = (frame.time - time_offset).max(0.);
+            frame.frame = (frame.frame - frame_offset).max(0);
+
+            if let FrameData::NetworkMessage(box_type) = &mut frame.frame_data {
+                let refparams = &mut box_type.1.info.refparams;
+                refparams.time = (refparams.time - refparams_offset).max(0.);
+            }
+        }
+
+        let mut playback = playback.clone();
+        playback.track_time = frames.last().map(|frame| frame.time).unwrap_or_default();
+        playback.frame_count = frames.len() as i32;
+        playback.frames = frames;
+
+        Ok(Demo {
+            header: self.header.clone(),
+            directory: Directory {
+                entries: vec![loading.clone(), playback],
+            },
+            _state: None,
+        })
+    }
+}
+
+/// Reliable messages before the cut that the client needs to keep.
+#[derive(Default)]
+pub(crate) struct CarriedMessages {
+    messages: Vec<EngineMessage>,
+    /// Message type and key to its position in `messages`, so only the latest is sent.
+    latest: HashMap<(u8, u16), usize>,
+}
+
+impl CarriedMessages {
+    pub(crate) fn push(&mut self, message: &EngineMessage) {
+        let key = match message {
+            EngineMessage::SvcSetView(_) => Some((5, 0)),
+            EngineMessage::SvcLightStyle(style) => Some((12, style.index as u16)),
+            EngineMessage::SvcUpdateUserInfo(info) => Some((13, info.index as u16)),
+            EngineMessage::SvcCdTrack(_) => Some((32, 0)),
+            EngineMessage::SvcDecalName(decal) => Some((36, decal.position_index as u16)),
+            EngineMessage::SvcRoomType(_) => Some((37, 0)),
+            EngineMessage::SvcNewUserMsg(user_msg) => Some((39, user_msg.index as u16)),
+            EngineMessage::SvcNewMovevars(_) => Some((44, 0)),
+            EngineMessage::SvcHltv(_) => Some((50, 0)),
+            EngineMessage::SvcVoiceInit(_) => Some((52, 0)),
+            EngineMessage::SvcSendExtraInfo(_) => Some((54, 0)),
+            EngineMessage::SvcTimeScale(_) => Some((55, 0)),
+            EngineMessage::SvcResourceLocation(_) => Some((56, 0)),
+            EngineMessage::SvcDeltaDescription(_)
+            | EngineMessage::SvcSpawnStatic(_)
+            | EngineMessage::SvcSpawnStaticSound(_) => None,
+            _ => return,
+        };
+
+        match key.and_then(|key| self.latest.get(&key).copied()) {
+            Some(index) => self.messages[index] = message.clone(),
+            None => {
+                if let Some(key) = key {
+                    self.latest.insert(key, self.messages.len());
+                }
+
+                self.messages.push(message.clone());
+            }
+        }
+    }
+
+    pub(crate) fn take(&mut self) -> Vec<NetMessage> {
+        self.latest.clear();
+
+        std::mem::take(&mut self.messages)
+            .into_iter()
+            .map(|message| NetMessage::EngineMessage(Box::new(message)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::open_demo_from_bytes;
+
+    #[test]
+    fn cut_playable() {
+        let demo = crate::open_demo("./src/tests/weapon.dem").unwrap();
+        let clip = demo.cut(5., 10.).unwrap();
+
+        // round trip through the writer to make sure every message still encodes
+        let clip = open_demo_from_bytes(&clip.write_to_bytes()).unwrap();
+        let playback = &clip.directory.entries[1];
+
+        assert!(playback.frames.iter().all(|frame| frame.time <= 5.01));
+        assert!(matches!(
+            playback.frames[0].frame_data,
+            FrameData::DemoStart
+        ));
+
+        let first = playback
+            .frames
+            .iter()
+            .find_map(|frame| frame.frame_data.get_network_message())
+            .unwrap();
+
+        let full = first
+            .1
+            .messages
+            .get_parsed_messages()
+            .unwrap()
+            .iter()
+            .find_map(|message| match message.get_engine_message() {
+                Some(EngineMessage::SvcPacketEntities(packet)) => Some(packet),
+                _ => None,
+            })
+            .unwrap();
+
+        assert_eq!(full.entity_count as usize, full.entity_states.len());
+        assert!(!full.entity_states.is_empty());
+    }
+
+    #[test]
+    fn missing_playback() {
+        let mut demo = crate::open_demo("./src/tests/weapon.dem").unwrap();
+        demo.directory.entries.truncate(1);
+
+        assert!(matches!(
+            demo.cut(0., 5.),
+            Err(DemoError::MissingDirectoryEntry)
+        ));
+    }
+}
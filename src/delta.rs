@@ -1,6 +1,6 @@
 use crate::{
     bit::{BitReader, BitSliceCast, BitWriter},
-    types::{Delta, DeltaDecoder, DeltaDecoderS, DeltaType, DeltaValue},
+    types::{Delta, DeltaDecoder, DeltaDecoderS, DeltaType, DeltaValue, DemoState},
 };
 
 pub fn parse_delta(dd: &DeltaDecoder, br: &mut BitReader) -> Delta {
@@ -48,6 +48,30 @@ fn parse_delta_field(description: &DeltaDecoderS, br: &mut BitReader) -> DeltaVa
     let is_angle = flag!(lhs, DeltaType::Angle);
     let is_string = flag!(lhs, DeltaType::String);
 
+    let max_bits = if is_byte {
+        8
+    } else if is_short {
+        16
+    } else {
+        32
+    };
+
+    // the sign takes a bit of its own
+    let min_bits = if is_signed && !is_angle { 2 } else { 1 };
+
+    // a garbage description would read more bits than the value holds or divide by 0
+    let is_valid = if is_byte || is_short || is_integer || is_some_float || is_angle {
+        (min_bits..=max_bits).contains(&description.bits)
+            && (is_some_float || is_angle || description.divisor >= 1.)
+    } else {
+        is_string
+    };
+
+    if !is_valid {
+        br.mark_bad_read();
+        return DeltaValue::IntUnsigned(0);
+    }
+
     if is_byte {
         if is_signed {
             let sign = if br.read_1_bit() { -1 } else { 1 };
@@ -100,17 +124,80 @@ fn parse_delta_field(description: &DeltaDecoderS, br: &mut BitReader) -> DeltaVa
         }
     } else if is_angle {
         let value = (br.read_n_bit(description.bits as usize)).to_u32();
-        let multiplier = 360f32 / ((1 << description.bits) as f32);
+        let multiplier = 360f32 / ((1u64 << description.bits) as f32);
         let res_value = value as f32 * multiplier;
 
         DeltaValue::Angle(res_value)
-    } else if is_string {
+    } else {
         DeltaValue::String(br.read_string().get_string())
+    }
+}
+
+/// Value of a field with the type the decoder expects, for building a [`Delta`] by hand.
+pub fn delta_value(description: &DeltaDecoderS, value: f32) -> DeltaValue {
+    let lhs = description.flags;
+    let is_signed = flag!(lhs, DeltaType::Signed);
+
+    if flag!(lhs, DeltaType::Byte) {
+        if is_signed {
+            DeltaValue::ByteSigned(value as i8)
+        } else {
+            DeltaValue::ByteUnsigned(value as u8)
+        }
+    } else if flag!(lhs, DeltaType::Short) {
+        if is_signed {
+            DeltaValue::ShortSigned(value as i16)
+        } else {
+            DeltaValue::ShortUnsigned(value as u16)
+        }
+    } else if flag!(lhs, DeltaType::Integer) {
+        if is_signed {
+            DeltaValue::IntSigned(value as i32)
+        } else {
+            DeltaValue::IntUnsigned(value as u32)
+        }
+    } else if flag!(lhs, DeltaType::Angle) {
+        DeltaValue::Angle(value.rem_euclid(360.))
+    } else if is_signed {
+        DeltaValue::FloatSigned(value)
     } else {
-        unreachable!("Encoded value does not match any types. Should this happens?");
+        DeltaValue::FloatUnsigned(value)
+    }
+}
+
+/// Numeric value of a field whatever its type, [`None`] for strings.
+pub fn delta_number(value: &DeltaValue) -> Option<f32> {
+    match value {
+        DeltaValue::ByteSigned(x) => Some(*x as f32),
+        DeltaValue::ByteUnsigned(x) => Some(*x as f32),
+        DeltaValue::ShortSigned(x) => Some(*x as f32),
+        DeltaValue::ShortUnsigned(x) => Some(*x as f32),
+        DeltaValue::IntSigned(x) => Some(*x as f32),
+        DeltaValue::IntUnsigned(x) => Some(*x as f32),
+        DeltaValue::FloatSigned(x) | DeltaValue::FloatUnsigned(x) | DeltaValue::Angle(x) => {
+            Some(*x)
+        }
+        DeltaValue::String(_) => None,
     }
 }
 
+/// Decoder of an entity in packet entities, which depends on whether it is a player.
+pub fn entity_decoder(
+    state: &DemoState,
+    entity_index: u16,
+    has_custom_delta: bool,
+) -> Option<&DeltaDecoder> {
+    let name = if entity_index > 0 && entity_index <= state.max_client as u16 {
+        "entity_state_player_t\0"
+    } else if has_custom_delta {
+        "custom_entity_state_t\0"
+    } else {
+        "entity_state_t\0"
+    };
+
+    state.delta_decoders.get(name)
+}
+
 pub fn write_delta(delta: &Delta, delta_decoder: &DeltaDecoder, bw: &mut BitWriter) {
     // Consider this like a modulo.
     // Delta with description of index 13 is byte_mask[13 / 8] at 13 % 8.
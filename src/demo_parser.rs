@@ -28,7 +28,9 @@ pub fn parse_demo(i: &[u8], netmsg_parse_mode: MessageDataParseMode) -> NomResul
 
         parse_fallback_directory(frames_start, file_start, netmsg_parse_mode, &mut demo_state)
     } else {
-        let directory_start = &file_start[header.directory_offset as usize..];
+        let Some(directory_start) = file_start.get(header.directory_offset as usize..) else {
+            return nom_fail("directory offset past end of file");
+        };
 
         parse_directory(
             directory_start,
@@ -204,7 +206,9 @@ pub fn parse_directory_entry<'a>(
     // frame_count is unreliable
     // parse until NextSection and stop for current entry
     let mut frames: Vec<Frame> = vec![];
-    let mut frames_start = &file_start[frame_offset as usize..];
+    let Some(mut frames_start) = file_start.get(frame_offset as usize..) else {
+        return nom_fail("frame offset past end of file");
+    };
 
     loop {
         let (end_current_frame, frame) = parse_frame(frames_start, netmsg_parse_mode, aux)?;
@@ -402,6 +406,13 @@ pub fn parse_network_messages<'a>(
         return nom_fail(format!("message length too long: {}", message_length));
     }
 
+    if message_length as usize > i.len() {
+        return nom_fail(format!(
+            "message length past end of file: {}",
+            message_length
+        ));
+    }
+
     // let (i, netmessage_data_chunk) = take(message_length)(i)?;
     let netmessage_data_chunk = &i[..message_length as usize];
     let the_rest = &i[message_length as usize..];
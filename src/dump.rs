@@ -0,0 +1,408 @@
+//! Readable listing of every frame and message, for debugging.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let demo = open_demo("./src/tests/demotest.dem").unwrap();
+//!
+//! demo.dump(
+//!     std::io::stdout().lock(),
+//!     &DumpOptions {
+//!         demo_info: false,
+//!         messages: vec!["SvcSound".to_owned(), "SayText".to_owned()],
+//!         ..Default::default()
+//!     },
+//! )
+//! .unwrap();
+//! ```
+//!
+//! Gives lines such as
+//!
+//! ```text
+//! [1] frame 52 time 0.512 NetworkMessage Normal
+//!   SvcSound { flags: 0, volume: None, ... }
+//!   UserMessage SayText (id 76) [2, 35, 67, ...]
+//! ```
+use std::io::Write;
+
+use crate::{
+    delta::entity_decoder,
+    error::DemoError,
+    types::{
+        Delta, DeltaDecoder, DeltaValue, Demo, DemoState, EngineMessage, FrameData, MessageData,
+        NetMessage,
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct DumpOptions {
+    /// Prints [`crate::types::DemoInfo`] and [`crate::types::SequenceInfo`] of network frames,
+    /// which has the refparams, usercmd and movevars.
+    pub demo_info: bool,
+    /// Prints packet entities and baselines as one line with the entity count instead of one line
+    /// for each entity.
+    pub collapse_entities: bool,
+    /// Only prints these messages, by [`EngineMessage::name`] such as `SvcSound` or by user
+    /// message name such as `SayText`. Frames without any of them are left out.
+    ///
+    /// Every message is printed when empty.
+    pub messages: Vec<String>,
+}
+
+impl Default for DumpOptions {
+    fn default() -> Self {
+        Self {
+            demo_info: true,
+            collapse_entities: false,
+            messages: vec![],
+        }
+    }
+}
+
+impl Demo {
+    /// Writes every frame with its entry index, frame number, time and type, then the messages
+    /// of network frames.
+    ///
+    /// User messages are named from their registration and delta fields are in the order of
+    /// their decoder.
+    pub fn dump(&self, mut writer: impl Write, options: &DumpOptions) -> Result<(), DemoError> {
+        let mut state = DemoState::new_raw();
+
+        for (entry_index, entry) in self.directory.entries.iter().enumerate() {
+            for frame in &entry.frames {
+                let mut lines = vec![];
+                let mut has_match = options.messages.is_empty();
+
+                let frame_type = match &frame.frame_data {
+                    FrameData::NetworkMessage(box_type) => {
+                        let network_message = &box_type.1;
+
+                        if options.demo_info {
+                            let info = &network_message.info;
+
+                            lines.push(format!("  timestamp {}", info.timestamp));
+                            lines.push(format!("  {:?}", info.refparams));
+                            lines.push(format!("  {:?}", info.usercmd));
+                            lines.push(format!("  {:?}", info.movevars));
+                            lines.push(format!(
+                                "  view {:?} viewmodel {}",
+                                info.view, info.viewmodel
+                            ));
+                            lines.push(format!("  {:?}", network_message.sequence_info));
+                        }
+
+                        match &network_message.messages {
+                            MessageData::Parsed(messages) => {
+                                for message in messages {
+                                    let name = match message {
+                                        NetMessage::UserMessage(user_message) => state
+                                            .custom_messages
+                                            .get(&user_message.id)
+                                            .and_then(|registered| registered.name.to_str().ok())
+                                            .unwrap_or("<unregistered>")
+                                            .to_owned(),
+                                        NetMessage::EngineMessage(engine_message) => {
+                                            engine_message.name().to_owned()
+                                        }
+                                    };
+
+                                    if options.messages.is_empty()
+                                        || options.messages.contains(&name)
+                                    {
+                                        has_match = true;
+                                        write_message(&mut lines, message, &name, &state, options);
+                                    }
+
+                                    state.apply(message);
+                                }
+                            }
+                            MessageData::Raw(bytes) => {
+                                lines.push(format!("  {} bytes not parsed", bytes.len()))
+                            }
+                            MessageData::None => (),
+                        }
+
+                        format!("NetworkMessage {:?}", box_type.0)
+                    }
+                    FrameData::DemoStart => "DemoStart".to_owned(),
+                    FrameData::NextSection => "NextSection".to_owned(),
+                    FrameData::ConsoleCommand(command) => format!("{command:?}"),
+                    FrameData::ClientData(client_data) => format!("{client_data:?}"),
+                    FrameData::Event(event) => format!("{event:?}"),
+                    FrameData::WeaponAnimation(animation) => format!("{animation:?}"),
+                    FrameData::Sound(sound) => format!("{sound:?}"),
+                    FrameData::DemoBuffer(buffer) => {
+                        format!("DemoBuffer {} bytes", buffer.buffer.len())
+                    }
+                };
+
+                if !has_match {
+                    continue;
+                }
+
+                writeln!(
+                    writer,
+                    "[{entry_index}] frame {} time {} {frame_type}",
+                    frame.frame, frame.time
+                )?;
+
+                for line in lines {
+                    writeln!(writer, "{line}")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn write_message(
+    lines: &mut Vec<String>,
+    message: &NetMessage,
+    name: &str,
+    state: &DemoState,
+    options: &DumpOptions,
+) {
+    let engine_message = match message {
+        NetMessage::UserMessage(user_message) => {
+            lines.push(format!(
+                "  UserMessage {name} (id {}) {:?}",
+                user_message.id, user_message.data
+            ));
+            return;
+        }
+        NetMessage::EngineMessage(engine_message) => engine_message.as_ref(),
+    };
+
+    let decoder = |name: &str| state.delta_decoders.get(name);
+
+    match engine_message {
+        EngineMessage::SvcPacketEntities(packet) => {
+            lines.push(format!("  {name} {} entities", packet.entity_states.len()));
+
+            if !options.collapse_entities {
+                for entity in &packet.entity_states {
+                    let decoder =
+                        entity_decoder(state, entity.entity_index, entity.has_custom_delta);
+
+                    lines.push(format!(
+                        "    entity {} {}",
+                        entity.entity_index,
+                        format_delta(&entity.delta, decoder)
+                    ));
+                }
+            }
+        }
+        EngineMessage::SvcDeltaPacketEntities(packet) => {
+            lines.push(format!(
+                "  {name} {} entities from sequence {}",
+                packet.entity_states.len(),
+                packet.delta_sequence
+            ));
+
+            if !options.collapse_entities {
+                for entity in &packet.entity_states {
+                    let delta = match &entity.delta {
+                        Some(delta) if !entity.remove_entity => {
+                            let decoder = entity_decoder(
+                                state,
+                                entity.entity_index,
+                                entity.has_custom_delta.unwrap_or_default(),
+                            );
+
+                            format_delta(delta, decoder)
+                        }
+                        _ => "removed".to_owned(),
+                    };
+
+                    lines.push(format!("    entity {} {delta}", entity.entity_index));
+                }
+            }
+        }
+        EngineMessage::SvcSpawnBaseline(baseline) => {
+            lines.push(format!(
+                "  {name} {} entities {} instanced",
+                baseline.entities.len(),
+                baseline.extra_data.len()
+            ));
+
+            if !options.collapse_entities {
+                for entity in &baseline.entities {
+                    let decoder = entity_decoder(state, entity.index, entity.type_ & 1 == 0);
+
+                    lines.push(format!(
+                        "    entity {} {}",
+                        entity.index,
+                        format_delta(&entity.delta, decoder)
+                    ));
+                }
+
+                for (index, delta) in baseline.extra_data.iter().enumerate() {
+                    lines.push(format!(
+                        "    instanced {index} {}",
+                        format_delta(delta, decoder("entity_state_t\0"))
+                    ));
+                }
+            }
+        }
+        EngineMessage::SvcClientData(client_data) => {
+            lines.push(format!(
+                "  {name} from sequence {:?} {}",
+                client_data.delta_update_mask,
+                format_delta(&client_data.client_data, decoder("clientdata_t\0"))
+            ));
+
+            for weapon in client_data.weapon_data.iter().flatten() {
+                lines.push(format!(
+                    "    weapon {} {}",
+                    weapon.weapon_index,
+                    format_delta(&weapon.weapon_data, decoder("weapon_data_t\0"))
+                ));
+            }
+        }
+        EngineMessage::SvcEvent(event) => {
+            lines.push(format!("  {name} {} events", event.events.len()));
+
+            for event in &event.events {
+                let delta = event
+                    .delta
+                    .as_ref()
+                    .map(|delta| format_delta(delta, decoder("event_t\0")))
+                    .unwrap_or_default();
+
+                lines.push(format!(
+                    "    event {} packet {:?} fire time {:?} {delta}",
+                    event.event_index, event.packet_index, event.fire_time
+                ));
+            }
+        }
+        EngineMessage::SvcEventReliable(event) => {
+            lines.push(format!(
+                "  {name} event {} fire time {:?} {}",
+                event.event_index,
+                event.fire_time,
+                format_delta(&event.event_args, decoder("event_t\0"))
+            ));
+        }
+        _ => {
+            // derived Debug is `Name(Name { .. })`, so the inner part already has the name
+            let debug = format!("{engine_message:?}");
+            let fields = debug
+                .strip_prefix(name)
+                .and_then(|rest| rest.strip_prefix('('))
+                .and_then(|rest| rest.strip_suffix(')'))
+                .unwrap_or(&debug);
+
+            lines.push(format!("  {fields}"));
+        }
+    }
+}
+
+/// `{ name: value, .. }` in the order of the decoder, then any field it does not have.
+fn format_delta(delta: &Delta, decoder: Option<&DeltaDecoder>) -> String {
+    let mut fields: Vec<(&str, &DeltaValue)> = decoder
+        .into_iter()
+        .flatten()
+        .filter_map(|description| {
+            let value = delta.get(&description.name)?;
+            Some((description.name.as_str(), value))
+        })
+        .collect();
+
+    let mut unknown: Vec<(&str, &DeltaValue)> = delta
+        .iter()
+        .filter(|(key, _)| !fields.iter().any(|(name, _)| name == key))
+        .map(|(key, value)| (key.as_str(), value))
+        .collect();
+
+    unknown.sort_by_key(|(key, _)| *key);
+    fields.extend(unknown);
+
+    let fields: Vec<String> = fields
+        .into_iter()
+        .map(|(name, value)| {
+            let name = name.trim_end_matches('\0');
+
+            match value {
+                DeltaValue::ByteSigned(x) => format!("{name}: {x}"),
+                DeltaValue::ByteUnsigned(x) => format!("{name}: {x}"),
+                DeltaValue::ShortSigned(x) => format!("{name}: {x}"),
+                DeltaValue::ShortUnsigned(x) => format!("{name}: {x}"),
+                DeltaValue::IntSigned(x) => format!("{name}: {x}"),
+                DeltaValue::IntUnsigned(x) => format!("{name}: {x}"),
+                DeltaValue::FloatSigned(x)
+                | DeltaValue::FloatUnsigned(x)
+                | DeltaValue::Angle(x) => format!("{name}: {x}"),
+                DeltaValue::String(x) => format!("{name}: {:?}", x.trim_end_matches('\0')),
+            }
+        })
+        .collect();
+
+    if fields.is_empty() {
+        "{}".to_owned()
+    } else {
+        format!("{{ {} }}", fields.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::open_demo;
+
+    fn dump(demo: &Demo, options: &DumpOptions) -> String {
+        let mut bytes = vec![];
+        demo.dump(&mut bytes, options).unwrap();
+
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn dump_all() {
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let text = dump(&demo, &DumpOptions::default());
+
+        assert!(
+            text.starts_with("[0] frame ")
+                && text
+                    .lines()
+                    .next()
+                    .unwrap()
+                    .ends_with("NetworkMessage Start")
+        );
+        assert!(text.contains("  SvcServerInfo {"));
+        assert!(text.contains("  UserMessage ResetHUD (id "));
+        assert!(text.contains("RefParams {"));
+
+        // decoder order, origin comes before modelindex
+        let entity = text
+            .lines()
+            .find(|line| line.starts_with("    entity 8 ") && line.contains("origin[0]"))
+            .unwrap();
+
+        assert!(entity.find("origin[0]").unwrap() < entity.find("origin[2]").unwrap());
+        assert!(entity.find("origin[2]").unwrap() < entity.find("modelindex").unwrap());
+    }
+
+    #[test]
+    fn dump_filtered() {
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let text = dump(
+            &demo,
+            &DumpOptions {
+                demo_info: false,
+                collapse_entities: true,
+                messages: vec!["SvcDeltaPacketEntities".to_owned(), "ResetHUD".to_owned()],
+            },
+        );
+
+        assert!(!text.contains("RefParams"));
+        assert!(!text.contains("    entity"));
+        assert!(text.lines().all(|line| {
+            line.starts_with('[')
+                || line.starts_with("  SvcDeltaPacketEntities")
+                || line.starts_with("  UserMessage ResetHUD")
+        }));
+        assert!(text.contains("UserMessage ResetHUD"));
+    }
+}
@@ -7,4 +7,48 @@ pub enum DemoError {
         #[from]
         source: std::io::Error,
     },
+    #[error("Network messages are not parsed")]
+    UnparsedMessages,
+    #[error("No frame with packet entities in the range")]
+    NoSnapshotInRange,
+    #[error("Demo has no LOADING or playback directory entry")]
+    MissingDirectoryEntry,
+    #[error("Demos have different protocols or game directories")]
+    IncompatibleDemos,
+    #[error("Demo was not recorded by HLTV")]
+    NotHltv,
+    #[error("Player is not in the demo")]
+    PlayerNotFound,
+    #[error("Not a GoldSrc BSP file")]
+    InvalidBsp,
+    #[error("Not a demo archive")]
+    InvalidArchive,
+    #[error("Not a ghost file")]
+    InvalidGhost,
+    #[error("Speed must be positive")]
+    InvalidSpeed,
+    #[error("Invalid hltas script at line {line}")]
+    InvalidHltas { line: usize },
+    #[error("Invalid protobuf demo")]
+    InvalidProto,
+    #[error("Unsupported schema version {version}")]
+    UnsupportedSchema { version: u32 },
+    #[cfg(feature = "parquet")]
+    #[error("ParquetError: {source}")]
+    ParquetError {
+        #[from]
+        source: parquet::errors::ParquetError,
+    },
+    #[cfg(feature = "proto")]
+    #[error("ProtoError: {source}")]
+    ProtoError {
+        #[from]
+        source: prost::DecodeError,
+    },
+    #[cfg(feature = "json")]
+    #[error("JSONError: {source}")]
+    JSONError {
+        #[from]
+        source: serde_json::Error,
+    },
 }
@@ -0,0 +1,270 @@
+//! One row per network frame of the view and input of the recording player, for spreadsheets
+//! and dataframes.
+//!
+//! Everything comes from [`crate::types::DemoInfo`], so the messages do not have to be parsed.
+//! Vectors are split into `_x`, `_y` and `_z` columns, which are pitch, yaw and roll for angles.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let demo = Demo::parse_from_file("./src/tests/demotest.dem", MessageDataParseMode::None).unwrap();
+//! let file = std::fs::File::create("./src/tests/demotest.csv").unwrap();
+//!
+//! demo.write_frames_csv(file).unwrap();
+//!
+//! // with the parquet feature
+//! let file = std::fs::File::create("./src/tests/demotest.parquet").unwrap();
+//! demo.write_frames_parquet(file).unwrap();
+//! ```
+use std::io::Write;
+
+use crate::{
+    error::DemoError,
+    types::{Demo, FrameData},
+};
+
+/// A network frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameRow {
+    pub entry_index: usize,
+    pub frame: i32,
+    pub time: f32,
+    pub view_origin: [f32; 3],
+    pub view_angles: [f32; 3],
+    /// Velocity from prediction.
+    pub sim_vel: [f32; 3],
+    pub on_ground: i32,
+    pub water_level: i32,
+    pub health: i32,
+    pub buttons: u16,
+    pub forward_move: f32,
+    pub side_move: f32,
+    pub up_move: f32,
+    pub msec: u8,
+    pub gravity: f32,
+    pub stopspeed: f32,
+    pub maxspeed: f32,
+    pub accelerate: f32,
+    pub airaccelerate: f32,
+    pub friction: f32,
+    pub edgefriction: f32,
+}
+
+#[derive(Clone, Copy)]
+enum Column {
+    Float(fn(&FrameRow) -> f32),
+    Int(fn(&FrameRow) -> i32),
+}
+
+/// Every column in order with how to read it from a row.
+const COLUMNS: &[(&str, Column)] = &[
+    ("entry_index", Column::Int(|row| row.entry_index as i32)),
+    ("frame", Column::Int(|row| row.frame)),
+    ("time", Column::Float(|row| row.time)),
+    ("view_origin_x", Column::Float(|row| row.view_origin[0])),
+    ("view_origin_y", Column::Float(|row| row.view_origin[1])),
+    ("view_origin_z", Column::Float(|row| row.view_origin[2])),
+    ("view_angles_x", Column::Float(|row| row.view_angles[0])),
+    ("view_angles_y", Column::Float(|row| row.view_angles[1])),
+    ("view_angles_z", Column::Float(|row| row.view_angles[2])),
+    ("sim_vel_x", Column::Float(|row| row.sim_vel[0])),
+    ("sim_vel_y", Column::Float(|row| row.sim_vel[1])),
+    ("sim_vel_z", Column::Float(|row| row.sim_vel[2])),
+    ("on_ground", Column::Int(|row| row.on_ground)),
+    ("water_level", Column::Int(|row| row.water_level)),
+    ("health", Column::Int(|row| row.health)),
+    ("buttons", Column::Int(|row| row.buttons as i32)),
+    ("forward_move", Column::Float(|row| row.forward_move)),
+    ("side_move", Column::Float(|row| row.side_move)),
+    ("up_move", Column::Float(|row| row.up_move)),
+    ("msec", Column::Int(|row| row.msec as i32)),
+    ("gravity", Column::Float(|row| row.gravity)),
+    ("stopspeed", Column::Float(|row| row.stopspeed)),
+    ("maxspeed", Column::Float(|row| row.maxspeed)),
+    ("accelerate", Column::Float(|row| row.accelerate)),
+    ("airaccelerate", Column::Float(|row| row.airaccelerate)),
+    ("friction", Column::Float(|row| row.friction)),
+    ("edgefriction", Column::Float(|row| row.edgefriction)),
+];
+
+fn vec3(point: &[f32]) -> [f32; 3] {
+    [0, 1, 2].map(|index| point.get(index).copied().unwrap_or_default())
+}
+
+impl Demo {
+    /// A row for every network frame of every entry, in order.
+    pub fn frame_rows(&self) -> Vec<FrameRow> {
+        self.directory
+            .entries
+            .iter()
+            .enumerate()
+            .flat_map(|(entry_index, entry)| {
+                entry.frames.iter().filter_map(move |frame| {
+                    let FrameData::NetworkMessage(box_type) = &frame.frame_data else {
+                        return None;
+                    };
+
+                    let info = &box_type.1.info;
+                    let refparams = &info.refparams;
+                    let usercmd = &info.usercmd;
+                    let movevars = &info.movevars;
+
+                    Some(FrameRow {
+                        entry_index,
+                        frame: frame.frame,
+                        time: frame.time,
+                        view_origin: vec3(&refparams.view_origin),
+                        view_angles: vec3(&refparams.view_angles),
+                        sim_vel: vec3(&refparams.sim_vel),
+                        on_ground: refparams.on_ground,
+                        water_level: refparams.water_level,
+                        health: refparams.health,
+                        buttons: usercmd.buttons,
+                        forward_move: usercmd.forward_move,
+                        side_move: usercmd.side_move,
+                        up_move: usercmd.up_move,
+                        msec: usercmd.msec,
+                        gravity: movevars.gravity,
+                        stopspeed: movevars.stopspeed,
+                        maxspeed: movevars.maxspeed,
+                        accelerate: movevars.accelerate,
+                        airaccelerate: movevars.airaccelerate,
+                        friction: movevars.friction,
+                        edgefriction: movevars.edgefriction,
+                    })
+                })
+            })
+            .collect()
+    }
+
+    /// Writes [`Demo::frame_rows`] as CSV with a header line.
+    pub fn write_frames_csv(&self, mut writer: impl Write) -> Result<(), DemoError> {
+        let header: Vec<&str> = COLUMNS.iter().map(|(name, _)| *name).collect();
+        writeln!(writer, "{}", header.join(","))?;
+
+        for row in self.frame_rows() {
+            let values: Vec<String> = COLUMNS
+                .iter()
+                .map(|(_, column)| match column {
+                    Column::Float(f) => f(&row).to_string(),
+                    Column::Int(f) => f(&row).to_string(),
+                })
+                .collect();
+
+            writeln!(writer, "{}", values.join(","))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "parquet")]
+mod arrow {
+    use std::{io::Write, sync::Arc};
+
+    use arrow_array::{ArrayRef, Float32Array, Int32Array, RecordBatch};
+    use arrow_schema::{DataType, Field, Schema};
+    use parquet::{arrow::ArrowWriter, errors::ParquetError};
+
+    use super::{COLUMNS, Column};
+    use crate::{error::DemoError, types::Demo};
+
+    impl Demo {
+        /// [`Demo::frame_rows`] as an Arrow record batch with a column for each CSV column.
+        pub fn frames_record_batch(&self) -> Result<RecordBatch, DemoError> {
+            let rows = self.frame_rows();
+
+            let fields: Vec<Field> = COLUMNS
+                .iter()
+                .map(|(name, column)| {
+                    let data_type = match column {
+                        Column::Float(_) => DataType::Float32,
+                        Column::Int(_) => DataType::Int32,
+                    };
+
+                    Field::new(*name, data_type, false)
+                })
+                .collect();
+
+            let arrays: Vec<ArrayRef> = COLUMNS
+                .iter()
+                .map(|(_, column)| -> ArrayRef {
+                    match column {
+                        Column::Float(f) => Arc::new(rows.iter().map(f).collect::<Float32Array>()),
+                        Column::Int(f) => Arc::new(rows.iter().map(f).collect::<Int32Array>()),
+                    }
+                })
+                .collect();
+
+            RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)
+                .map_err(|error| ParquetError::from(error).into())
+        }
+
+        /// Writes [`Demo::frames_record_batch`] as a Parquet file.
+        pub fn write_frames_parquet(&self, writer: impl Write + Send) -> Result<(), DemoError> {
+            let batch = self.frames_record_batch()?;
+            let mut writer = ArrowWriter::try_new(writer, batch.schema(), None)?;
+
+            writer.write(&batch)?;
+            writer.close()?;
+
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::open_demo;
+
+    #[test]
+    fn csv() {
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let rows = demo.frame_rows();
+
+        let mut bytes = vec![];
+        demo.write_frames_csv(&mut bytes).unwrap();
+
+        let csv = String::from_utf8(bytes).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), rows.len() + 1);
+        assert!(lines[0].starts_with("entry_index,frame,time,view_origin_x"));
+        assert!(
+            lines
+                .iter()
+                .all(|line| line.split(',').count() == COLUMNS.len())
+        );
+
+        // the playback has the player moving around
+        assert!(
+            rows.iter()
+                .any(|row| row.entry_index == 1 && row.health > 0)
+        );
+        assert!(rows.iter().any(|row| row.view_origin != [0.; 3]));
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn parquet() {
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let path = std::env::temp_dir().join("dem_export_test.parquet");
+
+        demo.write_frames_parquet(std::fs::File::create(&path).unwrap())
+            .unwrap();
+
+        let reader =
+            ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap()).unwrap();
+
+        assert_eq!(
+            reader.metadata().file_metadata().num_rows() as usize,
+            demo.frame_rows().len()
+        );
+        assert_eq!(reader.schema().fields().len(), COLUMNS.len());
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,425 @@
+//! Ghost files, the compact recordings of a run that replay plugins draw next to live players.
+//!
+//! A ghost is the origin, view angles and buttons of the recording player over time. The file
+//! is little-endian:
+//!
+//! ```text
+//! magic           b"HLGHOST\0"
+//! version         u32, currently 1
+//! map name        u32 length then UTF-8 bytes
+//! frame count     u32
+//! frames          time f32, origin [f32; 3], view angles [f32; 3], buttons u16
+//! ```
+//!
+//! Time starts at 0 with the first frame.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let demo = open_demo("./src/tests/demotest.dem").unwrap();
+//!
+//! let ghost = demo.ghost(&GhostOptions { interval: 0.01 });
+//! ghost.write_to_file("./src/tests/demotest.ghost").unwrap();
+//!
+//! // any demo on the same map provides what the client needs to load it
+//! let ghost = Ghost::parse_from_file("./src/tests/demotest.ghost").unwrap();
+//! let demo = ghost.to_demo(&demo, 28.).unwrap();
+//! demo.write_to_file("./src/tests/ghost.dem").unwrap();
+//! ```
+use std::{
+    ffi::OsStr,
+    fs::OpenOptions,
+    io::{Read, Write},
+    path::Path,
+};
+
+use crate::{
+    error::DemoError,
+    pov::PlayerView,
+    types::{
+        Buttons, ClientData, Demo, DemoState, EngineMessage, Frame, FrameData, MessageData,
+        NetMessage, SvcClientData, SvcDeltaPacketEntities, SvcTime,
+    },
+};
+
+const GHOST_MAGIC: &[u8; 8] = b"HLGHOST\0";
+const GHOST_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Default)]
+pub struct GhostOptions {
+    /// Seconds between kept frames. 0 keeps every frame.
+    pub interval: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GhostFrame {
+    pub time: f32,
+    pub origin: [f32; 3],
+    pub view_angles: [f32; 3],
+    pub buttons: Buttons,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ghost {
+    pub map_name: String,
+    pub frames: Vec<GhostFrame>,
+}
+
+impl Demo {
+    /// [`crate::types::RefParams::sim_org`], [`crate::types::RefParams::view_angles`] and
+    /// [`crate::types::UserCmd::buttons`] of every network frame outside of loading.
+    pub fn ghost(&self, options: &GhostOptions) -> Ghost {
+        let mut frames: Vec<GhostFrame> = vec![];
+        let mut start_time: Option<f32> = None;
+
+        let network_frames = self
+            .directory
+            .entries
+            .iter()
+            .filter(|entry| !entry.is_loading())
+            .flat_map(|entry| &entry.frames)
+            .filter_map(|frame| Some((frame.time, frame.frame_data.get_network_message()?)));
+
+        for (time, box_type) in network_frames {
+            let time = time - *start_time.get_or_insert(time);
+
+            if frames
+                .last()
+                .is_some_and(|last| time - last.time < options.interval)
+            {
+                continue;
+            }
+
+            let info = &box_type.1.info;
+
+            frames.push(GhostFrame {
+                time,
+                origin: vec3(&info.refparams.sim_org),
+                view_angles: vec3(&info.refparams.view_angles),
+                buttons: info.usercmd.button_flags(),
+            });
+        }
+
+        Ghost {
+            map_name: self.header.map_name.to_str().unwrap_or_default().to_owned(),
+            frames,
+        }
+    }
+}
+
+impl Ghost {
+    pub fn parse_from_bytes(bytes: &[u8]) -> Result<Self, DemoError> {
+        let mut offset = 0;
+        let mut take = |length: usize| -> Result<&[u8], DemoError> {
+            let taken = bytes
+                .get(offset..offset + length)
+                .ok_or(DemoError::InvalidGhost)?;
+            offset += length;
+
+            Ok(taken)
+        };
+
+        if take(8)? != GHOST_MAGIC {
+            return Err(DemoError::InvalidGhost);
+        }
+
+        let mut read_u32 =
+            || -> Result<u32, DemoError> { Ok(u32::from_le_bytes(take(4)?.try_into().unwrap())) };
+
+        if read_u32()? != GHOST_VERSION {
+            return Err(DemoError::InvalidGhost);
+        }
+
+        let map_name_length = read_u32()? as usize;
+        let map_name = std::str::from_utf8(take(map_name_length)?)
+            .map_err(|_| DemoError::InvalidGhost)?
+            .to_owned();
+
+        let frame_count = u32::from_le_bytes(take(4)?.try_into().unwrap()) as usize;
+
+        let frames = take(frame_count * 30)?
+            .chunks_exact(30)
+            .map(|frame| {
+                let f32_at =
+                    |index: usize| f32::from_le_bytes(frame[index * 4..][..4].try_into().unwrap());
+
+                GhostFrame {
+                    time: f32_at(0),
+                    origin: [f32_at(1), f32_at(2), f32_at(3)],
+                    view_angles: [f32_at(4), f32_at(5), f32_at(6)],
+                    buttons: Buttons::from_bits_retain(u16::from_le_bytes([frame[28], frame[29]])),
+                }
+            })
+            .collect();
+
+        Ok(Self { map_name, frames })
+    }
+
+    pub fn parse_from_file(path: impl AsRef<OsStr> + AsRef<Path>) -> Result<Self, DemoError> {
+        let mut file = OpenOptions::new().read(true).open(path)?;
+        let mut bytes = vec![];
+
+        file.read_to_end(&mut bytes)?;
+
+        Self::parse_from_bytes(&bytes)
+    }
+
+    pub fn write_to_bytes(&self) -> Vec<u8> {
+        let mut bytes = GHOST_MAGIC.to_vec();
+
+        bytes.extend(GHOST_VERSION.to_le_bytes());
+        bytes.extend((self.map_name.len() as u32).to_le_bytes());
+        bytes.extend(self.map_name.as_bytes());
+        bytes.extend((self.frames.len() as u32).to_le_bytes());
+
+        for frame in &self.frames {
+            [frame.time]
+                .iter()
+                .chain(&frame.origin)
+                .chain(&frame.view_angles)
+                .for_each(|value| bytes.extend(value.to_le_bytes()));
+
+            bytes.extend(frame.buttons.bits().to_le_bytes());
+        }
+
+        bytes
+    }
+
+    pub fn write_to_file(&self, path: impl AsRef<OsStr> + AsRef<Path>) -> Result<(), DemoError> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(path)?;
+
+        file.write_all(&self.write_to_bytes())?;
+        file.flush()?;
+
+        Ok(())
+    }
+
+    /// Builds a demo that plays the ghost back in first person.
+    ///
+    /// The LOADING entry, header and the world at the start come from the template, which has to
+    /// be on the same map. Entities stay as they were in the first frame of the template and
+    /// `view_height` is the eye height above the ghost origin.
+    pub fn to_demo(&self, template: &Demo, view_height: f32) -> Result<Demo, DemoError> {
+        if template.header.map_name.to_str().ok() != Some(self.map_name.as_str()) {
+            return Err(DemoError::IncompatibleDemos);
+        }
+
+        // only the first frame of the cut is used, which has everything written in full
+        let mut demo = template.cut(0., f32::MAX)?;
+        let mut state = DemoState::new_raw();
+
+        let [loading, playback] = demo.directory.entries.as_mut_slice() else {
+            unreachable!("a cut has a loading and a playback entry");
+        };
+
+        for box_type in loading
+            .frames
+            .iter()
+            .filter_map(|frame| frame.frame_data.get_network_message())
+        {
+            box_type
+                .1
+                .messages
+                .get_parsed_messages()
+                .into_iter()
+                .flatten()
+                .for_each(|message| state.apply(message));
+        }
+
+        let Some(anchor) = playback
+            .frames
+            .iter()
+            .find_map(|frame| frame.frame_data.get_network_message())
+            .map(|anchor| Box::new(anchor.clone()))
+        else {
+            return Err(DemoError::NoSnapshotInRange);
+        };
+
+        let anchor_messages = anchor
+            .1
+            .messages
+            .get_parsed_messages()
+            .ok_or(DemoError::UnparsedMessages)?;
+
+        let mut entity_count = 0;
+
+        for message in anchor_messages {
+            state.apply(message);
+
+            if let Some(EngineMessage::SvcPacketEntities(packet)) = message.get_engine_message() {
+                entity_count = packet.entity_count;
+            }
+        }
+
+        let sequence_info = anchor.1.sequence_info.clone();
+
+        let mut frames = vec![Frame {
+            time: 0.,
+            frame: 0,
+            frame_data: FrameData::DemoStart,
+        }];
+        let mut last_time = 0.;
+
+        for (index, ghost_frame) in self.frames.iter().enumerate() {
+            let view = PlayerView {
+                origin: ghost_frame.origin,
+                view_angles: ghost_frame.view_angles,
+                view_height,
+                is_ducking: false,
+                viewmodel: None,
+            };
+            let eye_origin = view.eye_origin().to_vec();
+            let view_angles = ghost_frame.view_angles.to_vec();
+            let frame_time = ghost_frame.time - last_time;
+
+            let engine_message =
+                |message: EngineMessage| NetMessage::EngineMessage(Box::new(message));
+
+            let mut messages = vec![
+                engine_message(EngineMessage::SvcTime(SvcTime {
+                    time: ghost_frame.time,
+                })),
+                engine_message(EngineMessage::SvcClientData(SvcClientData {
+                    has_delta_update_mask: false,
+                    delta_update_mask: None,
+                    client_data: view.client_data(&state),
+                    weapon_data: None,
+                })),
+            ];
+
+            // the world is sent once and then stays the same
+            if index == 0 {
+                messages.extend(
+                    anchor_messages
+                        .iter()
+                        .filter(|message| {
+                            !matches!(
+                                message.get_engine_message(),
+                                Some(EngineMessage::SvcTime(_) | EngineMessage::SvcClientData(_))
+                            )
+                        })
+                        .cloned(),
+                );
+            } else {
+                messages.push(engine_message(EngineMessage::SvcDeltaPacketEntities(
+                    SvcDeltaPacketEntities {
+                        entity_count,
+                        delta_sequence: ((sequence_info.incoming_sequence + index as i32 - 1)
+                            & 0xFF) as u8,
+                        entity_states: vec![],
+                    },
+                )));
+            }
+
+            let mut box_type = anchor.clone();
+            let network_message = &mut box_type.1;
+            let info = &mut network_message.info;
+            let refparams = &mut info.refparams;
+
+            refparams.time = ghost_frame.time;
+            refparams.frame_time = frame_time;
+            refparams.view_origin = eye_origin.clone();
+            refparams.view_angles = view_angles.clone();
+            refparams.cl_viewangles = view_angles.clone();
+            refparams.sim_org = ghost_frame.origin.to_vec();
+            refparams.view_height = vec![0., 0., view_height];
+
+            info.usercmd.view_angles = view_angles.clone();
+            info.usercmd.buttons = ghost_frame.buttons.bits();
+            info.usercmd.msec = (frame_time * 1000.).round().clamp(0., u8::MAX as f32) as u8;
+            info.view = eye_origin.clone();
+
+            let sequence = &mut network_message.sequence_info;
+            sequence.incoming_sequence = sequence_info.incoming_sequence + index as i32;
+            sequence.incoming_acknowledged = sequence_info.incoming_acknowledged + index as i32;
+            sequence.outgoing_sequence = sequence_info.outgoing_sequence + index as i32;
+
+            network_message.messages = MessageData::Parsed(messages);
+
+            // playback has client data before every network message
+            frames.push(Frame {
+                time: ghost_frame.time,
+                frame: index as i32,
+                frame_data: FrameData::ClientData(ClientData {
+                    origin: eye_origin,
+                    viewangles: view_angles,
+                    weapon_bits: 0,
+                    fov: 90.,
+                }),
+            });
+            frames.push(Frame {
+                time: ghost_frame.time,
+                frame: index as i32,
+                frame_data: FrameData::NetworkMessage(box_type),
+            });
+
+            last_time = ghost_frame.time;
+        }
+
+        frames.push(Frame {
+            time: last_time,
+            frame: self.frames.len() as i32,
+            frame_data: FrameData::NextSection,
+        });
+
+        playback.track_time = last_time;
+        playback.frame_count = frames.len() as i32;
+        playback.frames = frames;
+
+        Ok(demo)
+    }
+}
+
+fn vec3(point: &[f32]) -> [f32; 3] {
+    [0, 1, 2].map(|index| point.get(index).copied().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{open_demo, open_demo_from_bytes};
+
+    #[test]
+    fn round_trip() {
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let ghost = demo.ghost(&GhostOptions::default());
+
+        assert_eq!(ghost.map_name, demo.header.map_name.to_str().unwrap());
+        assert_eq!(ghost.frames[0].time, 0.);
+
+        let bytes = ghost.write_to_bytes();
+        assert_eq!(Ghost::parse_from_bytes(&bytes).unwrap(), ghost);
+        assert!(matches!(
+            Ghost::parse_from_bytes(&bytes[..bytes.len() - 1]),
+            Err(DemoError::InvalidGhost)
+        ));
+
+        let downsampled = demo.ghost(&GhostOptions { interval: 0.1 });
+        assert!(downsampled.frames.len() < ghost.frames.len());
+        assert!(
+            downsampled
+                .frames
+                .windows(2)
+                .all(|pair| pair[1].time - pair[0].time >= 0.1)
+        );
+    }
+
+    #[test]
+    fn to_demo() {
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let ghost = demo.ghost(&GhostOptions { interval: 0.05 });
+
+        let ghost_demo = ghost.to_demo(&demo, 28.).unwrap();
+        // round trip through the writer to make sure every message still encodes
+        let ghost_demo = open_demo_from_bytes(&ghost_demo.write_to_bytes()).unwrap();
+
+        assert_eq!(
+            ghost_demo.ghost(&GhostOptions::default()).frames,
+            ghost.frames
+        );
+    }
+}
@@ -0,0 +1,454 @@
+//! Usercmds of a demo as `.hltas` scripts, the input format of the Half-Life TAS tools, and back.
+//!
+//! A script is a list of frame bulks, each holding keys for some number of frames:
+//!
+//! ```text
+//! version 1
+//! frames
+//! ----------|f-----|j-----|0.01|90|-5|3
+//! ----------|f-r---|------|0.01|95|-|1|impulse 101
+//! ```
+//!
+//! The fields are automatic actions, movement keys (`flrbud`), action keys (`jdu12r`), frame
+//! time, yaw, pitch, repeat count and console commands. Yaw and pitch are `-` when they stay the
+//! same.
+//!
+//! Movement keys come from the signs of [`UserCmd::forward_move`], [`UserCmd::side_move`] and
+//! [`UserCmd::up_move`]. Action keys are jump, duck, use, attack, attack2 and reload from
+//! [`UserCmd::buttons`], and other buttons are dropped. The frame time is [`UserCmd::msec`], so
+//! it is rounded to a millisecond.
+//!
+//! Reading a script ignores automatic actions and every line other than frame bulks, since those
+//! need the player movement simulated to turn into usercmds.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let demo = open_demo("./src/tests/demotest.dem").unwrap();
+//! std::fs::write("./src/tests/demotest.hltas", demo.to_hltas()).unwrap();
+//!
+//! let script = std::fs::read_to_string("./src/tests/demotest.hltas").unwrap();
+//! let user_cmds = hltas_to_user_cmds(&script, &HltasOptions::default()).unwrap();
+//! ```
+use std::fmt::Write;
+
+use crate::{
+    error::DemoError,
+    types::{Buttons, Demo, FrameData, UserCmd},
+};
+
+/// Field of automatic actions with none set.
+const NO_AUTO_ACTIONS: &str = "----------";
+const MOVEMENT_KEYS: [char; 6] = ['f', 'l', 'r', 'b', 'u', 'd'];
+const ACTION_KEYS: [(char, Buttons); 6] = [
+    ('j', Buttons::JUMP),
+    ('d', Buttons::DUCK),
+    ('u', Buttons::USE),
+    ('1', Buttons::ATTACK),
+    ('2', Buttons::ATTACK2),
+    ('r', Buttons::RELOAD),
+];
+/// Buttons held along with the movement keys.
+const MOVEMENT_BUTTONS: [Buttons; 6] = [
+    Buttons::FORWARD,
+    Buttons::MOVELEFT,
+    Buttons::MOVERIGHT,
+    Buttons::BACK,
+    Buttons::empty(),
+    Buttons::empty(),
+];
+
+#[derive(Debug, Clone)]
+pub struct HltasOptions {
+    /// `cl_forwardspeed`
+    pub forward_speed: f32,
+    /// `cl_sidespeed`
+    pub side_speed: f32,
+    /// `cl_upspeed`
+    pub up_speed: f32,
+}
+
+impl Default for HltasOptions {
+    fn default() -> Self {
+        Self {
+            forward_speed: 400.,
+            side_speed: 400.,
+            up_speed: 320.,
+        }
+    }
+}
+
+/// A frame bulk without its repeat count.
+#[derive(Debug, Clone, PartialEq)]
+struct FrameBulk {
+    movement: [bool; 6],
+    actions: Buttons,
+    msec: u8,
+    yaw: f32,
+    pitch: f32,
+    impulse: i8,
+}
+
+impl FrameBulk {
+    fn from_user_cmd(user_cmd: &UserCmd) -> Self {
+        let forward = user_cmd.forward_move;
+        let side = user_cmd.side_move;
+        let up = user_cmd.up_move;
+
+        let actions = ACTION_KEYS
+            .iter()
+            .map(|(_, button)| *button)
+            .filter(|button| user_cmd.button_flags().contains(*button))
+            .collect();
+
+        Self {
+            movement: [
+                forward > 0.,
+                side < 0.,
+                side > 0.,
+                forward < 0.,
+                up > 0.,
+                up < 0.,
+            ],
+            actions,
+            msec: user_cmd.msec,
+            pitch: user_cmd.view_angles.first().copied().unwrap_or_default(),
+            yaw: user_cmd.view_angles.get(1).copied().unwrap_or_default(),
+            impulse: user_cmd.impulse,
+        }
+    }
+
+    fn to_user_cmd(&self, options: &HltasOptions) -> UserCmd {
+        let [forward, left, right, back, up, down] = self.movement;
+        let axis = |positive: bool, negative: bool, speed: f32| match (positive, negative) {
+            (true, false) => speed,
+            (false, true) => -speed,
+            _ => 0.,
+        };
+
+        let buttons = MOVEMENT_BUTTONS
+            .iter()
+            .zip(self.movement)
+            .filter(|(_, is_held)| *is_held)
+            .fold(self.actions, |buttons, (button, _)| buttons | *button);
+
+        UserCmd {
+            lerp_msec: 0,
+            msec: self.msec,
+            unknown1: 0,
+            view_angles: vec![self.pitch, self.yaw, 0.],
+            forward_move: axis(forward, back, options.forward_speed),
+            side_move: axis(right, left, options.side_speed),
+            up_move: axis(up, down, options.up_speed),
+            light_level: 0,
+            unknonwn2: 0,
+            buttons: buttons.bits(),
+            impulse: self.impulse,
+            weapon_select: 0,
+            unknown3: 0,
+            unknown4: 0,
+            impact_index: 0,
+            impact_position: vec![0.; 3],
+        }
+    }
+}
+
+impl Demo {
+    /// A script with the usercmd of every network frame outside of loading.
+    ///
+    /// Demos only keep the last usercmd sent before each frame, so runs recorded with a higher
+    /// `cl_cmdrate` than frame rate lose some inputs.
+    pub fn to_hltas(&self) -> String {
+        let user_cmds = self
+            .directory
+            .entries
+            .iter()
+            .filter(|entry| !entry.is_loading())
+            .flat_map(|entry| &entry.frames)
+            .filter_map(|frame| match &frame.frame_data {
+                FrameData::NetworkMessage(box_type) => Some(&box_type.1.info.usercmd),
+                _ => None,
+            });
+
+        user_cmds_to_hltas(user_cmds)
+    }
+}
+
+/// Writes the usercmds as a script, joining identical ones in a row into one frame bulk.
+pub fn user_cmds_to_hltas<'a>(user_cmds: impl IntoIterator<Item = &'a UserCmd>) -> String {
+    let mut bulks: Vec<(FrameBulk, usize)> = vec![];
+
+    for user_cmd in user_cmds {
+        let bulk = FrameBulk::from_user_cmd(user_cmd);
+
+        match bulks.last_mut() {
+            Some((last, count)) if *last == bulk => *count += 1,
+            _ => bulks.push((bulk, 1)),
+        }
+    }
+
+    let mut script = String::from("version 1\nframes\n");
+    let mut last_angles: Option<(f32, f32)> = None;
+
+    for (bulk, count) in bulks {
+        let movement: String = MOVEMENT_KEYS
+            .iter()
+            .zip(bulk.movement)
+            .map(|(key, is_held)| if is_held { *key } else { '-' })
+            .collect();
+        let actions: String = ACTION_KEYS
+            .iter()
+            .map(|(key, button)| {
+                if bulk.actions.contains(*button) {
+                    *key
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+
+        let (last_yaw, last_pitch) = last_angles.unzip();
+        let angle = |angle: f32, last: Option<f32>| {
+            if last == Some(angle) {
+                "-".to_owned()
+            } else {
+                angle.to_string()
+            }
+        };
+
+        write!(
+            script,
+            "{NO_AUTO_ACTIONS}|{movement}|{actions}|{}|{}|{}|{count}",
+            bulk.msec as f32 / 1000.,
+            angle(bulk.yaw, last_yaw),
+            angle(bulk.pitch, last_pitch),
+        )
+        .unwrap();
+
+        if bulk.impulse != 0 {
+            // impulses go up to 255 in the game
+            write!(script, "|impulse {}", bulk.impulse as u8).unwrap();
+        }
+
+        script.push('\n');
+        last_angles = Some((bulk.yaw, bulk.pitch));
+    }
+
+    script
+}
+
+/// Reads the frame bulks of a script into a usercmd for every frame.
+///
+/// Movement keys move at the speeds in the options. Yaw and pitch start at 0.
+pub fn hltas_to_user_cmds(script: &str, options: &HltasOptions) -> Result<Vec<UserCmd>, DemoError> {
+    let mut user_cmds = vec![];
+    let mut yaw = 0.;
+    let mut pitch = 0.;
+    let mut is_in_frames = false;
+
+    for (line_index, line) in script.lines().enumerate() {
+        let line = line.trim();
+        let error = || DemoError::InvalidHltas {
+            line: line_index + 1,
+        };
+
+        if line.is_empty() || line.starts_with("//") {
+            continue;
+        }
+
+        // properties come before frames
+        if !is_in_frames {
+            is_in_frames = line == "frames";
+            continue;
+        }
+
+        // strafing settings and other lines between bulks
+        if !line.contains('|') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.splitn(8, '|').collect();
+
+        let [
+            _,
+            movement,
+            actions,
+            frame_time,
+            new_yaw,
+            new_pitch,
+            count,
+            ..,
+        ] = fields[..]
+        else {
+            return Err(error());
+        };
+
+        let keys = |field: &str, keys: &[char]| -> Option<Vec<bool>> {
+            let chars: Vec<char> = field.chars().collect();
+
+            (chars.len() == keys.len()).then_some(())?;
+
+            chars
+                .iter()
+                .zip(keys)
+                .map(|(char, key)| match char {
+                    '-' => Some(false),
+                    char if char == key => Some(true),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        let movement: [bool; 6] = keys(movement, &MOVEMENT_KEYS)
+            .and_then(|keys| keys.try_into().ok())
+            .ok_or_else(error)?;
+        let actions = keys(actions, &ACTION_KEYS.map(|(key, _)| key))
+            .ok_or_else(error)?
+            .into_iter()
+            .zip(ACTION_KEYS)
+            .filter(|(is_held, _)| *is_held)
+            .map(|(_, (_, button))| button)
+            .collect();
+
+        let frame_time: f32 = frame_time.parse().map_err(|_| error())?;
+
+        if new_yaw != "-" {
+            yaw = new_yaw.parse().map_err(|_| error())?;
+        }
+
+        if new_pitch != "-" {
+            pitch = new_pitch.parse().map_err(|_| error())?;
+        }
+
+        let count: usize = count.parse().map_err(|_| error())?;
+
+        let impulse = fields
+            .get(7)
+            .into_iter()
+            .flat_map(|commands| commands.split(';'))
+            .find_map(|command| command.trim().strip_prefix("impulse "))
+            .map(|impulse| impulse.trim().parse::<u8>())
+            .transpose()
+            .map_err(|_| error())?
+            .unwrap_or_default() as i8;
+
+        let bulk = FrameBulk {
+            movement,
+            actions,
+            msec: (frame_time * 1000.).round().clamp(0., u8::MAX as f32) as u8,
+            yaw,
+            pitch,
+            impulse,
+        };
+
+        let user_cmd = bulk.to_user_cmd(options);
+        user_cmds.extend(std::iter::repeat_n(user_cmd, count));
+    }
+
+    Ok(user_cmds)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::open_demo;
+
+    #[test]
+    fn round_trip() {
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let script = demo.to_hltas();
+
+        let user_cmds: Vec<UserCmd> = demo
+            .directory
+            .entries
+            .iter()
+            .filter(|entry| !entry.is_loading())
+            .flat_map(|entry| &entry.frames)
+            .filter_map(|frame| match &frame.frame_data {
+                FrameData::NetworkMessage(box_type) => Some(box_type.1.info.usercmd.clone()),
+                _ => None,
+            })
+            .collect();
+
+        // identical usercmds in a row are joined
+        assert!(script.lines().count() < user_cmds.len());
+
+        let from_script = hltas_to_user_cmds(&script, &HltasOptions::default()).unwrap();
+
+        assert_eq!(from_script.len(), user_cmds.len());
+        assert!(
+            user_cmds
+                .iter()
+                .zip(&from_script)
+                .all(|(user_cmd, from_script)| FrameBulk::from_user_cmd(user_cmd)
+                    == FrameBulk::from_user_cmd(from_script))
+        );
+        assert_eq!(user_cmds_to_hltas(&from_script), script);
+    }
+
+    #[test]
+    fn read_script() {
+        let script = "\
+version 1
+demo run
+frames
+// comment
+----------|f-----|j-----|0.01|90|-5|3
+strafing vectorial
+s03lj-----|f-r---|------|0.004|-|-|1|echo hi; impulse 101
+";
+
+        let user_cmds = hltas_to_user_cmds(script, &HltasOptions::default()).unwrap();
+
+        assert_eq!(user_cmds.len(), 4);
+        assert_eq!(user_cmds[0].msec, 10);
+        assert_eq!(user_cmds[0].view_angles, [-5., 90., 0.]);
+        assert_eq!(user_cmds[0].forward_move, 400.);
+        assert_eq!(
+            user_cmds[0].button_flags(),
+            Buttons::JUMP | Buttons::FORWARD
+        );
+        assert_eq!(user_cmds[3].msec, 4);
+        assert_eq!(user_cmds[3].view_angles, [-5., 90., 0.]);
+        assert_eq!(user_cmds[3].side_move, 400.);
+        assert_eq!(user_cmds[3].impulse, 101);
+
+        assert!(matches!(
+            hltas_to_user_cmds(
+                "frames\n----------|x-----|------|0.01|-|-|1",
+                &HltasOptions::default()
+            ),
+            Err(DemoError::InvalidHltas { line: 2 })
+        ));
+    }
+
+    #[test]
+    fn impulse_above_127() {
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let mut user_cmd = demo.directory.entries[1]
+            .frames
+            .iter()
+            .find_map(|frame| match &frame.frame_data {
+                FrameData::NetworkMessage(box_type) => Some(box_type.1.info.usercmd.clone()),
+                _ => None,
+            })
+            .unwrap();
+
+        // 201 is what the byte holds, even though the field is signed
+        user_cmd.impulse = 201u8 as i8;
+
+        let script = user_cmds_to_hltas(&[user_cmd]);
+        assert!(script.contains("|impulse 201\n"));
+
+        let user_cmds = hltas_to_user_cmds(&script, &HltasOptions::default()).unwrap();
+        assert_eq!(user_cmds[0].impulse as u8, 201);
+
+        assert!(matches!(
+            hltas_to_user_cmds(
+                "frames\n----------|------|------|0.01|-|-|1|impulse 256",
+                &HltasOptions::default()
+            ),
+            Err(DemoError::InvalidHltas { line: 2 })
+        ));
+    }
+}
@@ -0,0 +1,139 @@
+//! Half-Life infostrings, such as `\name\foo\model\gign\topcolor\0`.
+//!
+//! Values are kept as bytes because player names are not always UTF-8.
+use crate::types::{ByteString, ByteVec};
+
+#[derive(Debug, Clone, Default)]
+pub struct InfoString {
+    /// Key and value pairs in the order they appear.
+    pub pairs: Vec<(String, ByteString)>,
+}
+
+impl InfoString {
+    /// Parses an infostring. Anything after the null terminator is ignored.
+    pub fn parse(i: &[u8]) -> Self {
+        let end = i.iter().position(|&c| c == 0).unwrap_or(i.len());
+        let i = &i[..end];
+
+        // leading backslash is optional
+        let i = i.strip_prefix(b"\\").unwrap_or(i);
+
+        let mut parts = i.split(|&c| c == b'\\');
+        let mut pairs = vec![];
+
+        while let Some(key) = parts.next() {
+            if key.is_empty() {
+                continue;
+            }
+
+            let value = parts.next().unwrap_or_default();
+
+            pairs.push((String::from_utf8_lossy(key).into_owned(), value.into()));
+        }
+
+        Self { pairs }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&ByteString> {
+        self.pairs
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Gets the value as a string, replacing invalid UTF-8.
+    pub fn get_lossy(&self, key: &str) -> Option<String> {
+        self.get(key)
+            .map(|value| String::from_utf8_lossy(value.as_slice()).into_owned())
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Sets the value for the key, keeping its position if it already exists.
+    pub fn set(&mut self, key: &str, value: impl Into<ByteString>) {
+        let value = value.into();
+
+        match self.pairs.iter_mut().find(|(k, _)| k == key) {
+            Some((_, old)) => *old = value,
+            None => self.pairs.push((key.to_owned(), value)),
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<ByteString> {
+        let index = self.pairs.iter().position(|(k, _)| k == key)?;
+
+        Some(self.pairs.remove(index).1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Writes the infostring back with a null terminator, ready for
+    /// [`crate::types::SvcUpdateUserInfo::user_info`].
+    pub fn to_bytes(&self) -> ByteVec {
+        let mut res = vec![];
+
+        for (key, value) in &self.pairs {
+            res.push(b'\\');
+            res.extend_from_slice(key.as_bytes());
+            res.push(b'\\');
+            res.extend_from_slice(value.as_slice());
+        }
+
+        res.push(0);
+
+        res
+    }
+}
+
+impl From<&ByteString> for InfoString {
+    fn from(value: &ByteString) -> Self {
+        Self::parse(value.as_slice())
+    }
+}
+
+impl From<&InfoString> for ByteString {
+    fn from(value: &InfoString) -> Self {
+        value.to_bytes().into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let info = InfoString::parse(b"\\name\\foo\\model\\gign\\*sid\\7656119\0garbage");
+
+        assert_eq!(info.pairs.len(), 3);
+        assert_eq!(info.get_lossy("name").unwrap(), "foo");
+        assert_eq!(info.get_lossy("*sid").unwrap(), "7656119");
+        assert!(info.get("garbage").is_none());
+    }
+
+    #[test]
+    fn parse_empty_value() {
+        let info = InfoString::parse(b"\\name\\\\model\\gign\0");
+
+        assert_eq!(info.get_lossy("name").unwrap(), "");
+        assert_eq!(info.get_lossy("model").unwrap(), "gign");
+    }
+
+    #[test]
+    fn set_write() {
+        let original = b"\\name\\foo\\model\\gign\0";
+        let mut info = InfoString::parse(original);
+
+        assert_eq!(info.to_bytes(), original);
+
+        info.set("name", "bar");
+        info.set("topcolor", "1");
+        info.remove("model");
+
+        assert_eq!(info.to_bytes(), b"\\name\\bar\\topcolor\\1\0");
+    }
+}
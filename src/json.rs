@@ -0,0 +1,174 @@
+//! A whole demo as JSON, for editing by hand and diffing.
+//!
+//! The JSON is the `serde` form of [`Demo`], so it follows [`crate::types`] field for field:
+//!
+//! ```json
+//! {
+//!   "header": {
+//!     "magic": [72, 76, 68, 69, 77, 79, 0, 0],
+//!     "demo_protocol": 5,
+//!     "network_protocol": 48,
+//!     "map_name": "crossfire\u0000\u0000...",
+//!     "game_directory": "cstrike\u0000\u0000...",
+//!     "map_checksum": 0,
+//!     "directory_offset": 495021
+//!   },
+//!   "directory": {
+//!     "entries": [
+//!       {
+//!         "type_": 1,
+//!         "description": "Playback\u0000\u0000...",
+//!         "flags": 0,
+//!         "cd_track": -1,
+//!         "track_time": 7.3526917,
+//!         "frame_count": 736,
+//!         "frame_offset": 35478,
+//!         "file_length": 459543,
+//!         "frames": [
+//!           { "time": 0.0, "frame": 0, "frame_data": "DemoStart" },
+//!           {
+//!             "time": 0.01,
+//!             "frame": 1,
+//!             "frame_data": {
+//!               "NetworkMessage": ["Normal", {
+//!                 "info": { ... },
+//!                 "sequence_info": { ... },
+//!                 "message_length": 123,
+//!                 "messages": { "Parsed": [
+//!                   { "EngineMessage": { "SvcTime": { "time": 1.5 } } },
+//!                   { "EngineMessage": "SvcNop" },
+//!                   { "UserMessage": { "id": 64, "name": "ResetHUD\u0000...", "data": [] } },
+//!                   { "EngineMessage": { "SvcClientData": {
+//!                     "has_delta_update_mask": false,
+//!                     "delta_update_mask": null,
+//!                     "client_data": { "flags\u0000": { "IntUnsigned": 512 } },
+//!                     "weapon_data": null
+//!                   } } }
+//!                 ] }
+//!               }]
+//!             }
+//!           }
+//!         ]
+//!       }
+//!     ]
+//!   }
+//! }
+//! ```
+//!
+//! - Enums are objects with the variant name as the only key, or just the name when the variant
+//!   has no data.
+//! - [`crate::types::ByteString`] is a string when its bytes are valid UTF-8, padding and null
+//!   terminator included, and an array of bytes otherwise. Other byte fields are arrays.
+//! - A [`crate::types::Delta`] is an object from field name, with its null terminator, to the
+//!   value tagged with its type. Fields are sorted by name so the output does not change between
+//!   runs.
+//! - Messages that were not parsed are `{ "Raw": [...] }`.
+//!
+//! Lengths, counts and offsets, such as `message_length`, `frame_count`, `frame_offset` and
+//! `directory_offset`, are computed again when writing, so they do not need to be updated after
+//! an edit. The parser state is not included.
+//!
+//! JSON has no NaN or infinity, so a demo with non-finite floats is written with `null` in their
+//! place and cannot be read back.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let demo = open_demo("./src/tests/demotest.dem").unwrap();
+//! demo.write_json_to_file("./src/tests/demotest.json").unwrap();
+//!
+//! // after editing
+//! let demo = Demo::from_json_file("./src/tests/demotest.json").unwrap();
+//! demo.write_to_file("./src/tests/demo2test.dem").unwrap();
+//! ```
+use std::{
+    ffi::OsStr,
+    fs::OpenOptions,
+    io::{Read, Write},
+    path::Path,
+};
+
+use crate::{error::DemoError, types::Demo};
+
+impl Demo {
+    /// Pretty-printed JSON of the whole demo.
+    pub fn to_json(&self) -> Result<String, DemoError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Reads a demo written by [`Demo::to_json`].
+    ///
+    /// [`Demo::write_to_bytes`] of the result gives back the original file.
+    pub fn from_json(json: &str) -> Result<Self, DemoError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn write_json_to_file(
+        &self,
+        path: impl AsRef<OsStr> + AsRef<Path>,
+    ) -> Result<(), DemoError> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(path)?;
+
+        file.write_all(self.to_json()?.as_bytes())?;
+        file.flush()?;
+
+        Ok(())
+    }
+
+    pub fn from_json_file(path: impl AsRef<OsStr> + AsRef<Path>) -> Result<Self, DemoError> {
+        let mut file = OpenOptions::new().read(true).open(path)?;
+        let mut json = String::new();
+
+        file.read_to_string(&mut json)?;
+
+        Self::from_json(&json)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{open_demo, open_demo_from_bytes};
+
+    #[test]
+    fn round_trip() {
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let json = demo.to_json().unwrap();
+
+        let from_json = Demo::from_json(&json).unwrap();
+
+        assert_eq!(
+            from_json.write_to_bytes(),
+            std::fs::read("./src/tests/weapon.dem").unwrap()
+        );
+        // deltas are sorted, so the new hash maps give the same JSON
+        assert_eq!(from_json.to_json().unwrap(), json);
+        assert!(Demo::from_json("{}").is_err());
+    }
+
+    #[test]
+    fn edited_frame_count() {
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let mut json: serde_json::Value = serde_json::from_str(&demo.to_json().unwrap()).unwrap();
+
+        // the last network frame and the frames after it, with the count left as it was
+        let frames = json["directory"]["entries"][1]["frames"]
+            .as_array_mut()
+            .unwrap();
+        let last_network_frame = frames
+            .iter()
+            .rposition(|frame| frame["frame_data"].get("NetworkMessage").is_some())
+            .unwrap();
+        frames.truncate(last_network_frame);
+
+        let edited = Demo::from_json(&json.to_string()).unwrap();
+        let reopened = open_demo_from_bytes(&edited.write_to_bytes()).unwrap();
+
+        assert_eq!(reopened.directory.entries[0].frame_count, 0);
+        assert_eq!(reopened.directory.entries[1].frame_count, 735);
+    }
+}
@@ -28,12 +28,42 @@ mod delta;
 mod nom_helper;
 mod utils;
 
+#[cfg(feature = "archive")]
+pub mod archive;
 pub mod bit;
+pub mod cinematic;
+pub mod concat;
+pub mod cut;
 pub mod demo_parser;
 pub mod demo_writer;
+pub mod dump;
 pub mod error;
+pub mod export;
+pub mod ghost;
+pub mod hltas;
+pub mod infostring;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod map;
 pub mod netmsg_doer;
+pub mod pov;
+#[cfg(feature = "proto")]
+pub mod proto;
+pub mod repair;
+pub mod retime;
+pub mod roster;
+pub mod scrub;
+pub mod snapshot;
+#[cfg(feature = "source")]
+pub mod source_demo;
+pub mod splice;
+pub mod timeline;
+pub mod trajectory;
+pub mod transcript;
+pub mod transform;
 pub mod types;
+pub mod usermsg_doer;
+pub mod voice;
 
 // need this to have the conversion function
 pub use crate::bit::BitSliceCast;
@@ -0,0 +1,313 @@
+//! Pointing a demo at a renamed or rebuilt map.
+//!
+//! The map name and checksum are in the header, in [`crate::types::SvcServerInfo`] and in the
+//! resource list. The client refuses to play the demo if its BSP does not match them.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut demo = open_demo("./src/tests/demotest.dem").unwrap();
+//!
+//! let checksum = bsp_checksum_from_file("./cstrike/maps/de_dust2_new.bsp").unwrap();
+//! demo.retarget_map("de_dust2_new", checksum);
+//!
+//! demo.write_to_file("./src/tests/demo2test.dem").unwrap();
+//! ```
+use std::{ffi::OsStr, fs::OpenOptions, io::Read, path::Path};
+
+use crate::{
+    error::DemoError,
+    types::{Demo, EngineMessage, FrameData},
+};
+
+const BSP_VERSION: i32 = 30;
+const BSP_LUMPS: usize = 15;
+/// The entities lump can be changed without the client noticing.
+const LUMP_ENTITIES: usize = 0;
+/// `mungify_table3`
+const MUNGIFY_TABLE_3: [u8; 16] = [
+    0x20, 0x07, 0x13, 0x61, 0x03, 0x45, 0x17, 0x72, 0x0A, 0x2D, 0x48, 0x0C, 0x4A, 0x12, 0xA9, 0xB5,
+];
+
+/// Computes the checksum the engine uses to tell if the client has the same map as the server.
+///
+/// It is the CRC32 of every lump except entities, in lump order.
+pub fn bsp_checksum(bsp: &[u8]) -> Result<u32, DemoError> {
+    let read_i32 = |offset: usize| -> Result<i32, DemoError> {
+        let bytes = bsp.get(offset..offset + 4).ok_or(DemoError::InvalidBsp)?;
+        Ok(i32::from_le_bytes(bytes.try_into().unwrap()))
+    };
+
+    if read_i32(0)? != BSP_VERSION {
+        return Err(DemoError::InvalidBsp);
+    }
+
+    let mut hasher = crc32fast::Hasher::new();
+
+    for lump in 0..BSP_LUMPS {
+        let offset = read_i32(4 + lump * 8)?;
+        let length = read_i32(4 + lump * 8 + 4)?;
+
+        if lump == LUMP_ENTITIES {
+            continue;
+        }
+
+        let (Ok(offset), Ok(length)) = (usize::try_from(offset), usize::try_from(length)) else {
+            return Err(DemoError::InvalidBsp);
+        };
+
+        let lump = bsp
+            .get(offset..offset + length)
+            .ok_or(DemoError::InvalidBsp)?;
+
+        hasher.update(lump);
+    }
+
+    Ok(hasher.finalize())
+}
+
+/// Sequence number the map checksum in [`crate::types::SvcServerInfo`] is munged with.
+fn munge_sequence(player_index: u8) -> u32 {
+    ((-1 - player_index as i32) & 0xFF) as u32
+}
+
+fn munge_key(j: usize) -> u8 {
+    0xA5 | (j << j) as u8 | j as u8 | MUNGIFY_TABLE_3[j & 0x0F]
+}
+
+/// `COM_Munge3` of the map checksum the server sends in [`crate::types::SvcServerInfo`] to the
+/// client in slot `player_index`.
+pub fn munge_map_checksum(map_checksum: u32, player_index: u8) -> u32 {
+    let sequence = munge_sequence(player_index);
+    let mut bytes = (map_checksum ^ !sequence).swap_bytes().to_le_bytes();
+
+    for (j, byte) in bytes.iter_mut().enumerate() {
+        *byte ^= munge_key(j);
+    }
+
+    u32::from_le_bytes(bytes) ^ sequence
+}
+
+/// `COM_UnMunge3`, the map checksum from [`crate::types::SvcServerInfo::map_checksum`] and
+/// [`crate::types::SvcServerInfo::player_index`].
+pub fn unmunge_map_checksum(munged: u32, player_index: u8) -> u32 {
+    let sequence = munge_sequence(player_index);
+    let mut bytes = (munged ^ sequence).to_le_bytes();
+
+    for (j, byte) in bytes.iter_mut().enumerate() {
+        *byte ^= munge_key(j);
+    }
+
+    u32::from_le_bytes(bytes).swap_bytes() ^ !sequence
+}
+
+/// [`bsp_checksum`] of a file.
+pub fn bsp_checksum_from_file(path: impl AsRef<OsStr> + AsRef<Path>) -> Result<u32, DemoError> {
+    let mut file = OpenOptions::new().read(true).open(path)?;
+    let mut bytes: Vec<u8> = vec![];
+
+    file.read_to_end(&mut bytes)?;
+
+    bsp_checksum(&bytes)
+}
+
+impl Demo {
+    /// Changes the map to `map_name`, without `maps/` and `.bsp`, with the checksum from
+    /// [`bsp_checksum`].
+    ///
+    /// Updates [`crate::types::Header::map_name`] and [`crate::types::Header::map_checksum`],
+    /// [`crate::types::SvcServerInfo::map_file_name`] and
+    /// [`crate::types::SvcServerInfo::map_checksum`], munged with the player slot like the server
+    /// does, and every resource under `maps/` named after the old map, such as the BSP itself and
+    /// its `.txt` and `.res`.
+    pub fn retarget_map(&mut self, map_name: &str, map_checksum: u32) {
+        let old_name = self.header.map_name.to_str().unwrap_or_default().to_owned();
+        let old_prefix = format!("maps/{old_name}.");
+        let new_prefix = format!("maps/{map_name}.");
+
+        self.header.map_name = map_name.into();
+        self.header.map_checksum = map_checksum;
+
+        self.for_each_engine_message(|message| match message {
+            EngineMessage::SvcServerInfo(server_info) => {
+                server_info.map_file_name = format!("maps/{map_name}.bsp\0").into_bytes();
+                server_info.map_checksum =
+                    munge_map_checksum(map_checksum, server_info.player_index) as i32;
+            }
+            EngineMessage::SvcResourceList(resource_list) => {
+                for resource in &mut resource_list.resources {
+                    if !old_name.is_empty()
+                        && let Some(extension) = resource.name.strip_prefix(&old_prefix)
+                    {
+                        resource.name = format!("{new_prefix}{extension}");
+                    }
+                }
+            }
+            _ => (),
+        });
+    }
+
+    /// Reads the name and checksum for [`Demo::retarget_map`] from a BSP file.
+    pub fn retarget_map_to_file(
+        &mut self,
+        path: impl AsRef<OsStr> + AsRef<Path>,
+    ) -> Result<(), DemoError> {
+        let map_checksum = bsp_checksum_from_file(&path)?;
+        let path: &Path = path.as_ref();
+        let map_name = path
+            .file_stem()
+            .and_then(OsStr::to_str)
+            .ok_or(DemoError::InvalidBsp)?;
+
+        self.retarget_map(map_name, map_checksum);
+
+        Ok(())
+    }
+
+    /// Changes [`crate::types::Header::game_directory`] and
+    /// [`crate::types::SvcServerInfo::game_dir`].
+    pub fn set_game_directory(&mut self, game_directory: &str) {
+        self.header.game_directory = game_directory.into();
+
+        self.for_each_engine_message(|message| {
+            if let EngineMessage::SvcServerInfo(server_info) = message {
+                server_info.game_dir = format!("{game_directory}\0").into_bytes();
+            }
+        });
+    }
+
+    fn for_each_engine_message(&mut self, mut f: impl FnMut(&mut EngineMessage)) {
+        self.directory
+            .entries
+            .iter_mut()
+            .flat_map(|entry| &mut entry.frames)
+            .filter_map(|frame| match &mut frame.frame_data {
+                FrameData::NetworkMessage(box_type) => {
+                    box_type.1.messages.get_parsed_messages_mut()
+                }
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|message| message.get_engine_message_mut())
+            .for_each(&mut f);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{open_demo, open_demo_from_bytes, types::SvcServerInfo};
+
+    fn server_info(demo: &Demo) -> SvcServerInfo {
+        demo.directory
+            .entries
+            .iter()
+            .flat_map(|entry| &entry.frames)
+            .filter_map(|frame| match &frame.frame_data {
+                FrameData::NetworkMessage(box_type) => box_type.1.messages.get_parsed_messages(),
+                _ => None,
+            })
+            .flatten()
+            .find_map(|message| match message.get_engine_message() {
+                Some(EngineMessage::SvcServerInfo(server_info)) => Some(server_info.clone()),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn checksum() {
+        let mut bsp = vec![];
+
+        bsp.extend(BSP_VERSION.to_le_bytes());
+
+        // entities right after the lump table, then one byte for each other lump
+        let data_start = 4 + BSP_LUMPS as i32 * 8;
+
+        bsp.extend(data_start.to_le_bytes());
+        bsp.extend(4i32.to_le_bytes());
+
+        for lump in 1..BSP_LUMPS as i32 {
+            bsp.extend((data_start + 3 + lump).to_le_bytes());
+            bsp.extend(1i32.to_le_bytes());
+        }
+
+        bsp.extend(b"{ }\n");
+        bsp.extend(1..BSP_LUMPS as u8);
+
+        assert_eq!(
+            bsp_checksum(&bsp).unwrap(),
+            crc32fast::hash(&(1..BSP_LUMPS as u8).collect::<Vec<u8>>())
+        );
+
+        // entities do not count
+        bsp[data_start as usize] = b'}';
+        assert_eq!(
+            bsp_checksum(&bsp).unwrap(),
+            crc32fast::hash(&(1..BSP_LUMPS as u8).collect::<Vec<u8>>())
+        );
+
+        assert!(bsp_checksum(&bsp[..20]).is_err());
+    }
+
+    #[test]
+    fn munge() {
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let server_info = server_info(&demo);
+
+        // the header has the plain checksum, server info the munged one
+        assert_eq!(demo.header.map_checksum, 0);
+        assert_eq!(server_info.player_index, 0);
+        assert_eq!(server_info.map_checksum as u32, 0xFF4058A5);
+        assert_eq!(munge_map_checksum(0, 0), 0xFF4058A5);
+        assert_eq!(unmunge_map_checksum(0xFF4058A5, 0), 0);
+
+        for player_index in [0, 1, 7, 31] {
+            let munged = munge_map_checksum(0xDEADBEEF, player_index);
+
+            assert_ne!(munged, 0xDEADBEEF);
+            assert_eq!(unmunge_map_checksum(munged, player_index), 0xDEADBEEF);
+        }
+    }
+
+    #[test]
+    fn retarget() {
+        let mut demo = open_demo("./src/tests/weapon.dem").unwrap();
+
+        demo.retarget_map("crossfire_b2", 0xDEADBEEF);
+
+        let demo = open_demo_from_bytes(&demo.write_to_bytes()).unwrap();
+        let server_info = server_info(&demo);
+
+        assert_eq!(demo.header.map_name.to_str(), Ok("crossfire_b2"));
+        assert_eq!(demo.header.map_checksum, 0xDEADBEEF);
+        assert_eq!(server_info.map_file_name, b"maps/crossfire_b2.bsp\0");
+        assert_eq!(
+            unmunge_map_checksum(server_info.map_checksum as u32, server_info.player_index),
+            0xDEADBEEF
+        );
+
+        let names: Vec<String> = demo
+            .directory
+            .entries
+            .iter()
+            .flat_map(|entry| &entry.frames)
+            .filter_map(|frame| match &frame.frame_data {
+                FrameData::NetworkMessage(box_type) => box_type.1.messages.get_parsed_messages(),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|message| match message.get_engine_message() {
+                Some(EngineMessage::SvcResourceList(resource_list)) => Some(resource_list),
+                _ => None,
+            })
+            .flat_map(|resource_list| &resource_list.resources)
+            .map(|resource| resource.name.clone())
+            .collect();
+
+        assert!(names.contains(&"maps/crossfire_b2.bsp\0".to_owned()));
+        assert!(!names.iter().any(|name| name.starts_with("maps/crossfire.")));
+        // not named after the map
+        assert!(names.contains(&"maps/default.txt\0".to_owned()));
+    }
+}
@@ -29,13 +29,13 @@ impl Doer for SvcClientData {
             None
         };
 
-        let client_data = parse_delta(aux.delta_decoders.get("clientdata_t\0").unwrap(), &mut br);
+        let client_data = parse_delta(get_decoder(aux, "clientdata_t\0", i)?, &mut br);
 
         // This is a vector unlike THE docs.
         let mut weapon_data: Vec<ClientDataWeaponData> = vec![];
         while br.read_1_bit() {
             let weapon_index = br.read_n_bit(6).to_u8();
-            let delta = parse_delta(aux.delta_decoders.get("weapon_data_t\0").unwrap(), &mut br);
+            let delta = parse_delta(get_decoder(aux, "weapon_data_t\0", i)?, &mut br);
 
             weapon_data.push(ClientDataWeaponData {
                 weapon_index,
@@ -51,8 +51,7 @@ impl Doer for SvcClientData {
 
         // Remember to write the last "false" bit.
 
-        let range = br.get_consumed_bytes();
-        let (i, _) = take(range)(i)?;
+        let (i, _) = br.take_consumed(i)?;
 
         Ok((
             i,
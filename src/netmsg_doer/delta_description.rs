@@ -1,6 +1,6 @@
 use std::str::from_utf8;
 
-use crate::types::{Delta, DeltaDecoder, DeltaDecoderS};
+use crate::types::{Delta, DeltaDecoder, DeltaDecoderS, DeltaValue};
 
 use super::*;
 
@@ -13,39 +13,52 @@ impl Doer for SvcDeltaDescription {
         let (i, name) = null_string(i)?;
         let (i, total_fields) = le_u16(i)?;
 
-        let clone = i;
-
         // Delta description is usually in LOADING section and first frame message.
         // It will detail the deltas being used and its index for correct decoding.
         // So this would be the only message that modifies the delta decode table.
 
         let mut br = BitReader::new(i);
+        let description_decoder = get_decoder(aux, "delta_description_t\0", i)?;
         let data: Vec<Delta> = (0..total_fields)
-            .map(|_| {
-                parse_delta(
-                    aux.delta_decoders.get("delta_description_t\0").unwrap(),
-                    &mut br,
-                )
-            })
+            .map(|_| parse_delta(description_decoder, &mut br))
             .collect();
 
-        let decoder: DeltaDecoder = data
+        let decoder: Option<DeltaDecoder> = data
             .iter()
-            .map(|entry| DeltaDecoderS {
-                name: entry.get("name").unwrap().get_str().to_owned(),
-                bits: entry.get("bits").unwrap().get_u32(),
-                divisor: entry.get("divisor").unwrap().get_f32(),
-                flags: entry.get("flags").unwrap().get_u32(),
+            .map(|entry| {
+                let (
+                    Some(DeltaValue::String(name)),
+                    Some(DeltaValue::IntUnsigned(bits)),
+                    Some(DeltaValue::FloatSigned(divisor) | DeltaValue::FloatUnsigned(divisor)),
+                    Some(DeltaValue::IntUnsigned(flags)),
+                ) = (
+                    entry.get("name"),
+                    entry.get("bits"),
+                    entry.get("divisor"),
+                    entry.get("flags"),
+                )
+                else {
+                    return None;
+                };
+
+                Some(DeltaDecoderS {
+                    name: name.to_owned(),
+                    bits: *bits,
+                    divisor: *divisor,
+                    flags: *flags,
+                })
             })
             .collect();
 
-        let range = br.get_consumed_bytes();
-        let clone = &clone[..range];
-        let (i, _) = take(range)(i)?;
+        let (i, clone) = br.take_consumed(i)?;
+
+        let (Some(decoder), Ok(name_str)) = (decoder, from_utf8(name)) else {
+            return context("Bad delta description", fail)(i);
+        };
 
         // mutate delta_decoders
         aux.delta_decoders
-            .insert(from_utf8(name).unwrap().to_owned(), decoder.clone());
+            .insert(name_str.to_owned(), decoder.clone());
 
         Ok((
             i,
@@ -27,10 +27,7 @@ impl Doer for SvcEvent {
                     None
                 };
                 let delta = if has_delta.is_some() && has_delta.unwrap() {
-                    Some(parse_delta(
-                        aux.delta_decoders.get("event_t\0").unwrap(),
-                        &mut br,
-                    ))
+                    Some(parse_delta(get_decoder(aux, "event_t\0", i)?, &mut br))
                 } else {
                     None
                 };
@@ -41,7 +38,7 @@ impl Doer for SvcEvent {
                     None
                 };
 
-                EventS {
+                Ok(EventS {
                     event_index,
                     has_packet_index,
                     packet_index,
@@ -49,12 +46,11 @@ impl Doer for SvcEvent {
                     delta,
                     has_fire_time,
                     fire_time,
-                }
+                })
             })
-            .collect();
+            .collect::<Result<_, _>>()?;
 
-        let range = br.get_consumed_bytes();
-        let (i, _) = take(range)(i)?;
+        let (i, _) = br.take_consumed(i)?;
 
         Ok((
             i,
@@ -9,7 +9,7 @@ impl Doer for SvcEventReliable {
         let mut br = BitReader::new(i);
 
         let event_index = br.read_n_bit(10).to_u16();
-        let event_args = parse_delta(aux.delta_decoders.get("event_t\0").unwrap(), &mut br);
+        let event_args = parse_delta(get_decoder(aux, "event_t\0", i)?, &mut br);
         let has_fire_time = br.read_1_bit();
         let fire_time = if has_fire_time {
             Some(br.read_n_bit(16).to_u16())
@@ -17,8 +17,7 @@ impl Doer for SvcEventReliable {
             None
         };
 
-        let range = br.get_consumed_bytes();
-        let (i, _) = take(range)(i)?;
+        let (i, _) = br.take_consumed(i)?;
 
         Ok((
             i,
@@ -19,16 +19,16 @@ use crate::{
     bit::BitWriter,
     delta::{parse_delta, write_delta},
     types::{
-        ByteVec, EngineMessage, SvcAddAngle, SvcCdTrack, SvcCenterPrint, SvcClientData,
-        SvcCrosshairAngle, SvcCustomization, SvcCutscene, SvcDecalName, SvcDeltaDescription,
-        SvcDeltaPacketEntities, SvcDirector, SvcDisconnect, SvcEvent, SvcEventReliable,
-        SvcFileTxferFailed, SvcFinale, SvcHltv, SvcLightStyle, SvcNewMovevars, SvcNewUserMsg,
-        SvcPacketEntities, SvcParticle, SvcPings, SvcPrint, SvcResourceList, SvcResourceLocation,
-        SvcResourceRequest, SvcRestore, SvcRoomType, SvcSendCvarValue, SvcSendCvarValue2,
-        SvcSendExtraInfo, SvcServerInfo, SvcSetAngle, SvcSetPause, SvcSetView, SvcSignOnNum,
-        SvcSound, SvcSoundFade, SvcSpawnBaseline, SvcSpawnStatic, SvcSpawnStaticSound,
-        SvcStopSound, SvcStuffText, SvcTempEntity, SvcTime, SvcTimeScale, SvcUpdateUserInfo,
-        SvcVersion, SvcVoiceData, SvcVoiceInit, SvcWeaponAnim,
+        ByteVec, DeltaDecoder, EngineMessage, SvcAddAngle, SvcCdTrack, SvcCenterPrint,
+        SvcClientData, SvcCrosshairAngle, SvcCustomization, SvcCutscene, SvcDecalName,
+        SvcDeltaDescription, SvcDeltaPacketEntities, SvcDirector, SvcDisconnect, SvcEvent,
+        SvcEventReliable, SvcFileTxferFailed, SvcFinale, SvcHltv, SvcLightStyle, SvcNewMovevars,
+        SvcNewUserMsg, SvcPacketEntities, SvcParticle, SvcPings, SvcPrint, SvcResourceList,
+        SvcResourceLocation, SvcResourceRequest, SvcRestore, SvcRoomType, SvcSendCvarValue,
+        SvcSendCvarValue2, SvcSendExtraInfo, SvcServerInfo, SvcSetAngle, SvcSetPause, SvcSetView,
+        SvcSignOnNum, SvcSound, SvcSoundFade, SvcSpawnBaseline, SvcSpawnStatic,
+        SvcSpawnStaticSound, SvcStopSound, SvcStuffText, SvcTempEntity, SvcTime, SvcTimeScale,
+        SvcUpdateUserInfo, SvcVersion, SvcVoiceData, SvcVoiceInit, SvcWeaponAnim,
     },
 };
 
@@ -86,6 +86,18 @@ mod voice_data;
 mod voice_init;
 mod weapon_anim;
 
+/// Decoder sent by [`SvcDeltaDescription`], failing if it has not been sent yet.
+fn get_decoder<'a, 'b>(
+    aux: &'b DemoGlobalState,
+    name: &str,
+    i: &'a [u8],
+) -> Result<&'b DeltaDecoder, nom::Err<nom::error::Error<&'a [u8]>>> {
+    match aux.delta_decoders.get(name) {
+        Some(decoder) => Ok(decoder),
+        None => context("Delta decoder is not sent yet", fail)(i).map(|(_, decoder)| decoder),
+    }
+}
+
 pub trait Doer {
     fn id(&self) -> u8;
     fn parse<'a>(i: &'a [u8], aux: &mut DemoGlobalState) -> NomResult<'a, Self>
@@ -144,6 +156,30 @@ impl NetMessage {
     }
 }
 
+impl DemoGlobalState {
+    /// Makes the same changes to the state as parsing the message does.
+    ///
+    /// Writing messages in order while applying them gives each message the state it was parsed
+    /// with, even when the demo has several segments.
+    pub fn apply(&mut self, message: &NetMessage) {
+        match message.get_engine_message() {
+            Some(EngineMessage::SvcServerInfo(info)) => self.max_client = info.max_players,
+            Some(EngineMessage::SvcDeltaDescription(description)) => {
+                self.delta_decoders.insert(
+                    String::from_utf8_lossy(&description.name).into_owned(),
+                    description.fields.clone(),
+                );
+            }
+            Some(EngineMessage::SvcNewUserMsg(user_msg)) => {
+                self.custom_messages
+                    .insert(user_msg.index, user_msg.clone());
+            }
+            Some(EngineMessage::SvcHltv(_)) => self.is_hltv = true,
+            _ => (),
+        }
+    }
+}
+
 impl UserMessage {
     fn parse<'a>(i: &'a [u8], id: u8, aux: &mut DemoGlobalState) -> NomResult<'a, UserMessage> {
         let custom_message = aux.custom_messages.get(&id);
@@ -53,17 +53,11 @@ impl Doer for SvcPacketEntities {
             let between = entity_index > 0 && entity_index <= aux.max_client as u16;
 
             let delta = if between {
-                parse_delta(
-                    aux.delta_decoders.get("entity_state_player_t\0").unwrap(),
-                    &mut br,
-                )
+                parse_delta(get_decoder(aux, "entity_state_player_t\0", i)?, &mut br)
             } else if has_custom_delta {
-                parse_delta(
-                    aux.delta_decoders.get("custom_entity_state_t\0").unwrap(),
-                    &mut br,
-                )
+                parse_delta(get_decoder(aux, "custom_entity_state_t\0", i)?, &mut br)
             } else {
-                parse_delta(aux.delta_decoders.get("entity_state_t\0").unwrap(), &mut br)
+                parse_delta(get_decoder(aux, "entity_state_t\0", i)?, &mut br)
             };
 
             entity_states.push(EntityState {
@@ -79,8 +73,7 @@ impl Doer for SvcPacketEntities {
             })
         }
 
-        let range = br.get_consumed_bytes();
-        let (i, _) = take(range)(i)?;
+        let (i, _) = br.take_consumed(i)?;
 
         Ok((
             i,
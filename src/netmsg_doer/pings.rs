@@ -31,7 +31,7 @@ impl Doer for SvcPings {
         });
 
         // Don't forget
-        let (i, _) = take(br.get_consumed_bytes())(i)?;
+        let (i, _) = br.take_consumed(i)?;
 
         Ok((i, SvcPings { pings }))
     }
@@ -64,7 +64,7 @@ impl Doer for SvcResourceList {
             }
         }
 
-        let (i, _) = take(br.get_consumed_bytes())(i)?;
+        let (i, _) = br.take_consumed(i)?;
 
         Ok((
             i,
@@ -50,7 +50,7 @@ impl Doer for SvcSound {
             1
         };
 
-        let (i, _) = take(br.get_consumed_bytes())(i)?;
+        let (i, _) = br.take_consumed(i)?;
 
         Ok((
             i,
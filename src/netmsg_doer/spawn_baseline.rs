@@ -11,7 +11,7 @@ impl Doer for SvcSpawnBaseline {
         let mut br = BitReader::new(i);
         let mut entities: Vec<EntityS> = vec![];
 
-        while br.peek_n_bits(16).to_u32() != (1 << 16) - 1 {
+        while !br.is_bad_read() && br.peek_n_bits(16).to_u32() != (1 << 16) - 1 {
             let index = br.read_n_bit(11).to_u16();
             let entity_index = index;
 
@@ -20,18 +20,12 @@ impl Doer for SvcSpawnBaseline {
 
             let delta = if type_ & 1 != 0 {
                 if between {
-                    parse_delta(
-                        aux.delta_decoders.get("entity_state_player_t\0").unwrap(),
-                        &mut br,
-                    )
+                    parse_delta(get_decoder(aux, "entity_state_player_t\0", i)?, &mut br)
                 } else {
-                    parse_delta(aux.delta_decoders.get("entity_state_t\0").unwrap(), &mut br)
+                    parse_delta(get_decoder(aux, "entity_state_t\0", i)?, &mut br)
                 }
             } else {
-                parse_delta(
-                    aux.delta_decoders.get("custom_entity_state_t\0").unwrap(),
-                    &mut br,
-                )
+                parse_delta(get_decoder(aux, "custom_entity_state_t\0", i)?, &mut br)
             };
 
             let res = EntityS {
@@ -49,13 +43,12 @@ impl Doer for SvcSpawnBaseline {
 
         let total_extra_data = br.read_n_bit(6).to_u8();
 
-        let extra_data_description = aux.delta_decoders.get("entity_state_t\0").unwrap();
+        let extra_data_description = get_decoder(aux, "entity_state_t\0", i)?;
         let extra_data: Vec<Delta> = (0..total_extra_data)
             .map(|_| parse_delta(extra_data_description, &mut br))
             .collect();
 
-        let range = br.get_consumed_bytes();
-        let (i, _) = take(range)(i)?;
+        let (i, _) = br.take_consumed(i)?;
 
         Ok((
             i,
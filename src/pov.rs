@@ -0,0 +1,521 @@
+//! Turning an HLTV demo into a first-person demo of one of the players.
+//!
+//! HLTV demos have the entity state of every player but no view or client data of their own.
+//! The view of the chosen player is rebuilt from their `entity_state_player_t`, so it moves with
+//! the network rate of the server and has no view bob or punch.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let demo = open_demo("./src/tests/hltv.dem").unwrap();
+//!
+//! let pov = demo.switch_pov(&PovOptions::new(3)).unwrap();
+//! pov.write_to_file("./src/tests/player3.dem").unwrap();
+//! ```
+use std::collections::HashMap;
+
+use crate::{
+    delta::{delta_number, delta_value},
+    error::DemoError,
+    snapshot::{EntitySnapshot, SnapshotTracker},
+    types::{
+        ClientData, Delta, Demo, DemoState, EngineMessage, Frame, FrameData, MessageData,
+        NetworkMessage, SvcClientData, SvcResourceList,
+    },
+};
+
+/// `t_model` in `resourcetype_t`.
+const RESOURCE_MODEL: u8 = 2;
+/// `FL_DUCKING`
+const FLAG_DUCKING: f32 = (1 << 14) as f32;
+
+#[derive(Debug, Clone)]
+pub struct PovOptions {
+    /// Player slot, starting from 0. The entity index is one more.
+    pub player_index: u8,
+    /// Eye height above the origin when standing.
+    pub view_height: f32,
+    /// Eye height above the origin when ducking.
+    pub duck_view_height: f32,
+    /// Field of view of the [`ClientData`] frames. HLTV does not send the player's own.
+    pub fov: f32,
+}
+
+impl PovOptions {
+    /// Uses the eye heights of Half-Life. Counter-Strike stands at 17.
+    pub fn new(player_index: u8) -> Self {
+        Self {
+            player_index,
+            view_height: 28.,
+            duck_view_height: 12.,
+            fov: 90.,
+        }
+    }
+}
+
+/// What is known about the player at a frame.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PlayerView {
+    pub(crate) origin: [f32; 3],
+    pub(crate) view_angles: [f32; 3],
+    pub(crate) view_height: f32,
+    pub(crate) is_ducking: bool,
+    /// Index of the `v_` model in the model precache.
+    pub(crate) viewmodel: Option<u16>,
+}
+
+impl Demo {
+    /// Rewrites the demo so it plays back from the eyes of the player.
+    ///
+    /// [`crate::types::RefParams`], [`crate::types::UserCmd::view_angles`], [`ClientData`]
+    /// frames, [`crate::types::SvcSetView`] and [`SvcClientData`] follow the player. The view
+    /// model is the `v_` model of the `p_` weapon model the player holds. [`crate::types::SvcHltv`]
+    /// and [`crate::types::SvcDirector`] are removed so the client does not play it as a
+    /// spectator.
+    ///
+    /// Frames before the player first shows up are left as they are. [`ClientData::weapon_bits`]
+    /// of new frames is 0 as HLTV does not know the weapons of a player.
+    ///
+    /// Returns [`DemoError::NotHltv`] if the demo is not from HLTV.
+    pub fn switch_pov(&self, options: &PovOptions) -> Result<Demo, DemoError> {
+        if !self.is_hltv() {
+            return Err(DemoError::NotHltv);
+        }
+
+        let entity_index = options.player_index as u16 + 1;
+
+        let mut demo = self.clone();
+        let mut tracker = SnapshotTracker::new();
+        let mut state = DemoState::new_raw();
+        // `p_` model index to `v_` model index
+        let mut viewmodels: HashMap<u16, u16> = HashMap::new();
+        let mut view: Option<PlayerView> = None;
+        let mut has_found_player = false;
+
+        for entry in &mut demo.directory.entries {
+            let needs_client_data = !entry.is_loading()
+                && !entry
+                    .frames
+                    .iter()
+                    .any(|frame| matches!(frame.frame_data, FrameData::ClientData(_)));
+            let mut frames = Vec::with_capacity(entry.frames.len());
+
+            for mut frame in std::mem::take(&mut entry.frames) {
+                match &mut frame.frame_data {
+                    FrameData::NetworkMessage(box_type) => {
+                        let network_message = &mut box_type.1;
+
+                        let Some(messages) = network_message.messages.get_parsed_messages() else {
+                            return Err(DemoError::UnparsedMessages);
+                        };
+
+                        for message in messages {
+                            if let Some(EngineMessage::SvcResourceList(resource_list)) =
+                                message.get_engine_message()
+                            {
+                                viewmodels = find_viewmodels(resource_list);
+                            }
+                        }
+
+                        tracker.apply_network_message(network_message);
+
+                        if let Some(entity) = tracker.current().entities.get(&entity_index)
+                            && let Some(new_view) = player_view(entity, options, &viewmodels)
+                        {
+                            view = Some(new_view);
+                            has_found_player = true;
+                        }
+
+                        // playback has client data before every network message
+                        if needs_client_data && let Some(view) = view {
+                            frames.push(Frame {
+                                time: frame.time,
+                                frame: frame.frame,
+                                frame_data: FrameData::ClientData(ClientData {
+                                    origin: view.eye_origin().to_vec(),
+                                    viewangles: view.view_angles.to_vec(),
+                                    weapon_bits: 0,
+                                    fov: options.fov,
+                                }),
+                            });
+                        }
+
+                        rewrite_network_message(
+                            network_message,
+                            options,
+                            view.as_ref(),
+                            &mut state,
+                        );
+                    }
+                    FrameData::ClientData(client_data) => {
+                        if let Some(view) = view {
+                            client_data.origin = view.eye_origin().to_vec();
+                            client_data.viewangles = view.view_angles.to_vec();
+                            client_data.fov = options.fov;
+                        }
+                    }
+                    _ => (),
+                }
+
+                frames.push(frame);
+            }
+
+            entry.frame_count = frames.len() as i32;
+            entry.frames = frames;
+        }
+
+        if !has_found_player {
+            return Err(DemoError::PlayerNotFound);
+        }
+
+        Ok(demo)
+    }
+
+    /// True if any parsed message is [`crate::types::SvcHltv`], which is only sent to HLTV.
+    pub fn is_hltv(&self) -> bool {
+        self.directory
+            .entries
+            .iter()
+            .flat_map(|entry| &entry.frames)
+            .filter_map(|frame| match &frame.frame_data {
+                FrameData::NetworkMessage(box_type) => box_type.1.messages.get_parsed_messages(),
+                _ => None,
+            })
+            .flatten()
+            .any(|message| {
+                matches!(
+                    message.get_engine_message(),
+                    Some(EngineMessage::SvcHltv(_))
+                )
+            })
+    }
+}
+
+impl PlayerView {
+    pub(crate) fn eye_origin(&self) -> [f32; 3] {
+        [
+            self.origin[0],
+            self.origin[1],
+            self.origin[2] + self.view_height,
+        ]
+    }
+
+    pub(crate) fn client_data(&self, state: &DemoState) -> Delta {
+        let Some(decoder) = state.delta_decoders.get("clientdata_t\0") else {
+            return Delta::new();
+        };
+
+        let flags = if self.is_ducking { FLAG_DUCKING } else { 0. };
+        // not sent for entities, anything above 0 keeps the client from thinking it is dead
+        let health = 100.;
+
+        let fields = [
+            ("origin[0]\0", Some(self.origin[0])),
+            ("origin[1]\0", Some(self.origin[1])),
+            ("origin[2]\0", Some(self.origin[2])),
+            ("view_ofs[2]\0", Some(self.view_height)),
+            ("flags\0", Some(flags)),
+            ("bInDuck\0", Some(self.is_ducking as u8 as f32)),
+            ("health\0", Some(health)),
+            ("viewmodel\0", self.viewmodel.map(|index| index as f32)),
+        ];
+
+        fields
+            .into_iter()
+            .filter_map(|(name, value)| {
+                let description = decoder.iter().find(|field| field.name == name)?;
+
+                Some((name.to_owned(), delta_value(description, value?)))
+            })
+            .collect()
+    }
+}
+
+fn get_f32(delta: &Delta, key: &str) -> Option<f32> {
+    delta_number(delta.get(key)?)
+}
+
+fn player_view(
+    entity: &EntitySnapshot,
+    options: &PovOptions,
+    viewmodels: &HashMap<u16, u16>,
+) -> Option<PlayerView> {
+    let delta = &entity.state;
+    let (origin, view_angles) = entity_view(delta)?;
+
+    let is_ducking = get_f32(delta, "usehull\0").is_some_and(|hull| hull == 1.);
+
+    let viewmodel = get_f32(delta, "weaponmodel\0")
+        .and_then(|weaponmodel| viewmodels.get(&(weaponmodel as u16)))
+        .copied();
+
+    Some(PlayerView {
+        origin,
+        view_angles,
+        view_height: if is_ducking {
+            options.duck_view_height
+        } else {
+            options.view_height
+        },
+        is_ducking,
+        viewmodel,
+    })
+}
+
+/// Origin and view angles of a player entity. None if it has no origin yet.
+pub(crate) fn entity_view(delta: &Delta) -> Option<([f32; 3], [f32; 3])> {
+    let origin = ["origin[0]\0", "origin[1]\0", "origin[2]\0"].map(|key| get_f32(delta, key));
+
+    // fields still at 0 are never sent
+    if origin.iter().all(Option::is_none) {
+        return None;
+    }
+
+    let origin = origin.map(Option::unwrap_or_default);
+
+    // the model pitch is a third of the view pitch and upside down
+    let pitch = get_f32(delta, "angles[0]\0").unwrap_or_default();
+    let pitch = if pitch > 180. { pitch - 360. } else { pitch };
+
+    let view_angles = [
+        -pitch * 3.,
+        get_f32(delta, "angles[1]\0").unwrap_or_default(),
+        get_f32(delta, "angles[2]\0").unwrap_or_default(),
+    ];
+
+    Some((origin, view_angles))
+}
+
+/// Matches `models/p_ak47.mdl` to `models/v_ak47.mdl` by precache index.
+fn find_viewmodels(resource_list: &SvcResourceList) -> HashMap<u16, u16> {
+    let models: HashMap<&str, u16> = resource_list
+        .resources
+        .iter()
+        .filter(|resource| resource.type_ == RESOURCE_MODEL)
+        .map(|resource| (resource.name.as_str(), resource.index))
+        .collect();
+
+    models
+        .iter()
+        .filter_map(|(name, &index)| {
+            let (directory, file) = name.rsplit_once('/').unwrap_or(("", name));
+            let weapon = file.strip_prefix("p_")?;
+
+            let viewmodel = if directory.is_empty() {
+                format!("v_{weapon}")
+            } else {
+                format!("{directory}/v_{weapon}")
+            };
+
+            Some((index, *models.get(viewmodel.as_str())?))
+        })
+        .collect()
+}
+
+fn rewrite_network_message(
+    network_message: &mut NetworkMessage,
+    options: &PovOptions,
+    view: Option<&PlayerView>,
+    state: &mut DemoState,
+) {
+    let entity_index = options.player_index as u16 + 1;
+
+    if let Some(view) = view {
+        let info = &mut network_message.info;
+        let refparams = &mut info.refparams;
+        let eye_origin = view.eye_origin().to_vec();
+        let view_angles = view.view_angles.to_vec();
+
+        refparams.view_origin = eye_origin.clone();
+        refparams.view_angles = view_angles.clone();
+        refparams.cl_viewangles = view_angles.clone();
+        refparams.sim_org = view.origin.to_vec();
+        refparams.view_height = vec![0., 0., view.view_height];
+        refparams.view_entity = entity_index as i32;
+        refparams.player_num = options.player_index as i32;
+        refparams.spectator = 0;
+
+        info.usercmd.view_angles = view_angles;
+        info.view = eye_origin;
+
+        if let Some(viewmodel) = view.viewmodel {
+            info.viewmodel = viewmodel as i32;
+        }
+    }
+
+    let MessageData::Parsed(messages) = &mut network_message.messages else {
+        return;
+    };
+
+    let mut message_length = 0;
+
+    messages.retain_mut(|message| {
+        match message.get_engine_message_mut() {
+            Some(EngineMessage::SvcHltv(_) | EngineMessage::SvcDirector(_)) => return false,
+            Some(EngineMessage::SvcSetView(set_view)) => {
+                set_view.entity_index = entity_index as i16;
+            }
+            Some(EngineMessage::SvcClientData(client_data)) => {
+                if let Some(view) = view {
+                    *client_data = SvcClientData {
+                        has_delta_update_mask: false,
+                        delta_update_mask: None,
+                        client_data: view.client_data(state),
+                        weapon_data: None,
+                    };
+                }
+            }
+            _ => (),
+        }
+
+        message_length += message.write(state).len() as u32;
+        state.apply(message);
+
+        true
+    });
+
+    network_message.message_length = message_length;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        open_demo, open_demo_from_bytes,
+        types::{NetMessage, Resource, SvcHltv},
+    };
+
+    /// weapon.dem as if HLTV recorded it.
+    fn hltv_demo() -> Demo {
+        let mut demo = open_demo("./src/tests/weapon.dem").unwrap();
+
+        let messages = demo.directory.entries[0]
+            .frames
+            .iter_mut()
+            .find_map(|frame| match &mut frame.frame_data {
+                FrameData::NetworkMessage(box_type) => {
+                    box_type.1.messages.get_parsed_messages_mut()
+                }
+                _ => None,
+            })
+            .unwrap();
+
+        messages.insert(
+            0,
+            NetMessage::EngineMessage(Box::new(EngineMessage::SvcHltv(SvcHltv { mode: 1 }))),
+        );
+
+        demo
+    }
+
+    #[test]
+    fn not_hltv() {
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+
+        assert!(!demo.is_hltv());
+        assert!(matches!(
+            demo.switch_pov(&PovOptions::new(7)),
+            Err(DemoError::NotHltv)
+        ));
+    }
+
+    #[test]
+    fn follow_player() {
+        let demo = hltv_demo();
+
+        assert!(demo.is_hltv());
+
+        // the recorder itself never has an origin in its entity
+        assert!(matches!(
+            demo.switch_pov(&PovOptions::new(0)),
+            Err(DemoError::PlayerNotFound)
+        ));
+
+        let pov = demo
+            .switch_pov(&PovOptions {
+                fov: 100.,
+                ..PovOptions::new(7)
+            })
+            .unwrap();
+        let pov = open_demo_from_bytes(&pov.write_to_bytes()).unwrap();
+
+        // the spectator messages are gone
+        assert!(!pov.is_hltv());
+
+        let client_data: Vec<&ClientData> = pov.directory.entries[1]
+            .frames
+            .iter()
+            .filter_map(|frame| match &frame.frame_data {
+                FrameData::ClientData(client_data) => Some(client_data),
+                _ => None,
+            })
+            .collect();
+
+        assert!(!client_data.is_empty());
+        assert!(
+            client_data
+                .iter()
+                .all(|client_data| client_data.fov == 100.)
+        );
+
+        let last_info = pov.directory.entries[1]
+            .frames
+            .iter()
+            .rev()
+            .find_map(|frame| match &frame.frame_data {
+                FrameData::NetworkMessage(box_type) => Some(box_type.1.info.clone()),
+                _ => None,
+            })
+            .unwrap();
+
+        assert_eq!(last_info.refparams.view_entity, 8);
+
+        let set_views: Vec<i16> = pov
+            .directory
+            .entries
+            .iter()
+            .flat_map(|entry| &entry.frames)
+            .filter_map(|frame| match &frame.frame_data {
+                FrameData::NetworkMessage(box_type) => box_type.1.messages.get_parsed_messages(),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|message| match message.get_engine_message() {
+                Some(EngineMessage::SvcSetView(set_view)) => Some(set_view.entity_index),
+                _ => None,
+            })
+            .collect();
+
+        assert!(!set_views.is_empty());
+        assert!(set_views.iter().all(|&index| index == 8));
+    }
+
+    #[test]
+    fn viewmodel_names() {
+        let resource = |name: &str, index| Resource {
+            type_: RESOURCE_MODEL,
+            name: name.to_owned(),
+            index,
+            size: 0,
+            flags: 0,
+            md5_hash: None,
+            has_extra_info: false,
+            extra_info: None,
+        };
+
+        let viewmodels = find_viewmodels(&SvcResourceList {
+            resource_count: 4,
+            resources: vec![
+                resource("models/v_ak47.mdl", 1),
+                resource("models/p_ak47.mdl", 2),
+                resource("models/shield/v_shield_usp.mdl", 3),
+                resource("models/shield/p_shield_usp.mdl", 4),
+            ],
+            consistencies: vec![],
+        });
+
+        assert_eq!(viewmodels.get(&2), Some(&1));
+        assert_eq!(viewmodels.get(&4), Some(&3));
+        assert_eq!(viewmodels.len(), 2);
+    }
+}
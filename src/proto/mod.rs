@@ -0,0 +1,3429 @@
+//! Demos as protocol buffers, for programs that are not written in Rust.
+//!
+//! The schema is `proto/dem.proto`, also in [`SCHEMA`], with package `dem.v1`. Its messages
+//! follow [`crate::types`] field for field: [`FrameData`], [`EngineMessage`] and
+//! [`TempEntity`] are `oneof`s, and a [`Delta`] is a map from field name to a value tagged with
+//! its type. The Rust side of the schema is in [`v1`].
+//!
+//! A demo goes through [`Demo::to_proto`] and [`Demo::from_proto`]. For a stream, each
+//! [`Frame`] converts on its own with [`From`] and [`TryFrom`]. Reading fails with
+//! [`DemoError::InvalidProto`] when a number does not fit its demo type or a message the demo
+//! needs is missing, such as the info of a network frame.
+//!
+//! Lengths and offsets are computed again when writing, like with JSON, so
+//! [`Demo::write_to_bytes`] of a converted demo gives back the original file.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let demo = open_demo("./src/tests/demotest.dem").unwrap();
+//! let bytes = demo.to_proto_bytes();
+//!
+//! // on the other side
+//! let demo = Demo::from_proto_bytes(&bytes).unwrap();
+//! demo.write_to_file("./src/tests/demo2test.dem").unwrap();
+//! ```
+use prost::Message as _;
+
+use crate::{
+    error::DemoError,
+    types::{
+        self, Delta, DeltaValue, Demo, EngineMessage, Frame, FrameData, MessageData,
+        NetworkMessageType, TempEntity,
+    },
+};
+
+pub mod v1;
+
+/// `proto/dem.proto`
+pub const SCHEMA: &str = include_str!("../../proto/dem.proto");
+/// `Demo.schema_version` of the messages in [`v1`].
+pub const SCHEMA_VERSION: u32 = 1;
+
+impl Demo {
+    pub fn to_proto(&self) -> v1::Demo {
+        v1::Demo {
+            schema_version: SCHEMA_VERSION,
+            header: Some((&self.header).into()),
+            entries: self.directory.entries.iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Reads a demo written by [`Demo::to_proto`], or by another program with the same schema
+    /// version.
+    pub fn from_proto(demo: v1::Demo) -> Result<Self, DemoError> {
+        if demo.schema_version != SCHEMA_VERSION {
+            return Err(DemoError::UnsupportedSchema {
+                version: demo.schema_version,
+            });
+        }
+
+        Ok(Self {
+            header: required(demo.header)?,
+            directory: types::Directory {
+                entries: all(demo.entries)?,
+            },
+            _state: None,
+        })
+    }
+
+    pub fn to_proto_bytes(&self) -> Vec<u8> {
+        self.to_proto().encode_to_vec()
+    }
+
+    pub fn from_proto_bytes(bytes: &[u8]) -> Result<Self, DemoError> {
+        Self::from_proto(v1::Demo::decode(bytes)?)
+    }
+}
+
+impl From<&Frame> for v1::Frame {
+    fn from(value: &Frame) -> Self {
+        use v1::frame::Data;
+
+        let data = match &value.frame_data {
+            FrameData::NetworkMessage(box_type) => {
+                Data::NetworkMessage(Box::new(box_type.as_ref().into()))
+            }
+            FrameData::DemoStart => Data::DemoStart(v1::Empty {}),
+            FrameData::ConsoleCommand(command) => Data::ConsoleCommand(command.into()),
+            FrameData::ClientData(client_data) => Data::ClientData(client_data.into()),
+            FrameData::NextSection => Data::NextSection(v1::Empty {}),
+            FrameData::Event(event) => Data::Event(event.into()),
+            FrameData::WeaponAnimation(animation) => Data::WeaponAnimation(animation.into()),
+            FrameData::Sound(sound) => Data::Sound(sound.into()),
+            FrameData::DemoBuffer(buffer) => Data::DemoBuffer(buffer.into()),
+        };
+
+        Self {
+            time: value.time,
+            frame: value.frame,
+            data: Some(data),
+        }
+    }
+}
+
+impl TryFrom<v1::Frame> for Frame {
+    type Error = DemoError;
+
+    fn try_from(value: v1::Frame) -> Result<Self, Self::Error> {
+        use v1::frame::Data;
+
+        let frame_data = match value.data.ok_or(DemoError::InvalidProto)? {
+            Data::NetworkMessage(network_message) => {
+                FrameData::NetworkMessage(Box::new((*network_message).try_into()?))
+            }
+            Data::DemoStart(_) => FrameData::DemoStart,
+            Data::ConsoleCommand(command) => FrameData::ConsoleCommand(command.try_into()?),
+            Data::ClientData(client_data) => FrameData::ClientData(client_data.try_into()?),
+            Data::NextSection(_) => FrameData::NextSection,
+            Data::Event(event) => FrameData::Event(event.try_into()?),
+            Data::WeaponAnimation(animation) => FrameData::WeaponAnimation(animation.try_into()?),
+            Data::Sound(sound) => FrameData::Sound(sound.try_into()?),
+            Data::DemoBuffer(buffer) => FrameData::DemoBuffer(buffer.try_into()?),
+        };
+
+        Ok(Self {
+            time: value.time,
+            frame: value.frame,
+            frame_data,
+        })
+    }
+}
+
+impl From<&(NetworkMessageType, types::NetworkMessage)> for v1::NetworkMessage {
+    fn from((message_type, value): &(NetworkMessageType, types::NetworkMessage)) -> Self {
+        use v1::network_message::Messages;
+
+        let frame_type = match message_type {
+            NetworkMessageType::Start => 0,
+            NetworkMessageType::Normal => 1,
+            NetworkMessageType::Unknown(frame_type) => *frame_type as u32,
+        };
+
+        let messages = match &value.messages {
+            MessageData::Parsed(messages) => Some(Messages::Parsed(v1::NetMessages {
+                messages: messages.iter().map(Into::into).collect(),
+            })),
+            MessageData::Raw(bytes) => Some(Messages::Raw(bytes.clone())),
+            MessageData::None => None,
+        };
+
+        Self {
+            frame_type,
+            info: Some((&value.info).into()),
+            sequence_info: Some((&value.sequence_info).into()),
+            message_length: value.message_length,
+            messages,
+        }
+    }
+}
+
+impl TryFrom<v1::NetworkMessage> for (NetworkMessageType, types::NetworkMessage) {
+    type Error = DemoError;
+
+    fn try_from(value: v1::NetworkMessage) -> Result<Self, Self::Error> {
+        use v1::network_message::Messages;
+
+        let message_type = NetworkMessageType::try_from(narrow::<u8, _>(value.frame_type)?)
+            .map_err(|_| DemoError::InvalidProto)?;
+
+        let messages = match value.messages {
+            Some(Messages::Parsed(messages)) => MessageData::Parsed(all(messages.messages)?),
+            Some(Messages::Raw(bytes)) => MessageData::Raw(bytes),
+            None => MessageData::None,
+        };
+
+        Ok((
+            message_type,
+            types::NetworkMessage {
+                info: required(value.info)?,
+                sequence_info: required(value.sequence_info)?,
+                message_length: value.message_length,
+                messages,
+            },
+        ))
+    }
+}
+
+impl From<&types::NetMessage> for v1::NetMessage {
+    fn from(value: &types::NetMessage) -> Self {
+        use v1::net_message::Message;
+
+        let message = match value {
+            types::NetMessage::UserMessage(message) => Message::UserMessage(v1::UserMessage {
+                id: message.id.into(),
+                name: message.name.0.clone(),
+                data: message.data.clone(),
+            }),
+            types::NetMessage::EngineMessage(message) => {
+                Message::EngineMessage(message.as_ref().into())
+            }
+        };
+
+        Self {
+            message: Some(message),
+        }
+    }
+}
+
+impl TryFrom<v1::NetMessage> for types::NetMessage {
+    type Error = DemoError;
+
+    fn try_from(value: v1::NetMessage) -> Result<Self, Self::Error> {
+        use v1::net_message::Message;
+
+        Ok(match value.message.ok_or(DemoError::InvalidProto)? {
+            Message::UserMessage(message) => Self::UserMessage(types::UserMessage {
+                id: narrow(message.id)?,
+                name: message.name.into(),
+                data: message.data,
+            }),
+            Message::EngineMessage(message) => Self::EngineMessage(Box::new(message.try_into()?)),
+        })
+    }
+}
+
+impl From<&EngineMessage> for v1::EngineMessage {
+    fn from(value: &EngineMessage) -> Self {
+        use v1::engine_message::Message;
+
+        let message = match value {
+            EngineMessage::SvcBad => Message::SvcBad(v1::Empty {}),
+            EngineMessage::SvcNop => Message::SvcNop(v1::Empty {}),
+            EngineMessage::SvcDisconnect(message) => Message::SvcDisconnect(message.into()),
+            EngineMessage::SvcEvent(message) => Message::SvcEvent(message.into()),
+            EngineMessage::SvcVersion(message) => Message::SvcVersion(message.into()),
+            EngineMessage::SvcSetView(message) => Message::SvcSetView(message.into()),
+            EngineMessage::SvcSound(message) => Message::SvcSound(message.as_ref().into()),
+            EngineMessage::SvcTime(message) => Message::SvcTime(message.into()),
+            EngineMessage::SvcPrint(message) => Message::SvcPrint(message.into()),
+            EngineMessage::SvcStuffText(message) => Message::SvcStuffText(message.into()),
+            EngineMessage::SvcSetAngle(message) => Message::SvcSetAngle(message.into()),
+            EngineMessage::SvcServerInfo(message) => Message::SvcServerInfo(message.into()),
+            EngineMessage::SvcLightStyle(message) => Message::SvcLightStyle(message.into()),
+            EngineMessage::SvcUpdateUserInfo(message) => Message::SvcUpdateUserInfo(message.into()),
+            EngineMessage::SvcDeltaDescription(message) => {
+                Message::SvcDeltaDescription(message.into())
+            }
+            EngineMessage::SvcClientData(message) => Message::SvcClientData(message.into()),
+            EngineMessage::SvcStopSound(message) => Message::SvcStopSound(message.into()),
+            EngineMessage::SvcPings(message) => Message::SvcPings(message.into()),
+            EngineMessage::SvcParticle(message) => Message::SvcParticle(message.into()),
+            EngineMessage::SvcDamage => Message::SvcDamage(v1::Empty {}),
+            EngineMessage::SvcSpawnStatic(message) => Message::SvcSpawnStatic(message.into()),
+            EngineMessage::SvcEventReliable(message) => Message::SvcEventReliable(message.into()),
+            EngineMessage::SvcSpawnBaseline(message) => Message::SvcSpawnBaseline(message.into()),
+            EngineMessage::SvcTempEntity(message) => Message::SvcTempEntity(message.into()),
+            EngineMessage::SvcSetPause(message) => Message::SvcSetPause(message.into()),
+            EngineMessage::SvcSignOnNum(message) => Message::SvcSignOnNum(message.into()),
+            EngineMessage::SvcCenterPrint(message) => Message::SvcCenterPrint(message.into()),
+            EngineMessage::SvcKilledMonster => Message::SvcKilledMonster(v1::Empty {}),
+            EngineMessage::SvcFoundSecret => Message::SvcFoundSecret(v1::Empty {}),
+            EngineMessage::SvcSpawnStaticSound(message) => {
+                Message::SvcSpawnStaticSound(message.into())
+            }
+            EngineMessage::SvcIntermission => Message::SvcIntermission(v1::Empty {}),
+            EngineMessage::SvcFinale(message) => Message::SvcFinale(message.into()),
+            EngineMessage::SvcCdTrack(message) => Message::SvcCdTrack(message.into()),
+            EngineMessage::SvcRestore(message) => Message::SvcRestore(message.into()),
+            EngineMessage::SvcCutscene(message) => Message::SvcCutscene(message.into()),
+            EngineMessage::SvcWeaponAnim(message) => Message::SvcWeaponAnim(message.into()),
+            EngineMessage::SvcDecalName(message) => Message::SvcDecalName(message.into()),
+            EngineMessage::SvcRoomType(message) => Message::SvcRoomType(message.into()),
+            EngineMessage::SvcAddAngle(message) => Message::SvcAddAngle(message.into()),
+            EngineMessage::SvcNewUserMsg(message) => Message::SvcNewUserMsg(message.into()),
+            EngineMessage::SvcPacketEntities(message) => Message::SvcPacketEntities(message.into()),
+            EngineMessage::SvcDeltaPacketEntities(message) => {
+                Message::SvcDeltaPacketEntities(message.into())
+            }
+            EngineMessage::SvcChoke => Message::SvcChoke(v1::Empty {}),
+            EngineMessage::SvcResourceList(message) => Message::SvcResourceList(message.into()),
+            EngineMessage::SvcNewMovevars(message) => Message::SvcNewMovevars(message.into()),
+            EngineMessage::SvcResourceRequest(message) => {
+                Message::SvcResourceRequest(message.into())
+            }
+            EngineMessage::SvcCustomization(message) => Message::SvcCustomization(message.into()),
+            EngineMessage::SvcCrosshairAngle(message) => Message::SvcCrosshairAngle(message.into()),
+            EngineMessage::SvcSoundFade(message) => Message::SvcSoundFade(message.into()),
+            EngineMessage::SvcFileTxferFailed(message) => {
+                Message::SvcFileTxferFailed(message.into())
+            }
+            EngineMessage::SvcHltv(message) => Message::SvcHltv(message.into()),
+            EngineMessage::SvcDirector(message) => Message::SvcDirector(message.into()),
+            EngineMessage::SvcVoiceInit(message) => Message::SvcVoiceInit(message.into()),
+            EngineMessage::SvcVoiceData(message) => Message::SvcVoiceData(message.into()),
+            EngineMessage::SvcSendExtraInfo(message) => Message::SvcSendExtraInfo(message.into()),
+            EngineMessage::SvcTimeScale(message) => Message::SvcTimeScale(message.into()),
+            EngineMessage::SvcResourceLocation(message) => {
+                Message::SvcResourceLocation(message.into())
+            }
+            EngineMessage::SvcSendCvarValue(message) => Message::SvcSendCvarValue(message.into()),
+            EngineMessage::SvcSendCvarValue2(message) => Message::SvcSendCvarValue2(message.into()),
+        };
+
+        Self {
+            message: Some(message),
+        }
+    }
+}
+
+impl TryFrom<v1::EngineMessage> for EngineMessage {
+    type Error = DemoError;
+
+    fn try_from(value: v1::EngineMessage) -> Result<Self, Self::Error> {
+        use v1::engine_message::Message;
+
+        Ok(match value.message.ok_or(DemoError::InvalidProto)? {
+            Message::SvcBad(_) => EngineMessage::SvcBad,
+            Message::SvcNop(_) => EngineMessage::SvcNop,
+            Message::SvcDisconnect(message) => EngineMessage::SvcDisconnect(message.try_into()?),
+            Message::SvcEvent(message) => EngineMessage::SvcEvent(message.try_into()?),
+            Message::SvcVersion(message) => EngineMessage::SvcVersion(message.try_into()?),
+            Message::SvcSetView(message) => EngineMessage::SvcSetView(message.try_into()?),
+            Message::SvcSound(message) => EngineMessage::SvcSound(Box::new(message.try_into()?)),
+            Message::SvcTime(message) => EngineMessage::SvcTime(message.try_into()?),
+            Message::SvcPrint(message) => EngineMessage::SvcPrint(message.try_into()?),
+            Message::SvcStuffText(message) => EngineMessage::SvcStuffText(message.try_into()?),
+            Message::SvcSetAngle(message) => EngineMessage::SvcSetAngle(message.try_into()?),
+            Message::SvcServerInfo(message) => EngineMessage::SvcServerInfo(message.try_into()?),
+            Message::SvcLightStyle(message) => EngineMessage::SvcLightStyle(message.try_into()?),
+            Message::SvcUpdateUserInfo(message) => {
+                EngineMessage::SvcUpdateUserInfo(message.try_into()?)
+            }
+            Message::SvcDeltaDescription(message) => {
+                EngineMessage::SvcDeltaDescription(message.try_into()?)
+            }
+            Message::SvcClientData(message) => EngineMessage::SvcClientData(message.try_into()?),
+            Message::SvcStopSound(message) => EngineMessage::SvcStopSound(message.try_into()?),
+            Message::SvcPings(message) => EngineMessage::SvcPings(message.try_into()?),
+            Message::SvcParticle(message) => EngineMessage::SvcParticle(message.try_into()?),
+            Message::SvcDamage(_) => EngineMessage::SvcDamage,
+            Message::SvcSpawnStatic(message) => EngineMessage::SvcSpawnStatic(message.try_into()?),
+            Message::SvcEventReliable(message) => {
+                EngineMessage::SvcEventReliable(message.try_into()?)
+            }
+            Message::SvcSpawnBaseline(message) => {
+                EngineMessage::SvcSpawnBaseline(message.try_into()?)
+            }
+            Message::SvcTempEntity(message) => EngineMessage::SvcTempEntity(message.try_into()?),
+            Message::SvcSetPause(message) => EngineMessage::SvcSetPause(message.try_into()?),
+            Message::SvcSignOnNum(message) => EngineMessage::SvcSignOnNum(message.try_into()?),
+            Message::SvcCenterPrint(message) => EngineMessage::SvcCenterPrint(message.try_into()?),
+            Message::SvcKilledMonster(_) => EngineMessage::SvcKilledMonster,
+            Message::SvcFoundSecret(_) => EngineMessage::SvcFoundSecret,
+            Message::SvcSpawnStaticSound(message) => {
+                EngineMessage::SvcSpawnStaticSound(message.try_into()?)
+            }
+            Message::SvcIntermission(_) => EngineMessage::SvcIntermission,
+            Message::SvcFinale(message) => EngineMessage::SvcFinale(message.try_into()?),
+            Message::SvcCdTrack(message) => EngineMessage::SvcCdTrack(message.try_into()?),
+            Message::SvcRestore(message) => EngineMessage::SvcRestore(message.try_into()?),
+            Message::SvcCutscene(message) => EngineMessage::SvcCutscene(message.try_into()?),
+            Message::SvcWeaponAnim(message) => EngineMessage::SvcWeaponAnim(message.try_into()?),
+            Message::SvcDecalName(message) => EngineMessage::SvcDecalName(message.try_into()?),
+            Message::SvcRoomType(message) => EngineMessage::SvcRoomType(message.try_into()?),
+            Message::SvcAddAngle(message) => EngineMessage::SvcAddAngle(message.try_into()?),
+            Message::SvcNewUserMsg(message) => EngineMessage::SvcNewUserMsg(message.try_into()?),
+            Message::SvcPacketEntities(message) => {
+                EngineMessage::SvcPacketEntities(message.try_into()?)
+            }
+            Message::SvcDeltaPacketEntities(message) => {
+                EngineMessage::SvcDeltaPacketEntities(message.try_into()?)
+            }
+            Message::SvcChoke(_) => EngineMessage::SvcChoke,
+            Message::SvcResourceList(message) => {
+                EngineMessage::SvcResourceList(message.try_into()?)
+            }
+            Message::SvcNewMovevars(message) => EngineMessage::SvcNewMovevars(message.try_into()?),
+            Message::SvcResourceRequest(message) => {
+                EngineMessage::SvcResourceRequest(message.try_into()?)
+            }
+            Message::SvcCustomization(message) => {
+                EngineMessage::SvcCustomization(message.try_into()?)
+            }
+            Message::SvcCrosshairAngle(message) => {
+                EngineMessage::SvcCrosshairAngle(message.try_into()?)
+            }
+            Message::SvcSoundFade(message) => EngineMessage::SvcSoundFade(message.try_into()?),
+            Message::SvcFileTxferFailed(message) => {
+                EngineMessage::SvcFileTxferFailed(message.try_into()?)
+            }
+            Message::SvcHltv(message) => EngineMessage::SvcHltv(message.try_into()?),
+            Message::SvcDirector(message) => EngineMessage::SvcDirector(message.try_into()?),
+            Message::SvcVoiceInit(message) => EngineMessage::SvcVoiceInit(message.try_into()?),
+            Message::SvcVoiceData(message) => EngineMessage::SvcVoiceData(message.try_into()?),
+            Message::SvcSendExtraInfo(message) => {
+                EngineMessage::SvcSendExtraInfo(message.try_into()?)
+            }
+            Message::SvcTimeScale(message) => EngineMessage::SvcTimeScale(message.try_into()?),
+            Message::SvcResourceLocation(message) => {
+                EngineMessage::SvcResourceLocation(message.try_into()?)
+            }
+            Message::SvcSendCvarValue(message) => {
+                EngineMessage::SvcSendCvarValue(message.try_into()?)
+            }
+            Message::SvcSendCvarValue2(message) => {
+                EngineMessage::SvcSendCvarValue2(message.try_into()?)
+            }
+        })
+    }
+}
+
+impl From<&TempEntity> for v1::TempEntity {
+    fn from(value: &TempEntity) -> Self {
+        use v1::temp_entity::Entity;
+
+        let entity = match value {
+            TempEntity::TeBeamPoints(entity) => Entity::TeBeamPoints(entity.into()),
+            TempEntity::TeBeamEntPoint(bytes) => Entity::TeBeamEntPoint(bytes.clone()),
+            TempEntity::TeGunshot(bytes) => Entity::TeGunshot(bytes.clone()),
+            TempEntity::TeExplosion(bytes) => Entity::TeExplosion(bytes.clone()),
+            TempEntity::TeTarExplosion(bytes) => Entity::TeTarExplosion(bytes.clone()),
+            TempEntity::TeSmoke(bytes) => Entity::TeSmoke(bytes.clone()),
+            TempEntity::TeTracer(bytes) => Entity::TeTracer(bytes.clone()),
+            TempEntity::TeLightning(bytes) => Entity::TeLightning(bytes.clone()),
+            TempEntity::TeBeamEnts(bytes) => Entity::TeBeamEnts(bytes.clone()),
+            TempEntity::TeSparks(bytes) => Entity::TeSparks(bytes.clone()),
+            TempEntity::TeLavaSplash(bytes) => Entity::TeLavaSplash(bytes.clone()),
+            TempEntity::TeTeleport(bytes) => Entity::TeTeleport(bytes.clone()),
+            TempEntity::TeExplosion2(bytes) => Entity::TeExplosion2(bytes.clone()),
+            TempEntity::TeBspDecal(entity) => Entity::TeBspDecal(entity.into()),
+            TempEntity::TeImplosion(bytes) => Entity::TeImplosion(bytes.clone()),
+            TempEntity::TeSpriteTrail(bytes) => Entity::TeSpriteTrail(bytes.clone()),
+            TempEntity::TeSprite(bytes) => Entity::TeSprite(bytes.clone()),
+            TempEntity::TeBeamSprite(bytes) => Entity::TeBeamSprite(bytes.clone()),
+            TempEntity::TeBeamTorus(bytes) => Entity::TeBeamTorus(bytes.clone()),
+            TempEntity::TeBeamDisk(bytes) => Entity::TeBeamDisk(bytes.clone()),
+            TempEntity::TeBeamCylinder(bytes) => Entity::TeBeamCylinder(bytes.clone()),
+            TempEntity::TeBeamFollow(bytes) => Entity::TeBeamFollow(bytes.clone()),
+            TempEntity::TeGlowSprite(bytes) => Entity::TeGlowSprite(bytes.clone()),
+            TempEntity::TeBeamRing(bytes) => Entity::TeBeamRing(bytes.clone()),
+            TempEntity::TeStreakSplash(bytes) => Entity::TeStreakSplash(bytes.clone()),
+            TempEntity::TeDLight(bytes) => Entity::TeDLight(bytes.clone()),
+            TempEntity::TeELight(bytes) => Entity::TeELight(bytes.clone()),
+            TempEntity::TeTextMessage(entity) => Entity::TeTextMessage(entity.into()),
+            TempEntity::TeLine(bytes) => Entity::TeLine(bytes.clone()),
+            TempEntity::TeBox(bytes) => Entity::TeBox(bytes.clone()),
+            TempEntity::TeKillBeam(bytes) => Entity::TeKillBeam(bytes.clone()),
+            TempEntity::TeLargeFunnel(bytes) => Entity::TeLargeFunnel(bytes.clone()),
+            TempEntity::TeBloodStream(bytes) => Entity::TeBloodStream(bytes.clone()),
+            TempEntity::TeShowLine(bytes) => Entity::TeShowLine(bytes.clone()),
+            TempEntity::TeBlood(bytes) => Entity::TeBlood(bytes.clone()),
+            TempEntity::TeDecal(bytes) => Entity::TeDecal(bytes.clone()),
+            TempEntity::TeFizz(bytes) => Entity::TeFizz(bytes.clone()),
+            TempEntity::TeModel(bytes) => Entity::TeModel(bytes.clone()),
+            TempEntity::TeExplodeModel(bytes) => Entity::TeExplodeModel(bytes.clone()),
+            TempEntity::TeBreakModel(bytes) => Entity::TeBreakModel(bytes.clone()),
+            TempEntity::TeGunshotDecal(bytes) => Entity::TeGunshotDecal(bytes.clone()),
+            TempEntity::TeSpriteSpray(bytes) => Entity::TeSpriteSpray(bytes.clone()),
+            TempEntity::TeArmorRicochet(bytes) => Entity::TeArmorRicochet(bytes.clone()),
+            TempEntity::TePlayerDecal(bytes) => Entity::TePlayerDecal(bytes.clone()),
+            TempEntity::TeBubbles(bytes) => Entity::TeBubbles(bytes.clone()),
+            TempEntity::TeBubbleTrail(bytes) => Entity::TeBubbleTrail(bytes.clone()),
+            TempEntity::TeBloodSprite(bytes) => Entity::TeBloodSprite(bytes.clone()),
+            TempEntity::TeWorldDecal(bytes) => Entity::TeWorldDecal(bytes.clone()),
+            TempEntity::TeWorldDecalHigh(bytes) => Entity::TeWorldDecalHigh(bytes.clone()),
+            TempEntity::TeDecalHigh(bytes) => Entity::TeDecalHigh(bytes.clone()),
+            TempEntity::TeProjectile(bytes) => Entity::TeProjectile(bytes.clone()),
+            TempEntity::TeSpray(bytes) => Entity::TeSpray(bytes.clone()),
+            TempEntity::TePlayerSprites(bytes) => Entity::TePlayerSprites(bytes.clone()),
+            TempEntity::TeParticleBurst(bytes) => Entity::TeParticleBurst(bytes.clone()),
+            TempEntity::TeFireField(bytes) => Entity::TeFireField(bytes.clone()),
+            TempEntity::TePlayerAttachment(bytes) => Entity::TePlayerAttachment(bytes.clone()),
+            TempEntity::TeKillPlayerAttachment(bytes) => {
+                Entity::TeKillPlayerAttachment(bytes.clone())
+            }
+            TempEntity::TeMultigunShot(bytes) => Entity::TeMultigunShot(bytes.clone()),
+            TempEntity::TeUserTracer(bytes) => Entity::TeUserTracer(bytes.clone()),
+        };
+
+        Self {
+            entity: Some(entity),
+        }
+    }
+}
+
+impl TryFrom<v1::TempEntity> for TempEntity {
+    type Error = DemoError;
+
+    fn try_from(value: v1::TempEntity) -> Result<Self, Self::Error> {
+        use v1::temp_entity::Entity;
+
+        Ok(match value.entity.ok_or(DemoError::InvalidProto)? {
+            Entity::TeBeamPoints(entity) => TempEntity::TeBeamPoints(entity.try_into()?),
+            Entity::TeBeamEntPoint(bytes) => TempEntity::TeBeamEntPoint(bytes),
+            Entity::TeGunshot(bytes) => TempEntity::TeGunshot(bytes),
+            Entity::TeExplosion(bytes) => TempEntity::TeExplosion(bytes),
+            Entity::TeTarExplosion(bytes) => TempEntity::TeTarExplosion(bytes),
+            Entity::TeSmoke(bytes) => TempEntity::TeSmoke(bytes),
+            Entity::TeTracer(bytes) => TempEntity::TeTracer(bytes),
+            Entity::TeLightning(bytes) => TempEntity::TeLightning(bytes),
+            Entity::TeBeamEnts(bytes) => TempEntity::TeBeamEnts(bytes),
+            Entity::TeSparks(bytes) => TempEntity::TeSparks(bytes),
+            Entity::TeLavaSplash(bytes) => TempEntity::TeLavaSplash(bytes),
+            Entity::TeTeleport(bytes) => TempEntity::TeTeleport(bytes),
+            Entity::TeExplosion2(bytes) => TempEntity::TeExplosion2(bytes),
+            Entity::TeBspDecal(entity) => TempEntity::TeBspDecal(entity.try_into()?),
+            Entity::TeImplosion(bytes) => TempEntity::TeImplosion(bytes),
+            Entity::TeSpriteTrail(bytes) => TempEntity::TeSpriteTrail(bytes),
+            Entity::TeSprite(bytes) => TempEntity::TeSprite(bytes),
+            Entity::TeBeamSprite(bytes) => TempEntity::TeBeamSprite(bytes),
+            Entity::TeBeamTorus(bytes) => TempEntity::TeBeamTorus(bytes),
+            Entity::TeBeamDisk(bytes) => TempEntity::TeBeamDisk(bytes),
+            Entity::TeBeamCylinder(bytes) => TempEntity::TeBeamCylinder(bytes),
+            Entity::TeBeamFollow(bytes) => TempEntity::TeBeamFollow(bytes),
+            Entity::TeGlowSprite(bytes) => TempEntity::TeGlowSprite(bytes),
+            Entity::TeBeamRing(bytes) => TempEntity::TeBeamRing(bytes),
+            Entity::TeStreakSplash(bytes) => TempEntity::TeStreakSplash(bytes),
+            Entity::TeDLight(bytes) => TempEntity::TeDLight(bytes),
+            Entity::TeELight(bytes) => TempEntity::TeELight(bytes),
+            Entity::TeTextMessage(entity) => TempEntity::TeTextMessage(entity.try_into()?),
+            Entity::TeLine(bytes) => TempEntity::TeLine(bytes),
+            Entity::TeBox(bytes) => TempEntity::TeBox(bytes),
+            Entity::TeKillBeam(bytes) => TempEntity::TeKillBeam(bytes),
+            Entity::TeLargeFunnel(bytes) => TempEntity::TeLargeFunnel(bytes),
+            Entity::TeBloodStream(bytes) => TempEntity::TeBloodStream(bytes),
+            Entity::TeShowLine(bytes) => TempEntity::TeShowLine(bytes),
+            Entity::TeBlood(bytes) => TempEntity::TeBlood(bytes),
+            Entity::TeDecal(bytes) => TempEntity::TeDecal(bytes),
+            Entity::TeFizz(bytes) => TempEntity::TeFizz(bytes),
+            Entity::TeModel(bytes) => TempEntity::TeModel(bytes),
+            Entity::TeExplodeModel(bytes) => TempEntity::TeExplodeModel(bytes),
+            Entity::TeBreakModel(bytes) => TempEntity::TeBreakModel(bytes),
+            Entity::TeGunshotDecal(bytes) => TempEntity::TeGunshotDecal(bytes),
+            Entity::TeSpriteSpray(bytes) => TempEntity::TeSpriteSpray(bytes),
+            Entity::TeArmorRicochet(bytes) => TempEntity::TeArmorRicochet(bytes),
+            Entity::TePlayerDecal(bytes) => TempEntity::TePlayerDecal(bytes),
+            Entity::TeBubbles(bytes) => TempEntity::TeBubbles(bytes),
+            Entity::TeBubbleTrail(bytes) => TempEntity::TeBubbleTrail(bytes),
+            Entity::TeBloodSprite(bytes) => TempEntity::TeBloodSprite(bytes),
+            Entity::TeWorldDecal(bytes) => TempEntity::TeWorldDecal(bytes),
+            Entity::TeWorldDecalHigh(bytes) => TempEntity::TeWorldDecalHigh(bytes),
+            Entity::TeDecalHigh(bytes) => TempEntity::TeDecalHigh(bytes),
+            Entity::TeProjectile(bytes) => TempEntity::TeProjectile(bytes),
+            Entity::TeSpray(bytes) => TempEntity::TeSpray(bytes),
+            Entity::TePlayerSprites(bytes) => TempEntity::TePlayerSprites(bytes),
+            Entity::TeParticleBurst(bytes) => TempEntity::TeParticleBurst(bytes),
+            Entity::TeFireField(bytes) => TempEntity::TeFireField(bytes),
+            Entity::TePlayerAttachment(bytes) => TempEntity::TePlayerAttachment(bytes),
+            Entity::TeKillPlayerAttachment(bytes) => TempEntity::TeKillPlayerAttachment(bytes),
+            Entity::TeMultigunShot(bytes) => TempEntity::TeMultigunShot(bytes),
+            Entity::TeUserTracer(bytes) => TempEntity::TeUserTracer(bytes),
+        })
+    }
+}
+
+impl From<&Delta> for v1::Delta {
+    fn from(value: &Delta) -> Self {
+        Self {
+            fields: value
+                .iter()
+                .map(|(name, value)| (name.clone(), value.into()))
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<v1::Delta> for Delta {
+    type Error = DemoError;
+
+    fn try_from(value: v1::Delta) -> Result<Self, Self::Error> {
+        value
+            .fields
+            .into_iter()
+            .map(|(name, value)| Ok((name, value.try_into()?)))
+            .collect()
+    }
+}
+
+impl From<&DeltaValue> for v1::DeltaValue {
+    fn from(value: &DeltaValue) -> Self {
+        use v1::delta_value::Value;
+
+        let value = match value {
+            DeltaValue::ByteSigned(value) => Value::ByteSigned((*value).into()),
+            DeltaValue::ByteUnsigned(value) => Value::ByteUnsigned((*value).into()),
+            DeltaValue::ShortSigned(value) => Value::ShortSigned((*value).into()),
+            DeltaValue::ShortUnsigned(value) => Value::ShortUnsigned((*value).into()),
+            DeltaValue::IntSigned(value) => Value::IntSigned(*value),
+            DeltaValue::IntUnsigned(value) => Value::IntUnsigned(*value),
+            DeltaValue::FloatSigned(value) => Value::FloatSigned(*value),
+            DeltaValue::FloatUnsigned(value) => Value::FloatUnsigned(*value),
+            DeltaValue::Angle(value) => Value::Angle(*value),
+            DeltaValue::String(value) => Value::String(value.clone()),
+        };
+
+        Self { value: Some(value) }
+    }
+}
+
+impl TryFrom<v1::DeltaValue> for DeltaValue {
+    type Error = DemoError;
+
+    fn try_from(value: v1::DeltaValue) -> Result<Self, Self::Error> {
+        use v1::delta_value::Value;
+
+        Ok(match value.value.ok_or(DemoError::InvalidProto)? {
+            Value::ByteSigned(value) => Self::ByteSigned(narrow(value)?),
+            Value::ByteUnsigned(value) => Self::ByteUnsigned(narrow(value)?),
+            Value::ShortSigned(value) => Self::ShortSigned(narrow(value)?),
+            Value::ShortUnsigned(value) => Self::ShortUnsigned(narrow(value)?),
+            Value::IntSigned(value) => Self::IntSigned(value),
+            Value::IntUnsigned(value) => Self::IntUnsigned(value),
+            Value::FloatSigned(value) => Self::FloatSigned(value),
+            Value::FloatUnsigned(value) => Self::FloatUnsigned(value),
+            Value::Angle(value) => Self::Angle(value),
+            Value::String(value) => Self::String(value),
+        })
+    }
+}
+
+impl From<&types::SvcClientData> for v1::SvcClientData {
+    fn from(value: &types::SvcClientData) -> Self {
+        Self {
+            has_delta_update_mask: value.has_delta_update_mask,
+            delta_update_mask: value.delta_update_mask.map(Into::into),
+            client_data: Some((&value.client_data).into()),
+            weapon_data: value
+                .weapon_data
+                .as_ref()
+                .map(|weapons| v1::WeaponDataList {
+                    weapons: weapons.iter().map(Into::into).collect(),
+                }),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcClientData> for types::SvcClientData {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcClientData) -> Result<Self, Self::Error> {
+        Ok(Self {
+            has_delta_update_mask: value.has_delta_update_mask,
+            delta_update_mask: value.delta_update_mask.map(narrow).transpose()?,
+            client_data: delta(value.client_data)?,
+            weapon_data: value
+                .weapon_data
+                .map(|weapons| all(weapons.weapons))
+                .transpose()?,
+        })
+    }
+}
+
+impl From<&types::Header> for v1::Header {
+    fn from(value: &types::Header) -> Self {
+        Self {
+            magic: value.magic.clone(),
+            demo_protocol: value.demo_protocol,
+            network_protocol: value.network_protocol,
+            map_name: value.map_name.0.clone(),
+            game_directory: value.game_directory.0.clone(),
+            map_checksum: value.map_checksum,
+            directory_offset: value.directory_offset,
+        }
+    }
+}
+
+impl TryFrom<v1::Header> for types::Header {
+    type Error = DemoError;
+
+    fn try_from(value: v1::Header) -> Result<Self, Self::Error> {
+        Ok(Self {
+            magic: value.magic,
+            demo_protocol: value.demo_protocol,
+            network_protocol: value.network_protocol,
+            map_name: value.map_name.into(),
+            game_directory: value.game_directory.into(),
+            map_checksum: value.map_checksum,
+            directory_offset: value.directory_offset,
+        })
+    }
+}
+
+impl From<&types::DirectoryEntry> for v1::DirectoryEntry {
+    fn from(value: &types::DirectoryEntry) -> Self {
+        Self {
+            r#type: value.type_,
+            description: value.description.0.clone(),
+            flags: value.flags,
+            cd_track: value.cd_track,
+            track_time: value.track_time,
+            frame_count: value.frame_count,
+            frame_offset: value.frame_offset,
+            file_length: value.file_length,
+            frames: value.frames.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<v1::DirectoryEntry> for types::DirectoryEntry {
+    type Error = DemoError;
+
+    fn try_from(value: v1::DirectoryEntry) -> Result<Self, Self::Error> {
+        Ok(Self {
+            type_: value.r#type,
+            description: value.description.into(),
+            flags: value.flags,
+            cd_track: value.cd_track,
+            track_time: value.track_time,
+            frame_count: value.frame_count,
+            frame_offset: value.frame_offset,
+            file_length: value.file_length,
+            frames: all(value.frames)?,
+        })
+    }
+}
+
+impl From<&types::ConsoleCommand> for v1::ConsoleCommand {
+    fn from(value: &types::ConsoleCommand) -> Self {
+        Self {
+            command: value.command.0.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::ConsoleCommand> for types::ConsoleCommand {
+    type Error = DemoError;
+
+    fn try_from(value: v1::ConsoleCommand) -> Result<Self, Self::Error> {
+        Ok(Self {
+            command: value.command.into(),
+        })
+    }
+}
+
+impl From<&types::ClientData> for v1::ClientData {
+    fn from(value: &types::ClientData) -> Self {
+        Self {
+            origin: value.origin.clone(),
+            viewangles: value.viewangles.clone(),
+            weapon_bits: value.weapon_bits,
+            fov: value.fov,
+        }
+    }
+}
+
+impl TryFrom<v1::ClientData> for types::ClientData {
+    type Error = DemoError;
+
+    fn try_from(value: v1::ClientData) -> Result<Self, Self::Error> {
+        Ok(Self {
+            origin: value.origin,
+            viewangles: value.viewangles,
+            weapon_bits: value.weapon_bits,
+            fov: value.fov,
+        })
+    }
+}
+
+impl From<&types::Event> for v1::Event {
+    fn from(value: &types::Event) -> Self {
+        Self {
+            flags: value.flags,
+            index: value.index,
+            delay: value.delay,
+            args: Some((&value.args).into()),
+        }
+    }
+}
+
+impl TryFrom<v1::Event> for types::Event {
+    type Error = DemoError;
+
+    fn try_from(value: v1::Event) -> Result<Self, Self::Error> {
+        Ok(Self {
+            flags: value.flags,
+            index: value.index,
+            delay: value.delay,
+            args: required(value.args)?,
+        })
+    }
+}
+
+impl From<&types::EventArgs> for v1::EventArgs {
+    fn from(value: &types::EventArgs) -> Self {
+        Self {
+            flags: value.flags,
+            entity_index: value.entity_index,
+            origin: value.origin.clone(),
+            angles: value.angles.clone(),
+            velocity: value.velocity.clone(),
+            ducking: value.ducking,
+            fparam1: value.fparam1,
+            fparam2: value.fparam2,
+            iparam1: value.iparam1,
+            iparam2: value.iparam2,
+            bparam1: value.bparam1,
+            bparam2: value.bparam2,
+        }
+    }
+}
+
+impl TryFrom<v1::EventArgs> for types::EventArgs {
+    type Error = DemoError;
+
+    fn try_from(value: v1::EventArgs) -> Result<Self, Self::Error> {
+        Ok(Self {
+            flags: value.flags,
+            entity_index: value.entity_index,
+            origin: value.origin,
+            angles: value.angles,
+            velocity: value.velocity,
+            ducking: value.ducking,
+            fparam1: value.fparam1,
+            fparam2: value.fparam2,
+            iparam1: value.iparam1,
+            iparam2: value.iparam2,
+            bparam1: value.bparam1,
+            bparam2: value.bparam2,
+        })
+    }
+}
+
+impl From<&types::Sound> for v1::Sound {
+    fn from(value: &types::Sound) -> Self {
+        Self {
+            channel: value.channel,
+            sample: value.sample.0.clone(),
+            attenuation: value.attenuation,
+            volume: value.volume,
+            flags: value.flags,
+            pitch: value.pitch,
+        }
+    }
+}
+
+impl TryFrom<v1::Sound> for types::Sound {
+    type Error = DemoError;
+
+    fn try_from(value: v1::Sound) -> Result<Self, Self::Error> {
+        Ok(Self {
+            channel: value.channel,
+            sample: value.sample.into(),
+            attenuation: value.attenuation,
+            volume: value.volume,
+            flags: value.flags,
+            pitch: value.pitch,
+        })
+    }
+}
+
+impl From<&types::WeaponAnimation> for v1::WeaponAnimation {
+    fn from(value: &types::WeaponAnimation) -> Self {
+        Self {
+            sequence: value.sequence,
+            body: value.body,
+        }
+    }
+}
+
+impl TryFrom<v1::WeaponAnimation> for types::WeaponAnimation {
+    type Error = DemoError;
+
+    fn try_from(value: v1::WeaponAnimation) -> Result<Self, Self::Error> {
+        Ok(Self {
+            sequence: value.sequence,
+            body: value.body,
+        })
+    }
+}
+
+impl From<&types::DemoBuffer> for v1::DemoBuffer {
+    fn from(value: &types::DemoBuffer) -> Self {
+        Self {
+            buffer: value.buffer.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::DemoBuffer> for types::DemoBuffer {
+    type Error = DemoError;
+
+    fn try_from(value: v1::DemoBuffer) -> Result<Self, Self::Error> {
+        Ok(Self {
+            buffer: value.buffer,
+        })
+    }
+}
+
+impl From<&types::DemoInfo> for v1::DemoInfo {
+    fn from(value: &types::DemoInfo) -> Self {
+        Self {
+            timestamp: value.timestamp,
+            refparams: Some((&value.refparams).into()),
+            usercmd: Some((&value.usercmd).into()),
+            movevars: Some((&value.movevars).into()),
+            view: value.view.clone(),
+            viewmodel: value.viewmodel,
+        }
+    }
+}
+
+impl TryFrom<v1::DemoInfo> for types::DemoInfo {
+    type Error = DemoError;
+
+    fn try_from(value: v1::DemoInfo) -> Result<Self, Self::Error> {
+        Ok(Self {
+            timestamp: value.timestamp,
+            refparams: required(value.refparams)?,
+            usercmd: required(value.usercmd)?,
+            movevars: required(value.movevars)?,
+            view: value.view,
+            viewmodel: value.viewmodel,
+        })
+    }
+}
+
+impl From<&types::RefParams> for v1::RefParams {
+    fn from(value: &types::RefParams) -> Self {
+        Self {
+            view_origin: value.view_origin.clone(),
+            view_angles: value.view_angles.clone(),
+            forward: value.forward.clone(),
+            right: value.right.clone(),
+            up: value.up.clone(),
+            frame_time: value.frame_time,
+            time: value.time,
+            intermission: value.intermission,
+            paused: value.paused,
+            spectator: value.spectator,
+            on_ground: value.on_ground,
+            water_level: value.water_level,
+            sim_vel: value.sim_vel.clone(),
+            sim_org: value.sim_org.clone(),
+            view_height: value.view_height.clone(),
+            ideal_pitch: value.ideal_pitch,
+            cl_viewangles: value.cl_viewangles.clone(),
+            health: value.health,
+            crosshair_angle: value.crosshair_angle.clone(),
+            view_size: value.view_size,
+            punch_angle: value.punch_angle.clone(),
+            max_clients: value.max_clients,
+            view_entity: value.view_entity,
+            player_num: value.player_num,
+            max_entities: value.max_entities,
+            demo_playback: value.demo_playback,
+            hardware: value.hardware,
+            smoothing: value.smoothing,
+            ptr_cmd: value.ptr_cmd,
+            ptr_move_vars: value.ptr_move_vars,
+            view_port: value.view_port.clone(),
+            next_view: value.next_view,
+            only_client_draw: value.only_client_draw,
+        }
+    }
+}
+
+impl TryFrom<v1::RefParams> for types::RefParams {
+    type Error = DemoError;
+
+    fn try_from(value: v1::RefParams) -> Result<Self, Self::Error> {
+        Ok(Self {
+            view_origin: value.view_origin,
+            view_angles: value.view_angles,
+            forward: value.forward,
+            right: value.right,
+            up: value.up,
+            frame_time: value.frame_time,
+            time: value.time,
+            intermission: value.intermission,
+            paused: value.paused,
+            spectator: value.spectator,
+            on_ground: value.on_ground,
+            water_level: value.water_level,
+            sim_vel: value.sim_vel,
+            sim_org: value.sim_org,
+            view_height: value.view_height,
+            ideal_pitch: value.ideal_pitch,
+            cl_viewangles: value.cl_viewangles,
+            health: value.health,
+            crosshair_angle: value.crosshair_angle,
+            view_size: value.view_size,
+            punch_angle: value.punch_angle,
+            max_clients: value.max_clients,
+            view_entity: value.view_entity,
+            player_num: value.player_num,
+            max_entities: value.max_entities,
+            demo_playback: value.demo_playback,
+            hardware: value.hardware,
+            smoothing: value.smoothing,
+            ptr_cmd: value.ptr_cmd,
+            ptr_move_vars: value.ptr_move_vars,
+            view_port: value.view_port,
+            next_view: value.next_view,
+            only_client_draw: value.only_client_draw,
+        })
+    }
+}
+
+impl From<&types::UserCmd> for v1::UserCmd {
+    fn from(value: &types::UserCmd) -> Self {
+        Self {
+            lerp_msec: value.lerp_msec.into(),
+            msec: value.msec.into(),
+            unknown1: value.unknown1.into(),
+            view_angles: value.view_angles.clone(),
+            forward_move: value.forward_move,
+            side_move: value.side_move,
+            up_move: value.up_move,
+            light_level: value.light_level.into(),
+            unknown2: value.unknonwn2.into(),
+            buttons: value.buttons.into(),
+            impulse: value.impulse.into(),
+            weapon_select: value.weapon_select.into(),
+            unknown3: value.unknown3.into(),
+            unknown4: value.unknown4.into(),
+            impact_index: value.impact_index,
+            impact_position: value.impact_position.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::UserCmd> for types::UserCmd {
+    type Error = DemoError;
+
+    fn try_from(value: v1::UserCmd) -> Result<Self, Self::Error> {
+        Ok(Self {
+            lerp_msec: narrow(value.lerp_msec)?,
+            msec: narrow(value.msec)?,
+            unknown1: narrow(value.unknown1)?,
+            view_angles: value.view_angles,
+            forward_move: value.forward_move,
+            side_move: value.side_move,
+            up_move: value.up_move,
+            light_level: narrow(value.light_level)?,
+            unknonwn2: narrow(value.unknown2)?,
+            buttons: narrow(value.buttons)?,
+            impulse: narrow(value.impulse)?,
+            weapon_select: narrow(value.weapon_select)?,
+            unknown3: narrow(value.unknown3)?,
+            unknown4: narrow(value.unknown4)?,
+            impact_index: value.impact_index,
+            impact_position: value.impact_position,
+        })
+    }
+}
+
+impl From<&types::MoveVars> for v1::MoveVars {
+    fn from(value: &types::MoveVars) -> Self {
+        Self {
+            gravity: value.gravity,
+            stopspeed: value.stopspeed,
+            maxspeed: value.maxspeed,
+            spectatormaxspeed: value.spectatormaxspeed,
+            accelerate: value.accelerate,
+            airaccelerate: value.airaccelerate,
+            wateraccelerate: value.wateraccelerate,
+            friction: value.friction,
+            edgefriction: value.edgefriction,
+            waterfriction: value.waterfriction,
+            entgravity: value.entgravity,
+            bounce: value.bounce,
+            stepsize: value.stepsize,
+            maxvelocity: value.maxvelocity,
+            zmax: value.zmax,
+            wave_height: value.wave_height,
+            footsteps: value.footsteps,
+            sky_name: value.sky_name.0.clone(),
+            rollangle: value.rollangle,
+            rollspeed: value.rollspeed,
+            skycolor: value.skycolor.clone(),
+            skyvec: value.skyvec.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::MoveVars> for types::MoveVars {
+    type Error = DemoError;
+
+    fn try_from(value: v1::MoveVars) -> Result<Self, Self::Error> {
+        Ok(Self {
+            gravity: value.gravity,
+            stopspeed: value.stopspeed,
+            maxspeed: value.maxspeed,
+            spectatormaxspeed: value.spectatormaxspeed,
+            accelerate: value.accelerate,
+            airaccelerate: value.airaccelerate,
+            wateraccelerate: value.wateraccelerate,
+            friction: value.friction,
+            edgefriction: value.edgefriction,
+            waterfriction: value.waterfriction,
+            entgravity: value.entgravity,
+            bounce: value.bounce,
+            stepsize: value.stepsize,
+            maxvelocity: value.maxvelocity,
+            zmax: value.zmax,
+            wave_height: value.wave_height,
+            footsteps: value.footsteps,
+            sky_name: value.sky_name.into(),
+            rollangle: value.rollangle,
+            rollspeed: value.rollspeed,
+            skycolor: value.skycolor,
+            skyvec: value.skyvec,
+        })
+    }
+}
+
+impl From<&types::SequenceInfo> for v1::SequenceInfo {
+    fn from(value: &types::SequenceInfo) -> Self {
+        Self {
+            incoming_sequence: value.incoming_sequence,
+            incoming_acknowledged: value.incoming_acknowledged,
+            incoming_reliable_acknowledged: value.incoming_reliable_acknowledged,
+            incoming_reliable_sequence: value.incoming_reliable_sequence,
+            outgoing_sequence: value.outgoing_sequence,
+            reliable_sequence: value.reliable_sequence,
+            last_reliable_sequence: value.last_reliable_sequence,
+        }
+    }
+}
+
+impl TryFrom<v1::SequenceInfo> for types::SequenceInfo {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SequenceInfo) -> Result<Self, Self::Error> {
+        Ok(Self {
+            incoming_sequence: value.incoming_sequence,
+            incoming_acknowledged: value.incoming_acknowledged,
+            incoming_reliable_acknowledged: value.incoming_reliable_acknowledged,
+            incoming_reliable_sequence: value.incoming_reliable_sequence,
+            outgoing_sequence: value.outgoing_sequence,
+            reliable_sequence: value.reliable_sequence,
+            last_reliable_sequence: value.last_reliable_sequence,
+        })
+    }
+}
+
+impl From<&types::SvcDisconnect> for v1::SvcDisconnect {
+    fn from(value: &types::SvcDisconnect) -> Self {
+        Self {
+            reason: value.reason.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcDisconnect> for types::SvcDisconnect {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcDisconnect) -> Result<Self, Self::Error> {
+        Ok(Self {
+            reason: value.reason,
+        })
+    }
+}
+
+impl From<&types::SvcEvent> for v1::SvcEvent {
+    fn from(value: &types::SvcEvent) -> Self {
+        Self {
+            event_count: value.event_count.into(),
+            events: value.events.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcEvent> for types::SvcEvent {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcEvent) -> Result<Self, Self::Error> {
+        Ok(Self {
+            event_count: narrow(value.event_count)?,
+            events: all(value.events)?,
+        })
+    }
+}
+
+impl From<&types::EventS> for v1::EventS {
+    fn from(value: &types::EventS) -> Self {
+        Self {
+            event_index: value.event_index.into(),
+            has_packet_index: value.has_packet_index,
+            packet_index: value.packet_index.map(Into::into),
+            has_delta: value.has_delta,
+            delta: value.delta.as_ref().map(Into::into),
+            has_fire_time: value.has_fire_time,
+            fire_time: value.fire_time.map(Into::into),
+        }
+    }
+}
+
+impl TryFrom<v1::EventS> for types::EventS {
+    type Error = DemoError;
+
+    fn try_from(value: v1::EventS) -> Result<Self, Self::Error> {
+        Ok(Self {
+            event_index: narrow(value.event_index)?,
+            has_packet_index: value.has_packet_index,
+            packet_index: value.packet_index.map(narrow).transpose()?,
+            has_delta: value.has_delta,
+            delta: value.delta.map(TryInto::try_into).transpose()?,
+            has_fire_time: value.has_fire_time,
+            fire_time: value.fire_time.map(narrow).transpose()?,
+        })
+    }
+}
+
+impl From<&types::SvcVersion> for v1::SvcVersion {
+    fn from(value: &types::SvcVersion) -> Self {
+        Self {
+            protocol_version: value.protocol_version,
+        }
+    }
+}
+
+impl TryFrom<v1::SvcVersion> for types::SvcVersion {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcVersion) -> Result<Self, Self::Error> {
+        Ok(Self {
+            protocol_version: value.protocol_version,
+        })
+    }
+}
+
+impl From<&types::SvcSetView> for v1::SvcSetView {
+    fn from(value: &types::SvcSetView) -> Self {
+        Self {
+            entity_index: value.entity_index.into(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcSetView> for types::SvcSetView {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcSetView) -> Result<Self, Self::Error> {
+        Ok(Self {
+            entity_index: narrow(value.entity_index)?,
+        })
+    }
+}
+
+impl From<&types::SvcSound> for v1::SvcSound {
+    fn from(value: &types::SvcSound) -> Self {
+        Self {
+            flags: value.flags.into(),
+            volume: value.volume.map(Into::into),
+            attenuation: value.attenuation.map(Into::into),
+            channel: value.channel.into(),
+            entity_index: value.entity_index.into(),
+            sound_index_long: value.sound_index_long.map(Into::into),
+            sound_index_short: value.sound_index_short.map(Into::into),
+            has_x: value.has_x,
+            has_y: value.has_y,
+            has_z: value.has_z,
+            origin_x: value.origin_x.as_ref().map(Into::into),
+            origin_y: value.origin_y.as_ref().map(Into::into),
+            origin_z: value.origin_z.as_ref().map(Into::into),
+            pitch: value.pitch.into(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcSound> for types::SvcSound {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcSound) -> Result<Self, Self::Error> {
+        Ok(Self {
+            flags: narrow(value.flags)?,
+            volume: value.volume.map(narrow).transpose()?,
+            attenuation: value.attenuation.map(narrow).transpose()?,
+            channel: narrow(value.channel)?,
+            entity_index: narrow(value.entity_index)?,
+            sound_index_long: value.sound_index_long.map(narrow).transpose()?,
+            sound_index_short: value.sound_index_short.map(narrow).transpose()?,
+            has_x: value.has_x,
+            has_y: value.has_y,
+            has_z: value.has_z,
+            origin_x: value.origin_x.map(TryInto::try_into).transpose()?,
+            origin_y: value.origin_y.map(TryInto::try_into).transpose()?,
+            origin_z: value.origin_z.map(TryInto::try_into).transpose()?,
+            pitch: narrow(value.pitch)?,
+        })
+    }
+}
+
+impl From<&types::OriginCoord> for v1::OriginCoord {
+    fn from(value: &types::OriginCoord) -> Self {
+        Self {
+            int_flag: value.int_flag,
+            fraction_flag: value.fraction_flag,
+            is_negative: value.is_negative,
+            int_value: value.int_value.map(Into::into),
+            fraction_value: value.fraction_value.map(Into::into),
+        }
+    }
+}
+
+impl TryFrom<v1::OriginCoord> for types::OriginCoord {
+    type Error = DemoError;
+
+    fn try_from(value: v1::OriginCoord) -> Result<Self, Self::Error> {
+        Ok(Self {
+            int_flag: value.int_flag,
+            fraction_flag: value.fraction_flag,
+            is_negative: value.is_negative,
+            int_value: value.int_value.map(narrow).transpose()?,
+            fraction_value: value.fraction_value.map(narrow).transpose()?,
+        })
+    }
+}
+
+impl From<&types::SvcTime> for v1::SvcTime {
+    fn from(value: &types::SvcTime) -> Self {
+        Self { time: value.time }
+    }
+}
+
+impl TryFrom<v1::SvcTime> for types::SvcTime {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcTime) -> Result<Self, Self::Error> {
+        Ok(Self { time: value.time })
+    }
+}
+
+impl From<&types::SvcPrint> for v1::SvcPrint {
+    fn from(value: &types::SvcPrint) -> Self {
+        Self {
+            message: value.message.0.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcPrint> for types::SvcPrint {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcPrint) -> Result<Self, Self::Error> {
+        Ok(Self {
+            message: value.message.into(),
+        })
+    }
+}
+
+impl From<&types::SvcStuffText> for v1::SvcStuffText {
+    fn from(value: &types::SvcStuffText) -> Self {
+        Self {
+            command: value.command.0.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcStuffText> for types::SvcStuffText {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcStuffText) -> Result<Self, Self::Error> {
+        Ok(Self {
+            command: value.command.into(),
+        })
+    }
+}
+
+impl From<&types::SvcSetAngle> for v1::SvcSetAngle {
+    fn from(value: &types::SvcSetAngle) -> Self {
+        Self {
+            pitch: value.pitch.into(),
+            yaw: value.yaw.into(),
+            roll: value.roll.into(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcSetAngle> for types::SvcSetAngle {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcSetAngle) -> Result<Self, Self::Error> {
+        Ok(Self {
+            pitch: narrow(value.pitch)?,
+            yaw: narrow(value.yaw)?,
+            roll: narrow(value.roll)?,
+        })
+    }
+}
+
+impl From<&types::SvcServerInfo> for v1::SvcServerInfo {
+    fn from(value: &types::SvcServerInfo) -> Self {
+        Self {
+            protocol: value.protocol,
+            spawn_count: value.spawn_count,
+            map_checksum: value.map_checksum,
+            client_dll_hash: value.client_dll_hash.0.clone(),
+            max_players: value.max_players.into(),
+            player_index: value.player_index.into(),
+            is_deathmatch: value.is_deathmatch.into(),
+            game_dir: value.game_dir.clone(),
+            hostname: value.hostname.clone(),
+            map_file_name: value.map_file_name.clone(),
+            map_cycle: value.map_cycle.clone(),
+            unknown: value.unknown.into(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcServerInfo> for types::SvcServerInfo {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcServerInfo) -> Result<Self, Self::Error> {
+        Ok(Self {
+            protocol: value.protocol,
+            spawn_count: value.spawn_count,
+            map_checksum: value.map_checksum,
+            client_dll_hash: value.client_dll_hash.into(),
+            max_players: narrow(value.max_players)?,
+            player_index: narrow(value.player_index)?,
+            is_deathmatch: narrow(value.is_deathmatch)?,
+            game_dir: value.game_dir,
+            hostname: value.hostname,
+            map_file_name: value.map_file_name,
+            map_cycle: value.map_cycle,
+            unknown: narrow(value.unknown)?,
+        })
+    }
+}
+
+impl From<&types::SvcLightStyle> for v1::SvcLightStyle {
+    fn from(value: &types::SvcLightStyle) -> Self {
+        Self {
+            index: value.index.into(),
+            light_info: value.light_info.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcLightStyle> for types::SvcLightStyle {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcLightStyle) -> Result<Self, Self::Error> {
+        Ok(Self {
+            index: narrow(value.index)?,
+            light_info: value.light_info,
+        })
+    }
+}
+
+impl From<&types::SvcUpdateUserInfo> for v1::SvcUpdateUserInfo {
+    fn from(value: &types::SvcUpdateUserInfo) -> Self {
+        Self {
+            index: value.index.into(),
+            id: value.id,
+            user_info: value.user_info.0.clone(),
+            cd_key_hash: value.cd_key_hash.0.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcUpdateUserInfo> for types::SvcUpdateUserInfo {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcUpdateUserInfo) -> Result<Self, Self::Error> {
+        Ok(Self {
+            index: narrow(value.index)?,
+            id: value.id,
+            user_info: value.user_info.into(),
+            cd_key_hash: value.cd_key_hash.into(),
+        })
+    }
+}
+
+impl From<&types::SvcDeltaDescription> for v1::SvcDeltaDescription {
+    fn from(value: &types::SvcDeltaDescription) -> Self {
+        Self {
+            name: value.name.clone(),
+            total_fields: value.total_fields.into(),
+            fields: value.fields.iter().map(Into::into).collect(),
+            clone: value.clone.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcDeltaDescription> for types::SvcDeltaDescription {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcDeltaDescription) -> Result<Self, Self::Error> {
+        Ok(Self {
+            name: value.name,
+            total_fields: narrow(value.total_fields)?,
+            fields: all(value.fields)?,
+            clone: value.clone,
+        })
+    }
+}
+
+impl From<&types::DeltaDecoderS> for v1::DeltaField {
+    fn from(value: &types::DeltaDecoderS) -> Self {
+        Self {
+            name: value.name.clone(),
+            bits: value.bits,
+            divisor: value.divisor,
+            flags: value.flags,
+        }
+    }
+}
+
+impl TryFrom<v1::DeltaField> for types::DeltaDecoderS {
+    type Error = DemoError;
+
+    fn try_from(value: v1::DeltaField) -> Result<Self, Self::Error> {
+        Ok(Self {
+            name: value.name,
+            bits: value.bits,
+            divisor: value.divisor,
+            flags: value.flags,
+        })
+    }
+}
+
+impl From<&types::ClientDataWeaponData> for v1::ClientDataWeaponData {
+    fn from(value: &types::ClientDataWeaponData) -> Self {
+        Self {
+            weapon_index: value.weapon_index.into(),
+            weapon_data: Some((&value.weapon_data).into()),
+        }
+    }
+}
+
+impl TryFrom<v1::ClientDataWeaponData> for types::ClientDataWeaponData {
+    type Error = DemoError;
+
+    fn try_from(value: v1::ClientDataWeaponData) -> Result<Self, Self::Error> {
+        Ok(Self {
+            weapon_index: narrow(value.weapon_index)?,
+            weapon_data: delta(value.weapon_data)?,
+        })
+    }
+}
+
+impl From<&types::SvcStopSound> for v1::SvcStopSound {
+    fn from(value: &types::SvcStopSound) -> Self {
+        Self {
+            entity_index: value.entity_index.into(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcStopSound> for types::SvcStopSound {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcStopSound) -> Result<Self, Self::Error> {
+        Ok(Self {
+            entity_index: narrow(value.entity_index)?,
+        })
+    }
+}
+
+impl From<&types::SvcPings> for v1::SvcPings {
+    fn from(value: &types::SvcPings) -> Self {
+        Self {
+            pings: value.pings.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcPings> for types::SvcPings {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcPings) -> Result<Self, Self::Error> {
+        Ok(Self {
+            pings: all(value.pings)?,
+        })
+    }
+}
+
+impl From<&types::PingS> for v1::PingS {
+    fn from(value: &types::PingS) -> Self {
+        Self {
+            has_ping_data: value.has_ping_data,
+            player_id: value.player_id.map(Into::into),
+            ping: value.ping.map(Into::into),
+            loss: value.loss.map(Into::into),
+        }
+    }
+}
+
+impl TryFrom<v1::PingS> for types::PingS {
+    type Error = DemoError;
+
+    fn try_from(value: v1::PingS) -> Result<Self, Self::Error> {
+        Ok(Self {
+            has_ping_data: value.has_ping_data,
+            player_id: value.player_id.map(narrow).transpose()?,
+            ping: value.ping.map(narrow).transpose()?,
+            loss: value.loss.map(narrow).transpose()?,
+        })
+    }
+}
+
+impl From<&types::SvcParticle> for v1::SvcParticle {
+    fn from(value: &types::SvcParticle) -> Self {
+        Self {
+            origin: value.origin.iter().map(|&coord| coord.into()).collect(),
+            direction: value.direction.clone(),
+            count: value.count.into(),
+            color: value.color.into(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcParticle> for types::SvcParticle {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcParticle) -> Result<Self, Self::Error> {
+        Ok(Self {
+            origin: value
+                .origin
+                .into_iter()
+                .map(narrow)
+                .collect::<Result<_, _>>()?,
+            direction: value.direction,
+            count: narrow(value.count)?,
+            color: narrow(value.color)?,
+        })
+    }
+}
+
+impl From<&types::SvcSpawnStatic> for v1::SvcSpawnStatic {
+    fn from(value: &types::SvcSpawnStatic) -> Self {
+        Self {
+            model_index: value.model_index.into(),
+            sequence: value.sequence.into(),
+            frame: value.frame.into(),
+            color_map: value.color_map.into(),
+            skin: value.skin.into(),
+            origin_x: value.origin_x.into(),
+            rotation_x: value.rotation_x.into(),
+            origin_y: value.origin_y.into(),
+            rotation_y: value.rotation_y.into(),
+            origin_z: value.origin_z.into(),
+            rotation_z: value.rotation_z.into(),
+            has_render_mode: value.has_render_mode.into(),
+            render_color: value.render_color.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcSpawnStatic> for types::SvcSpawnStatic {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcSpawnStatic) -> Result<Self, Self::Error> {
+        Ok(Self {
+            model_index: narrow(value.model_index)?,
+            sequence: narrow(value.sequence)?,
+            frame: narrow(value.frame)?,
+            color_map: narrow(value.color_map)?,
+            skin: narrow(value.skin)?,
+            origin_x: narrow(value.origin_x)?,
+            rotation_x: narrow(value.rotation_x)?,
+            origin_y: narrow(value.origin_y)?,
+            rotation_y: narrow(value.rotation_y)?,
+            origin_z: narrow(value.origin_z)?,
+            rotation_z: narrow(value.rotation_z)?,
+            has_render_mode: narrow(value.has_render_mode)?,
+            render_color: value.render_color,
+        })
+    }
+}
+
+impl From<&types::SvcEventReliable> for v1::SvcEventReliable {
+    fn from(value: &types::SvcEventReliable) -> Self {
+        Self {
+            event_index: value.event_index.into(),
+            event_args: Some((&value.event_args).into()),
+            has_fire_time: value.has_fire_time,
+            fire_time: value.fire_time.map(Into::into),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcEventReliable> for types::SvcEventReliable {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcEventReliable) -> Result<Self, Self::Error> {
+        Ok(Self {
+            event_index: narrow(value.event_index)?,
+            event_args: delta(value.event_args)?,
+            has_fire_time: value.has_fire_time,
+            fire_time: value.fire_time.map(narrow).transpose()?,
+        })
+    }
+}
+
+impl From<&types::SvcSpawnBaseline> for v1::SvcSpawnBaseline {
+    fn from(value: &types::SvcSpawnBaseline) -> Self {
+        Self {
+            entities: value.entities.iter().map(Into::into).collect(),
+            total_extra_data: value.total_extra_data.into(),
+            extra_data: value.extra_data.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcSpawnBaseline> for types::SvcSpawnBaseline {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcSpawnBaseline) -> Result<Self, Self::Error> {
+        Ok(Self {
+            entities: all(value.entities)?,
+            total_extra_data: narrow(value.total_extra_data)?,
+            extra_data: all(value.extra_data)?,
+        })
+    }
+}
+
+impl From<&types::EntityS> for v1::EntityS {
+    fn from(value: &types::EntityS) -> Self {
+        Self {
+            entity_index: value.entity_index.into(),
+            index: value.index.into(),
+            r#type: value.type_.into(),
+            delta: Some((&value.delta).into()),
+        }
+    }
+}
+
+impl TryFrom<v1::EntityS> for types::EntityS {
+    type Error = DemoError;
+
+    fn try_from(value: v1::EntityS) -> Result<Self, Self::Error> {
+        Ok(Self {
+            entity_index: narrow(value.entity_index)?,
+            index: narrow(value.index)?,
+            type_: narrow(value.r#type)?,
+            delta: delta(value.delta)?,
+        })
+    }
+}
+
+impl From<&types::SvcTempEntity> for v1::SvcTempEntity {
+    fn from(value: &types::SvcTempEntity) -> Self {
+        Self {
+            entity_type: value.entity_type.into(),
+            entity: Some((&value.entity).into()),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcTempEntity> for types::SvcTempEntity {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcTempEntity) -> Result<Self, Self::Error> {
+        Ok(Self {
+            entity_type: narrow(value.entity_type)?,
+            entity: required(value.entity)?,
+        })
+    }
+}
+
+impl From<&types::TeBeamPoints> for v1::TeBeamPoints {
+    fn from(value: &types::TeBeamPoints) -> Self {
+        Self {
+            start_position: value
+                .start_position
+                .iter()
+                .map(|&coord| coord.into())
+                .collect(),
+            end_position: value
+                .end_position
+                .iter()
+                .map(|&coord| coord.into())
+                .collect(),
+            sprite_index: value.sprite_index.into(),
+            start_frame: value.start_frame.into(),
+            frame_rate: value.frame_rate.into(),
+            life: value.life.into(),
+            width: value.width.into(),
+            noise: value.noise.into(),
+            color: value.color.clone(),
+            speed: value.speed.into(),
+        }
+    }
+}
+
+impl TryFrom<v1::TeBeamPoints> for types::TeBeamPoints {
+    type Error = DemoError;
+
+    fn try_from(value: v1::TeBeamPoints) -> Result<Self, Self::Error> {
+        Ok(Self {
+            start_position: value
+                .start_position
+                .into_iter()
+                .map(narrow)
+                .collect::<Result<_, _>>()?,
+            end_position: value
+                .end_position
+                .into_iter()
+                .map(narrow)
+                .collect::<Result<_, _>>()?,
+            sprite_index: narrow(value.sprite_index)?,
+            start_frame: narrow(value.start_frame)?,
+            frame_rate: narrow(value.frame_rate)?,
+            life: narrow(value.life)?,
+            width: narrow(value.width)?,
+            noise: narrow(value.noise)?,
+            color: value.color,
+            speed: narrow(value.speed)?,
+        })
+    }
+}
+
+impl From<&types::TeBspDecal> for v1::TeBspDecal {
+    fn from(value: &types::TeBspDecal) -> Self {
+        Self {
+            unknown1: value.unknown1.clone(),
+            entity_index: value.entity_index.into(),
+            unknown2: value.unknown2.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::TeBspDecal> for types::TeBspDecal {
+    type Error = DemoError;
+
+    fn try_from(value: v1::TeBspDecal) -> Result<Self, Self::Error> {
+        Ok(Self {
+            unknown1: value.unknown1,
+            entity_index: narrow(value.entity_index)?,
+            unknown2: value.unknown2,
+        })
+    }
+}
+
+impl From<&types::TeTextMessage> for v1::TeTextMessage {
+    fn from(value: &types::TeTextMessage) -> Self {
+        Self {
+            channel: value.channel.into(),
+            x: value.x.into(),
+            y: value.y.into(),
+            effect: value.effect.into(),
+            text_color: value.text_color.clone(),
+            effect_color: value.effect_color.clone(),
+            fade_in_time: value.fade_in_time.into(),
+            fade_out_time: value.fade_out_time.into(),
+            hold_time: value.hold_time.into(),
+            effect_time: value.effect_time.map(Into::into),
+            message: value.message.0.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::TeTextMessage> for types::TeTextMessage {
+    type Error = DemoError;
+
+    fn try_from(value: v1::TeTextMessage) -> Result<Self, Self::Error> {
+        Ok(Self {
+            channel: narrow(value.channel)?,
+            x: narrow(value.x)?,
+            y: narrow(value.y)?,
+            effect: narrow(value.effect)?,
+            text_color: value.text_color,
+            effect_color: value.effect_color,
+            fade_in_time: narrow(value.fade_in_time)?,
+            fade_out_time: narrow(value.fade_out_time)?,
+            hold_time: narrow(value.hold_time)?,
+            effect_time: value.effect_time.map(narrow).transpose()?,
+            message: value.message.into(),
+        })
+    }
+}
+
+impl From<&types::SvcSetPause> for v1::SvcSetPause {
+    fn from(value: &types::SvcSetPause) -> Self {
+        Self {
+            is_paused: value.is_paused.into(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcSetPause> for types::SvcSetPause {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcSetPause) -> Result<Self, Self::Error> {
+        Ok(Self {
+            is_paused: narrow(value.is_paused)?,
+        })
+    }
+}
+
+impl From<&types::SvcSignOnNum> for v1::SvcSignOnNum {
+    fn from(value: &types::SvcSignOnNum) -> Self {
+        Self {
+            sign: value.sign.into(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcSignOnNum> for types::SvcSignOnNum {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcSignOnNum) -> Result<Self, Self::Error> {
+        Ok(Self {
+            sign: narrow(value.sign)?,
+        })
+    }
+}
+
+impl From<&types::SvcCenterPrint> for v1::SvcCenterPrint {
+    fn from(value: &types::SvcCenterPrint) -> Self {
+        Self {
+            message: value.message.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcCenterPrint> for types::SvcCenterPrint {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcCenterPrint) -> Result<Self, Self::Error> {
+        Ok(Self {
+            message: value.message,
+        })
+    }
+}
+
+impl From<&types::SvcSpawnStaticSound> for v1::SvcSpawnStaticSound {
+    fn from(value: &types::SvcSpawnStaticSound) -> Self {
+        Self {
+            origin: value.origin.iter().map(|&coord| coord.into()).collect(),
+            sound_index: value.sound_index.into(),
+            volume: value.volume.into(),
+            attenuation: value.attenuation.into(),
+            entity_index: value.entity_index.into(),
+            pitch: value.pitch.into(),
+            flags: value.flags.into(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcSpawnStaticSound> for types::SvcSpawnStaticSound {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcSpawnStaticSound) -> Result<Self, Self::Error> {
+        Ok(Self {
+            origin: value
+                .origin
+                .into_iter()
+                .map(narrow)
+                .collect::<Result<_, _>>()?,
+            sound_index: narrow(value.sound_index)?,
+            volume: narrow(value.volume)?,
+            attenuation: narrow(value.attenuation)?,
+            entity_index: narrow(value.entity_index)?,
+            pitch: narrow(value.pitch)?,
+            flags: narrow(value.flags)?,
+        })
+    }
+}
+
+impl From<&types::SvcFinale> for v1::SvcFinale {
+    fn from(value: &types::SvcFinale) -> Self {
+        Self {
+            text: value.text.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcFinale> for types::SvcFinale {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcFinale) -> Result<Self, Self::Error> {
+        Ok(Self { text: value.text })
+    }
+}
+
+impl From<&types::SvcCdTrack> for v1::SvcCdTrack {
+    fn from(value: &types::SvcCdTrack) -> Self {
+        Self {
+            track: value.track.into(),
+            loop_track: value.loop_track.into(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcCdTrack> for types::SvcCdTrack {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcCdTrack) -> Result<Self, Self::Error> {
+        Ok(Self {
+            track: narrow(value.track)?,
+            loop_track: narrow(value.loop_track)?,
+        })
+    }
+}
+
+impl From<&types::SvcRestore> for v1::SvcRestore {
+    fn from(value: &types::SvcRestore) -> Self {
+        Self {
+            save_name: value.save_name.clone(),
+            map_count: value.map_count.into(),
+            map_names: value.map_names.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcRestore> for types::SvcRestore {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcRestore) -> Result<Self, Self::Error> {
+        Ok(Self {
+            save_name: value.save_name,
+            map_count: narrow(value.map_count)?,
+            map_names: value.map_names,
+        })
+    }
+}
+
+impl From<&types::SvcCutscene> for v1::SvcCutscene {
+    fn from(value: &types::SvcCutscene) -> Self {
+        Self {
+            text: value.text.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcCutscene> for types::SvcCutscene {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcCutscene) -> Result<Self, Self::Error> {
+        Ok(Self { text: value.text })
+    }
+}
+
+impl From<&types::SvcWeaponAnim> for v1::SvcWeaponAnim {
+    fn from(value: &types::SvcWeaponAnim) -> Self {
+        Self {
+            sequence_number: value.sequence_number.into(),
+            weapon_model_body_group: value.weapon_model_body_group.into(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcWeaponAnim> for types::SvcWeaponAnim {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcWeaponAnim) -> Result<Self, Self::Error> {
+        Ok(Self {
+            sequence_number: narrow(value.sequence_number)?,
+            weapon_model_body_group: narrow(value.weapon_model_body_group)?,
+        })
+    }
+}
+
+impl From<&types::SvcDecalName> for v1::SvcDecalName {
+    fn from(value: &types::SvcDecalName) -> Self {
+        Self {
+            position_index: value.position_index.into(),
+            decal_name: value.decal_name.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcDecalName> for types::SvcDecalName {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcDecalName) -> Result<Self, Self::Error> {
+        Ok(Self {
+            position_index: narrow(value.position_index)?,
+            decal_name: value.decal_name,
+        })
+    }
+}
+
+impl From<&types::SvcRoomType> for v1::SvcRoomType {
+    fn from(value: &types::SvcRoomType) -> Self {
+        Self {
+            room_type: value.room_type.into(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcRoomType> for types::SvcRoomType {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcRoomType) -> Result<Self, Self::Error> {
+        Ok(Self {
+            room_type: narrow(value.room_type)?,
+        })
+    }
+}
+
+impl From<&types::SvcAddAngle> for v1::SvcAddAngle {
+    fn from(value: &types::SvcAddAngle) -> Self {
+        Self {
+            angle_to_add: value.angle_to_add.into(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcAddAngle> for types::SvcAddAngle {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcAddAngle) -> Result<Self, Self::Error> {
+        Ok(Self {
+            angle_to_add: narrow(value.angle_to_add)?,
+        })
+    }
+}
+
+impl From<&types::SvcNewUserMsg> for v1::SvcNewUserMsg {
+    fn from(value: &types::SvcNewUserMsg) -> Self {
+        Self {
+            index: value.index.into(),
+            size: value.size.into(),
+            name: value.name.0.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcNewUserMsg> for types::SvcNewUserMsg {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcNewUserMsg) -> Result<Self, Self::Error> {
+        Ok(Self {
+            index: narrow(value.index)?,
+            size: narrow(value.size)?,
+            name: value.name.into(),
+        })
+    }
+}
+
+impl From<&types::SvcPacketEntities> for v1::SvcPacketEntities {
+    fn from(value: &types::SvcPacketEntities) -> Self {
+        Self {
+            entity_count: value.entity_count.into(),
+            entity_states: value.entity_states.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcPacketEntities> for types::SvcPacketEntities {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcPacketEntities) -> Result<Self, Self::Error> {
+        Ok(Self {
+            entity_count: narrow(value.entity_count)?,
+            entity_states: all(value.entity_states)?,
+        })
+    }
+}
+
+impl From<&types::EntityState> for v1::EntityState {
+    fn from(value: &types::EntityState) -> Self {
+        Self {
+            entity_index: value.entity_index.into(),
+            increment_entity_number: value.increment_entity_number,
+            is_absolute_entity_index: value.is_absolute_entity_index,
+            absolute_entity_index: value.absolute_entity_index.map(Into::into),
+            entity_index_difference: value.entity_index_difference.map(Into::into),
+            has_custom_delta: value.has_custom_delta,
+            has_baseline_index: value.has_baseline_index,
+            baseline_index: value.baseline_index.map(Into::into),
+            delta: Some((&value.delta).into()),
+        }
+    }
+}
+
+impl TryFrom<v1::EntityState> for types::EntityState {
+    type Error = DemoError;
+
+    fn try_from(value: v1::EntityState) -> Result<Self, Self::Error> {
+        Ok(Self {
+            entity_index: narrow(value.entity_index)?,
+            increment_entity_number: value.increment_entity_number,
+            is_absolute_entity_index: value.is_absolute_entity_index,
+            absolute_entity_index: value.absolute_entity_index.map(narrow).transpose()?,
+            entity_index_difference: value.entity_index_difference.map(narrow).transpose()?,
+            has_custom_delta: value.has_custom_delta,
+            has_baseline_index: value.has_baseline_index,
+            baseline_index: value.baseline_index.map(narrow).transpose()?,
+            delta: delta(value.delta)?,
+        })
+    }
+}
+
+impl From<&types::SvcDeltaPacketEntities> for v1::SvcDeltaPacketEntities {
+    fn from(value: &types::SvcDeltaPacketEntities) -> Self {
+        Self {
+            entity_count: value.entity_count.into(),
+            delta_sequence: value.delta_sequence.into(),
+            entity_states: value.entity_states.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcDeltaPacketEntities> for types::SvcDeltaPacketEntities {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcDeltaPacketEntities) -> Result<Self, Self::Error> {
+        Ok(Self {
+            entity_count: narrow(value.entity_count)?,
+            delta_sequence: narrow(value.delta_sequence)?,
+            entity_states: all(value.entity_states)?,
+        })
+    }
+}
+
+impl From<&types::EntityStateDelta> for v1::EntityStateDelta {
+    fn from(value: &types::EntityStateDelta) -> Self {
+        Self {
+            entity_index: value.entity_index.into(),
+            remove_entity: value.remove_entity,
+            is_absolute_entity_index: value.is_absolute_entity_index,
+            absolute_entity_index: value.absolute_entity_index.map(Into::into),
+            entity_index_difference: value.entity_index_difference.map(Into::into),
+            has_custom_delta: value.has_custom_delta,
+            delta: value.delta.as_ref().map(Into::into),
+        }
+    }
+}
+
+impl TryFrom<v1::EntityStateDelta> for types::EntityStateDelta {
+    type Error = DemoError;
+
+    fn try_from(value: v1::EntityStateDelta) -> Result<Self, Self::Error> {
+        Ok(Self {
+            entity_index: narrow(value.entity_index)?,
+            remove_entity: value.remove_entity,
+            is_absolute_entity_index: value.is_absolute_entity_index,
+            absolute_entity_index: value.absolute_entity_index.map(narrow).transpose()?,
+            entity_index_difference: value.entity_index_difference.map(narrow).transpose()?,
+            has_custom_delta: value.has_custom_delta,
+            delta: value.delta.map(TryInto::try_into).transpose()?,
+        })
+    }
+}
+
+impl From<&types::SvcResourceList> for v1::SvcResourceList {
+    fn from(value: &types::SvcResourceList) -> Self {
+        Self {
+            resource_count: value.resource_count.into(),
+            resources: value.resources.iter().map(Into::into).collect(),
+            consistencies: value.consistencies.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcResourceList> for types::SvcResourceList {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcResourceList) -> Result<Self, Self::Error> {
+        Ok(Self {
+            resource_count: narrow(value.resource_count)?,
+            resources: all(value.resources)?,
+            consistencies: all(value.consistencies)?,
+        })
+    }
+}
+
+impl From<&types::Resource> for v1::Resource {
+    fn from(value: &types::Resource) -> Self {
+        Self {
+            r#type: value.type_.into(),
+            name: value.name.clone(),
+            index: value.index.into(),
+            size: value.size,
+            flags: value.flags.into(),
+            md5_hash: value.md5_hash.map(Vec::from),
+            has_extra_info: value.has_extra_info,
+            extra_info: value.extra_info.map(Vec::from),
+        }
+    }
+}
+
+impl TryFrom<v1::Resource> for types::Resource {
+    type Error = DemoError;
+
+    fn try_from(value: v1::Resource) -> Result<Self, Self::Error> {
+        Ok(Self {
+            type_: narrow(value.r#type)?,
+            name: value.name,
+            index: narrow(value.index)?,
+            size: value.size,
+            flags: narrow(value.flags)?,
+            md5_hash: value.md5_hash.map(array).transpose()?,
+            has_extra_info: value.has_extra_info,
+            extra_info: value.extra_info.map(array).transpose()?,
+        })
+    }
+}
+
+impl From<&types::Consistency> for v1::Consistency {
+    fn from(value: &types::Consistency) -> Self {
+        Self {
+            is_short_index: value.is_short_index,
+            short_index: value.short_index.map(Into::into),
+            long_index: value.long_index.map(Into::into),
+        }
+    }
+}
+
+impl TryFrom<v1::Consistency> for types::Consistency {
+    type Error = DemoError;
+
+    fn try_from(value: v1::Consistency) -> Result<Self, Self::Error> {
+        Ok(Self {
+            is_short_index: value.is_short_index,
+            short_index: value.short_index.map(narrow).transpose()?,
+            long_index: value.long_index.map(narrow).transpose()?,
+        })
+    }
+}
+
+impl From<&types::SvcNewMovevars> for v1::SvcNewMovevars {
+    fn from(value: &types::SvcNewMovevars) -> Self {
+        Self {
+            gravity: value.gravity,
+            stop_speed: value.stop_speed,
+            max_speed: value.max_speed,
+            spectator_max_speed: value.spectator_max_speed,
+            accelerate: value.accelerate,
+            airaccelerate: value.airaccelerate,
+            water_accelerate: value.water_accelerate,
+            friction: value.friction,
+            edge_friction: value.edge_friction,
+            water_friction: value.water_friction,
+            ent_gravity: value.ent_garvity,
+            bounce: value.bounce,
+            step_size: value.step_size,
+            max_velocity: value.max_velocity,
+            z_max: value.z_max,
+            wave_height: value.wave_height,
+            footsteps: value.footsteps.into(),
+            roll_angle: value.roll_angle,
+            roll_speed: value.roll_speed,
+            sky_color: value.sky_color.clone(),
+            sky_vec: value.sky_vec.clone(),
+            sky_name: value.sky_name.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcNewMovevars> for types::SvcNewMovevars {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcNewMovevars) -> Result<Self, Self::Error> {
+        Ok(Self {
+            gravity: value.gravity,
+            stop_speed: value.stop_speed,
+            max_speed: value.max_speed,
+            spectator_max_speed: value.spectator_max_speed,
+            accelerate: value.accelerate,
+            airaccelerate: value.airaccelerate,
+            water_accelerate: value.water_accelerate,
+            friction: value.friction,
+            edge_friction: value.edge_friction,
+            water_friction: value.water_friction,
+            ent_garvity: value.ent_gravity,
+            bounce: value.bounce,
+            step_size: value.step_size,
+            max_velocity: value.max_velocity,
+            z_max: value.z_max,
+            wave_height: value.wave_height,
+            footsteps: narrow(value.footsteps)?,
+            roll_angle: value.roll_angle,
+            roll_speed: value.roll_speed,
+            sky_color: value.sky_color,
+            sky_vec: value.sky_vec,
+            sky_name: value.sky_name,
+        })
+    }
+}
+
+impl From<&types::SvcResourceRequest> for v1::SvcResourceRequest {
+    fn from(value: &types::SvcResourceRequest) -> Self {
+        Self {
+            spawn_count: value.spawn_count,
+            unknown: value.unknown.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcResourceRequest> for types::SvcResourceRequest {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcResourceRequest) -> Result<Self, Self::Error> {
+        Ok(Self {
+            spawn_count: value.spawn_count,
+            unknown: value.unknown,
+        })
+    }
+}
+
+impl From<&types::SvcCustomization> for v1::SvcCustomization {
+    fn from(value: &types::SvcCustomization) -> Self {
+        Self {
+            player_index: value.player_index.into(),
+            r#type: value.type_.into(),
+            name: value.name.clone(),
+            index: value.index.into(),
+            download_size: value.download_size,
+            flags: value.flags.into(),
+            md5_hash: value.md5_hash.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcCustomization> for types::SvcCustomization {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcCustomization) -> Result<Self, Self::Error> {
+        Ok(Self {
+            player_index: narrow(value.player_index)?,
+            type_: narrow(value.r#type)?,
+            name: value.name,
+            index: narrow(value.index)?,
+            download_size: value.download_size,
+            flags: narrow(value.flags)?,
+            md5_hash: value.md5_hash,
+        })
+    }
+}
+
+impl From<&types::SvcCrosshairAngle> for v1::SvcCrosshairAngle {
+    fn from(value: &types::SvcCrosshairAngle) -> Self {
+        Self {
+            pitch: value.pitch.into(),
+            yaw: value.yaw.into(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcCrosshairAngle> for types::SvcCrosshairAngle {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcCrosshairAngle) -> Result<Self, Self::Error> {
+        Ok(Self {
+            pitch: narrow(value.pitch)?,
+            yaw: narrow(value.yaw)?,
+        })
+    }
+}
+
+impl From<&types::SvcSoundFade> for v1::SvcSoundFade {
+    fn from(value: &types::SvcSoundFade) -> Self {
+        Self {
+            initial_percent: value.initial_percent.into(),
+            hold_time: value.hold_time.into(),
+            fade_out_time: value.fade_out_time.into(),
+            fade_in_time: value.fade_in_time.into(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcSoundFade> for types::SvcSoundFade {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcSoundFade) -> Result<Self, Self::Error> {
+        Ok(Self {
+            initial_percent: narrow(value.initial_percent)?,
+            hold_time: narrow(value.hold_time)?,
+            fade_out_time: narrow(value.fade_out_time)?,
+            fade_in_time: narrow(value.fade_in_time)?,
+        })
+    }
+}
+
+impl From<&types::SvcFileTxferFailed> for v1::SvcFileTxferFailed {
+    fn from(value: &types::SvcFileTxferFailed) -> Self {
+        Self {
+            file_name: value.file_name.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcFileTxferFailed> for types::SvcFileTxferFailed {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcFileTxferFailed) -> Result<Self, Self::Error> {
+        Ok(Self {
+            file_name: value.file_name,
+        })
+    }
+}
+
+impl From<&types::SvcHltv> for v1::SvcHltv {
+    fn from(value: &types::SvcHltv) -> Self {
+        Self {
+            mode: value.mode.into(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcHltv> for types::SvcHltv {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcHltv) -> Result<Self, Self::Error> {
+        Ok(Self {
+            mode: narrow(value.mode)?,
+        })
+    }
+}
+
+impl From<&types::SvcDirector> for v1::SvcDirector {
+    fn from(value: &types::SvcDirector) -> Self {
+        Self {
+            length: value.length.into(),
+            command: value.command.into(),
+            message: value.message.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcDirector> for types::SvcDirector {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcDirector) -> Result<Self, Self::Error> {
+        Ok(Self {
+            length: narrow(value.length)?,
+            command: narrow(value.command)?,
+            message: value.message,
+        })
+    }
+}
+
+impl From<&types::SvcVoiceInit> for v1::SvcVoiceInit {
+    fn from(value: &types::SvcVoiceInit) -> Self {
+        Self {
+            codec_name: value.codec_name.clone(),
+            quality: value.quality.into(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcVoiceInit> for types::SvcVoiceInit {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcVoiceInit) -> Result<Self, Self::Error> {
+        Ok(Self {
+            codec_name: value.codec_name,
+            quality: narrow(value.quality)?,
+        })
+    }
+}
+
+impl From<&types::SvcVoiceData> for v1::SvcVoiceData {
+    fn from(value: &types::SvcVoiceData) -> Self {
+        Self {
+            player_index: value.player_index.into(),
+            size: value.size.into(),
+            data: value.data.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcVoiceData> for types::SvcVoiceData {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcVoiceData) -> Result<Self, Self::Error> {
+        Ok(Self {
+            player_index: narrow(value.player_index)?,
+            size: narrow(value.size)?,
+            data: value.data,
+        })
+    }
+}
+
+impl From<&types::SvcSendExtraInfo> for v1::SvcSendExtraInfo {
+    fn from(value: &types::SvcSendExtraInfo) -> Self {
+        Self {
+            fallback_dir: value.fallback_dir.clone(),
+            can_cheat: value.can_cheat.into(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcSendExtraInfo> for types::SvcSendExtraInfo {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcSendExtraInfo) -> Result<Self, Self::Error> {
+        Ok(Self {
+            fallback_dir: value.fallback_dir,
+            can_cheat: narrow(value.can_cheat)?,
+        })
+    }
+}
+
+impl From<&types::SvcTimeScale> for v1::SvcTimeScale {
+    fn from(value: &types::SvcTimeScale) -> Self {
+        Self {
+            time_scale: value.time_scale,
+        }
+    }
+}
+
+impl TryFrom<v1::SvcTimeScale> for types::SvcTimeScale {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcTimeScale) -> Result<Self, Self::Error> {
+        Ok(Self {
+            time_scale: value.time_scale,
+        })
+    }
+}
+
+impl From<&types::SvcResourceLocation> for v1::SvcResourceLocation {
+    fn from(value: &types::SvcResourceLocation) -> Self {
+        Self {
+            download_url: value.download_url.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcResourceLocation> for types::SvcResourceLocation {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcResourceLocation) -> Result<Self, Self::Error> {
+        Ok(Self {
+            download_url: value.download_url,
+        })
+    }
+}
+
+impl From<&types::SvcSendCvarValue> for v1::SvcSendCvarValue {
+    fn from(value: &types::SvcSendCvarValue) -> Self {
+        Self {
+            name: value.name.0.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcSendCvarValue> for types::SvcSendCvarValue {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcSendCvarValue) -> Result<Self, Self::Error> {
+        Ok(Self {
+            name: value.name.into(),
+        })
+    }
+}
+
+impl From<&types::SvcSendCvarValue2> for v1::SvcSendCvarValue2 {
+    fn from(value: &types::SvcSendCvarValue2) -> Self {
+        Self {
+            request_id: value.request_id,
+            name: value.name.0.clone(),
+        }
+    }
+}
+
+impl TryFrom<v1::SvcSendCvarValue2> for types::SvcSendCvarValue2 {
+    type Error = DemoError;
+
+    fn try_from(value: v1::SvcSendCvarValue2) -> Result<Self, Self::Error> {
+        Ok(Self {
+            request_id: value.request_id,
+            name: value.name.into(),
+        })
+    }
+}
+
+/// A demo integer from its wider protobuf type.
+fn narrow<T: TryFrom<V>, V>(value: V) -> Result<T, DemoError> {
+    T::try_from(value).map_err(|_| DemoError::InvalidProto)
+}
+
+/// A message field the demo cannot do without.
+fn required<T: TryFrom<V, Error = DemoError>, V>(value: Option<V>) -> Result<T, DemoError> {
+    value.ok_or(DemoError::InvalidProto)?.try_into()
+}
+
+/// An empty delta is the same as a missing one, which is how other writers may send it.
+fn delta(value: Option<v1::Delta>) -> Result<Delta, DemoError> {
+    value
+        .map(TryInto::try_into)
+        .transpose()
+        .map(Option::unwrap_or_default)
+}
+
+fn all<T: TryFrom<V, Error = DemoError>, V>(values: Vec<V>) -> Result<Vec<T>, DemoError> {
+    values.into_iter().map(TryInto::try_into).collect()
+}
+
+fn array<const N: usize>(bytes: Vec<u8>) -> Result<[u8; N], DemoError> {
+    bytes.try_into().map_err(|_| DemoError::InvalidProto)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::{open_demo, types::*};
+
+    #[test]
+    fn round_trip() {
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let bytes = demo.to_proto_bytes();
+
+        let from_proto = Demo::from_proto_bytes(&bytes).unwrap();
+
+        assert_eq!(
+            from_proto.write_to_bytes(),
+            std::fs::read("./src/tests/weapon.dem").unwrap()
+        );
+        // deltas are maps sorted by name, so the encoding does not change between runs
+        assert_eq!(from_proto.to_proto_bytes(), bytes);
+    }
+
+    #[test]
+    fn invalid() {
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+
+        let mut proto = demo.to_proto();
+        proto.schema_version = 2;
+
+        assert!(matches!(
+            Demo::from_proto(proto),
+            Err(DemoError::UnsupportedSchema { version: 2 })
+        ));
+
+        let mut frame = v1::Frame::from(&demo.directory.entries[1].frames[0]);
+        frame.data = None;
+
+        assert!(matches!(
+            Frame::try_from(frame),
+            Err(DemoError::InvalidProto)
+        ));
+
+        let value = v1::DeltaValue {
+            value: Some(v1::delta_value::Value::ByteUnsigned(256)),
+        };
+
+        assert!(DeltaValue::try_from(value).is_err());
+        assert!(Demo::from_proto_bytes(b"not a demo").is_err());
+    }
+
+    /// Goes through the protocol buffer bytes and back. Deltas have at most one field so the
+    /// debug output of the hash maps does not depend on their order.
+    fn assert_round_trip(message: EngineMessage) {
+        let bytes = v1::EngineMessage::from(&message).encode_to_vec();
+        let back = EngineMessage::try_from(v1::EngineMessage::decode(bytes.as_slice()).unwrap());
+
+        assert_eq!(format!("{:?}", back.unwrap()), format!("{message:?}"));
+    }
+
+    fn one_field_delta() -> Delta {
+        Delta::from([("origin[0]".to_owned(), DeltaValue::FloatSigned(-1.5))])
+    }
+
+    fn origin_coord() -> OriginCoord {
+        OriginCoord {
+            int_flag: true,
+            fraction_flag: true,
+            is_negative: Some(true),
+            int_value: Some(300),
+            fraction_value: Some(3),
+        }
+    }
+
+    #[test]
+    fn engine_messages() {
+        let messages = vec![
+            EngineMessage::SvcBad,
+            EngineMessage::SvcNop,
+            EngineMessage::SvcDisconnect(SvcDisconnect {
+                reason: b"kicked\0".to_vec(),
+            }),
+            EngineMessage::SvcEvent(SvcEvent {
+                event_count: 2,
+                events: vec![
+                    EventS {
+                        event_index: 12,
+                        has_packet_index: true,
+                        packet_index: Some(34),
+                        has_delta: Some(true),
+                        delta: Some(one_field_delta()),
+                        has_fire_time: true,
+                        fire_time: Some(56),
+                    },
+                    EventS {
+                        event_index: 7,
+                        has_packet_index: false,
+                        packet_index: None,
+                        has_delta: None,
+                        delta: None,
+                        has_fire_time: false,
+                        fire_time: None,
+                    },
+                ],
+            }),
+            EngineMessage::SvcVersion(SvcVersion {
+                protocol_version: 48,
+            }),
+            EngineMessage::SvcSetView(SvcSetView { entity_index: -3 }),
+            EngineMessage::SvcSound(Box::new(SvcSound {
+                flags: 0x1ff,
+                volume: Some(200),
+                attenuation: Some(64),
+                channel: 6,
+                entity_index: 2000,
+                sound_index_long: Some(600),
+                sound_index_short: None,
+                has_x: true,
+                has_y: false,
+                has_z: true,
+                origin_x: Some(origin_coord()),
+                origin_y: None,
+                origin_z: Some(OriginCoord {
+                    int_flag: false,
+                    fraction_flag: false,
+                    is_negative: None,
+                    int_value: None,
+                    fraction_value: None,
+                }),
+                pitch: 110,
+            })),
+            EngineMessage::SvcTime(SvcTime { time: 12.25 }),
+            EngineMessage::SvcPrint(SvcPrint {
+                message: b"hello\n\0".to_vec().into(),
+            }),
+            EngineMessage::SvcStuffText(SvcStuffText {
+                command: b"+attack\0".to_vec().into(),
+            }),
+            EngineMessage::SvcSetAngle(SvcSetAngle {
+                pitch: -100,
+                yaw: 200,
+                roll: 3,
+            }),
+            EngineMessage::SvcServerInfo(SvcServerInfo {
+                protocol: 48,
+                spawn_count: 5,
+                map_checksum: -123456,
+                client_dll_hash: vec![9; 16].into(),
+                max_players: 32,
+                player_index: 4,
+                is_deathmatch: 1,
+                game_dir: b"valve\0".to_vec(),
+                hostname: b"server\0".to_vec(),
+                map_file_name: b"maps/crossfire.bsp\0".to_vec(),
+                map_cycle: b"crossfire\0".to_vec(),
+                unknown: 1,
+            }),
+            EngineMessage::SvcLightStyle(SvcLightStyle {
+                index: 11,
+                light_info: b"abcz\0".to_vec(),
+            }),
+            EngineMessage::SvcUpdateUserInfo(SvcUpdateUserInfo {
+                index: 3,
+                id: 77,
+                user_info: b"\\name\\player\0".to_vec().into(),
+                cd_key_hash: vec![1; 16].into(),
+            }),
+            EngineMessage::SvcDeltaDescription(SvcDeltaDescription {
+                name: b"weapon_data_t\0".to_vec(),
+                total_fields: 1,
+                fields: vec![DeltaDecoderS {
+                    name: "m_iId".to_owned(),
+                    bits: 5,
+                    divisor: 1.,
+                    flags: 1 << 3,
+                }],
+                clone: vec![],
+            }),
+            EngineMessage::SvcClientData(SvcClientData {
+                has_delta_update_mask: true,
+                delta_update_mask: Some(9),
+                client_data: one_field_delta(),
+                weapon_data: Some(vec![ClientDataWeaponData {
+                    weapon_index: 17,
+                    weapon_data: one_field_delta(),
+                }]),
+            }),
+            EngineMessage::SvcStopSound(SvcStopSound { entity_index: 42 }),
+            EngineMessage::SvcPings(SvcPings {
+                pings: vec![
+                    PingS {
+                        has_ping_data: true,
+                        player_id: Some(2),
+                        ping: Some(80),
+                        loss: Some(1),
+                    },
+                    PingS {
+                        has_ping_data: false,
+                        player_id: None,
+                        ping: None,
+                        loss: None,
+                    },
+                ],
+            }),
+            EngineMessage::SvcParticle(SvcParticle {
+                origin: vec![1, -2, 3],
+                direction: vec![4, 5, 6],
+                count: 7,
+                color: 8,
+            }),
+            EngineMessage::SvcDamage,
+            EngineMessage::SvcSpawnStatic(SvcSpawnStatic {
+                model_index: 10,
+                sequence: -1,
+                frame: 2,
+                color_map: 3,
+                skin: -4,
+                origin_x: 500,
+                rotation_x: 5,
+                origin_y: -600,
+                rotation_y: 6,
+                origin_z: 700,
+                rotation_z: -7,
+                has_render_mode: 1,
+                render_color: Some(vec![255, 128, 0]),
+            }),
+            EngineMessage::SvcEventReliable(SvcEventReliable {
+                event_index: 300,
+                event_args: one_field_delta(),
+                has_fire_time: true,
+                fire_time: Some(1000),
+            }),
+            EngineMessage::SvcSpawnBaseline(SvcSpawnBaseline {
+                entities: vec![EntityS {
+                    entity_index: 1,
+                    index: 2,
+                    type_: 1,
+                    delta: one_field_delta(),
+                }],
+                total_extra_data: 1,
+                extra_data: vec![one_field_delta()],
+            }),
+            EngineMessage::SvcTempEntity(SvcTempEntity {
+                entity_type: 2,
+                entity: TempEntity::TeGunshot(vec![1, 2, 3, 4, 5, 6]),
+            }),
+            EngineMessage::SvcSetPause(SvcSetPause { is_paused: 1 }),
+            EngineMessage::SvcSignOnNum(SvcSignOnNum { sign: 2 }),
+            EngineMessage::SvcCenterPrint(SvcCenterPrint {
+                message: b"center\0".to_vec(),
+            }),
+            EngineMessage::SvcKilledMonster,
+            EngineMessage::SvcFoundSecret,
+            EngineMessage::SvcSpawnStaticSound(SvcSpawnStaticSound {
+                origin: vec![-8, 16, 32],
+                sound_index: 99,
+                volume: 255,
+                attenuation: 2,
+                entity_index: 12,
+                pitch: 100,
+                flags: 4,
+            }),
+            EngineMessage::SvcIntermission,
+            EngineMessage::SvcFinale(SvcFinale {
+                text: b"the end\0".to_vec(),
+            }),
+            EngineMessage::SvcCdTrack(SvcCdTrack {
+                track: 3,
+                loop_track: -1,
+            }),
+            EngineMessage::SvcRestore(SvcRestore {
+                save_name: b"quick\0".to_vec(),
+                map_count: 2,
+                map_names: vec![b"c1a0\0".to_vec(), b"c1a1\0".to_vec()],
+            }),
+            EngineMessage::SvcCutscene(SvcCutscene {
+                text: b"scene\0".to_vec(),
+            }),
+            EngineMessage::SvcWeaponAnim(SvcWeaponAnim {
+                sequence_number: 5,
+                weapon_model_body_group: -2,
+            }),
+            EngineMessage::SvcDecalName(SvcDecalName {
+                position_index: 9,
+                decal_name: b"{blood1\0".to_vec(),
+            }),
+            EngineMessage::SvcRoomType(SvcRoomType { room_type: 26 }),
+            EngineMessage::SvcAddAngle(SvcAddAngle { angle_to_add: -90 }),
+            EngineMessage::SvcNewUserMsg(SvcNewUserMsg {
+                index: 64,
+                size: -1,
+                name: b"SayText\0\0\0\0\0\0\0\0\0".to_vec().into(),
+            }),
+            EngineMessage::SvcPacketEntities(SvcPacketEntities {
+                entity_count: 1,
+                entity_states: vec![EntityState {
+                    entity_index: 40,
+                    increment_entity_number: false,
+                    is_absolute_entity_index: Some(true),
+                    absolute_entity_index: Some(40),
+                    entity_index_difference: None,
+                    has_custom_delta: true,
+                    has_baseline_index: true,
+                    baseline_index: Some(3),
+                    delta: one_field_delta(),
+                }],
+            }),
+            EngineMessage::SvcDeltaPacketEntities(SvcDeltaPacketEntities {
+                entity_count: 2,
+                delta_sequence: 200,
+                entity_states: vec![
+                    EntityStateDelta {
+                        entity_index: 5,
+                        remove_entity: false,
+                        is_absolute_entity_index: false,
+                        absolute_entity_index: None,
+                        entity_index_difference: Some(4),
+                        has_custom_delta: Some(false),
+                        delta: Some(one_field_delta()),
+                    },
+                    EntityStateDelta {
+                        entity_index: 900,
+                        remove_entity: true,
+                        is_absolute_entity_index: true,
+                        absolute_entity_index: Some(900),
+                        entity_index_difference: None,
+                        has_custom_delta: None,
+                        delta: None,
+                    },
+                ],
+            }),
+            EngineMessage::SvcChoke,
+            EngineMessage::SvcResourceList(SvcResourceList {
+                resource_count: 2,
+                resources: vec![
+                    Resource {
+                        type_: 1,
+                        name: "models/player.mdl".to_owned(),
+                        index: 3,
+                        size: 123456,
+                        flags: 4,
+                        md5_hash: Some([7; 16]),
+                        has_extra_info: true,
+                        extra_info: Some([8; 32]),
+                    },
+                    Resource {
+                        type_: 0,
+                        name: "weapons/ak47-1.wav".to_owned(),
+                        index: 4,
+                        size: 0,
+                        flags: 0,
+                        md5_hash: None,
+                        has_extra_info: false,
+                        extra_info: None,
+                    },
+                ],
+                consistencies: vec![
+                    Consistency {
+                        is_short_index: Some(true),
+                        short_index: Some(3),
+                        long_index: None,
+                    },
+                    Consistency {
+                        is_short_index: Some(false),
+                        short_index: None,
+                        long_index: Some(700),
+                    },
+                ],
+            }),
+            EngineMessage::SvcNewMovevars(SvcNewMovevars {
+                gravity: 800.,
+                stop_speed: 100.,
+                max_speed: 320.,
+                spectator_max_speed: 500.,
+                accelerate: 10.,
+                airaccelerate: 100.,
+                water_accelerate: 10.,
+                friction: 4.,
+                edge_friction: 2.,
+                water_friction: 1.,
+                ent_garvity: 1.,
+                bounce: 1.,
+                step_size: 18.,
+                max_velocity: 2000.,
+                z_max: 4096.,
+                wave_height: 0.5,
+                footsteps: 1,
+                roll_angle: 2.,
+                roll_speed: 200.,
+                sky_color: vec![1., 2., 3.],
+                sky_vec: vec![0., 0.5, -1.],
+                sky_name: b"desert\0".to_vec(),
+            }),
+            EngineMessage::SvcResourceRequest(SvcResourceRequest {
+                spawn_count: 3,
+                unknown: vec![0, 0, 0, 0],
+            }),
+            EngineMessage::SvcCustomization(SvcCustomization {
+                player_index: 2,
+                type_: 3,
+                name: b"logo.wad\0".to_vec(),
+                index: 5,
+                download_size: 4096,
+                flags: 1,
+                md5_hash: Some(vec![6; 16]),
+            }),
+            EngineMessage::SvcCrosshairAngle(SvcCrosshairAngle { pitch: -5, yaw: 10 }),
+            EngineMessage::SvcSoundFade(SvcSoundFade {
+                initial_percent: 50,
+                hold_time: 2,
+                fade_out_time: 3,
+                fade_in_time: 4,
+            }),
+            EngineMessage::SvcFileTxferFailed(SvcFileTxferFailed {
+                file_name: b"maps/missing.bsp\0".to_vec(),
+            }),
+            EngineMessage::SvcHltv(SvcHltv { mode: 1 }),
+            EngineMessage::SvcDirector(SvcDirector {
+                length: 3,
+                command: 1,
+                message: vec![2, 3],
+            }),
+            EngineMessage::SvcVoiceInit(SvcVoiceInit {
+                codec_name: b"voice_speex\0".to_vec(),
+                quality: 5,
+            }),
+            EngineMessage::SvcVoiceData(SvcVoiceData {
+                player_index: 1,
+                size: 3,
+                data: vec![10, 20, 30],
+            }),
+            EngineMessage::SvcSendExtraInfo(SvcSendExtraInfo {
+                fallback_dir: b"valve\0".to_vec(),
+                can_cheat: 1,
+            }),
+            EngineMessage::SvcTimeScale(SvcTimeScale { time_scale: 0.5 }),
+            EngineMessage::SvcResourceLocation(SvcResourceLocation {
+                download_url: b"http://example.com/\0".to_vec(),
+            }),
+            EngineMessage::SvcSendCvarValue(SvcSendCvarValue {
+                name: b"fps_max\0".to_vec().into(),
+            }),
+            EngineMessage::SvcSendCvarValue2(SvcSendCvarValue2 {
+                request_id: 1234,
+                name: b"cl_updaterate\0".to_vec().into(),
+            }),
+        ];
+
+        let names: std::collections::HashSet<_> =
+            messages.iter().map(EngineMessage::name).collect();
+        assert_eq!(names.len(), 59);
+
+        messages.into_iter().for_each(assert_round_trip);
+    }
+
+    #[test]
+    fn temp_entities() {
+        // sizes from the comments on TempEntity
+        let entities = vec![
+            TempEntity::TeBeamPoints(TeBeamPoints {
+                start_position: vec![1, -2, 3],
+                end_position: vec![-4, 5, -6],
+                sprite_index: 7,
+                start_frame: 8,
+                frame_rate: 9,
+                life: 10,
+                width: 11,
+                noise: 12,
+                color: vec![255, 0, 128, 200],
+                speed: 13,
+            }),
+            TempEntity::TeBeamEntPoint(vec![1; 20]),
+            TempEntity::TeGunshot(vec![2; 6]),
+            TempEntity::TeExplosion(vec![3; 11]),
+            TempEntity::TeTarExplosion(vec![4; 6]),
+            TempEntity::TeSmoke(vec![5; 10]),
+            TempEntity::TeTracer(vec![6; 12]),
+            TempEntity::TeLightning(vec![7; 17]),
+            TempEntity::TeBeamEnts(vec![8; 16]),
+            TempEntity::TeSparks(vec![9; 6]),
+            TempEntity::TeLavaSplash(vec![10; 6]),
+            TempEntity::TeTeleport(vec![11; 6]),
+            TempEntity::TeExplosion2(vec![12; 8]),
+            TempEntity::TeBspDecal(TeBspDecal {
+                unknown1: vec![1, 2, 3, 4, 5, 6, 7, 8],
+                entity_index: 3,
+                unknown2: Some(vec![9, 10]),
+            }),
+            TempEntity::TeImplosion(vec![14; 9]),
+            TempEntity::TeSpriteTrail(vec![15; 19]),
+            TempEntity::TeSprite(vec![17; 10]),
+            TempEntity::TeBeamSprite(vec![18; 16]),
+            TempEntity::TeBeamTorus(vec![19; 24]),
+            TempEntity::TeBeamDisk(vec![20; 24]),
+            TempEntity::TeBeamCylinder(vec![21; 24]),
+            TempEntity::TeBeamFollow(vec![22; 10]),
+            TempEntity::TeGlowSprite(vec![23; 11]),
+            TempEntity::TeBeamRing(vec![24; 16]),
+            TempEntity::TeStreakSplash(vec![25; 19]),
+            TempEntity::TeDLight(vec![27; 12]),
+            TempEntity::TeELight(vec![28; 16]),
+            TempEntity::TeTextMessage(TeTextMessage {
+                channel: 1,
+                x: -1,
+                y: 4096,
+                effect: 2,
+                text_color: vec![255, 255, 0, 255],
+                effect_color: vec![0, 255, 255, 128],
+                fade_in_time: 10,
+                fade_out_time: 20,
+                hold_time: 300,
+                effect_time: Some(40),
+                message: b"Round start\0".to_vec().into(),
+            }),
+            TempEntity::TeLine(vec![30; 17]),
+            TempEntity::TeBox(vec![31; 17]),
+            TempEntity::TeKillBeam(vec![99; 2]),
+            TempEntity::TeLargeFunnel(vec![100; 10]),
+            TempEntity::TeBloodStream(vec![101; 14]),
+            TempEntity::TeShowLine(vec![102; 12]),
+            TempEntity::TeBlood(vec![103; 14]),
+            TempEntity::TeDecal(vec![104; 9]),
+            TempEntity::TeFizz(vec![105; 5]),
+            TempEntity::TeModel(vec![106; 17]),
+            TempEntity::TeExplodeModel(vec![107; 13]),
+            TempEntity::TeBreakModel(vec![108; 24]),
+            TempEntity::TeGunshotDecal(vec![109; 9]),
+            TempEntity::TeSpriteSpray(vec![110; 17]),
+            TempEntity::TeArmorRicochet(vec![111; 7]),
+            TempEntity::TePlayerDecal(vec![112; 10]),
+            TempEntity::TeBubbles(vec![113; 10]),
+            TempEntity::TeBubbleTrail(vec![114; 19]),
+            TempEntity::TeBloodSprite(vec![115; 12]),
+            TempEntity::TeWorldDecal(vec![116; 7]),
+            TempEntity::TeWorldDecalHigh(vec![117; 7]),
+            TempEntity::TeDecalHigh(vec![118; 9]),
+            TempEntity::TeProjectile(vec![119; 16]),
+            TempEntity::TeSpray(vec![120; 18]),
+            TempEntity::TePlayerSprites(vec![121; 5]),
+            TempEntity::TeParticleBurst(vec![122; 10]),
+            TempEntity::TeFireField(vec![123; 9]),
+            TempEntity::TePlayerAttachment(vec![124; 7]),
+            TempEntity::TeKillPlayerAttachment(vec![125; 1]),
+            TempEntity::TeMultigunShot(vec![126; 18]),
+            TempEntity::TeUserTracer(vec![127; 15]),
+        ];
+
+        let ids: std::collections::HashSet<_> = entities.iter().map(TempEntity::id).collect();
+        assert_eq!(ids.len(), 59);
+
+        for entity in entities {
+            assert_round_trip(EngineMessage::SvcTempEntity(SvcTempEntity {
+                entity_type: entity.id(),
+                entity,
+            }));
+        }
+    }
+
+    /// Message names, and every field as message, field name, tag and type.
+    type Schema = (BTreeSet<String>, BTreeSet<(String, String, u32, String)>);
+
+    /// Type of a field the same way for both sides: the label, then the scalar type or the name
+    /// of the message. Messages are always optional, so that label is left out.
+    fn field_type(label: &str, type_: &str) -> String {
+        let is_message = type_.starts_with(|c: char| c.is_ascii_uppercase());
+        let label = if is_message && label == "optional" {
+            ""
+        } else {
+            label
+        };
+
+        format!("{label} {type_}").trim().to_owned()
+    }
+
+    fn snake_case(name: &str) -> String {
+        let mut res = String::new();
+
+        for (index, c) in name.chars().enumerate() {
+            if c.is_ascii_uppercase() && index > 0 {
+                res.push('_');
+            }
+
+            res.push(c.to_ascii_lowercase());
+        }
+
+        res
+    }
+
+    fn camel_case(name: &str) -> String {
+        name.split('_')
+            .map(|part| part[..1].to_uppercase() + &part[1..])
+            .collect()
+    }
+
+    fn parse_schema(schema: &str) -> Schema {
+        let mut messages = BTreeSet::new();
+        let mut fields = BTreeSet::new();
+        // None for a oneof, whose fields belong to the message around it
+        let mut blocks: Vec<Option<String>> = vec![];
+
+        for line in schema.lines() {
+            let line = line.split("//").next().unwrap().trim();
+            let message = blocks.iter().rev().flatten().next().cloned();
+
+            if let Some(name) = line.strip_prefix("message ") {
+                let name = name.trim_end_matches(['{', ' ']).to_owned();
+
+                messages.insert(name.clone());
+                blocks.push(Some(name));
+            } else if let Some(name) = line.strip_prefix("oneof ") {
+                let name = name.trim_end_matches(['{', ' ']).to_owned();
+
+                fields.insert((message.unwrap(), name, 0, "oneof".to_owned()));
+                blocks.push(None);
+            } else if line.starts_with('}') {
+                blocks.pop();
+            } else if let Some(message) = message
+                && let Some((field, tag)) = line.trim_end_matches(';').split_once(" = ")
+            {
+                let tag = tag.parse().unwrap();
+
+                let (name, type_) = if let Some(map) = field.strip_prefix("map<") {
+                    let (types, name) = map.split_once("> ").unwrap();
+                    (name, format!("map<{}>", types.replace(' ', "")))
+                } else {
+                    let tokens: Vec<&str> = field.split_whitespace().collect();
+
+                    match tokens.as_slice() {
+                        [type_, name] => (*name, field_type("", type_)),
+                        [label, type_, name] => (*name, field_type(label, type_)),
+                        _ => panic!("cannot read field {line}"),
+                    }
+                };
+
+                fields.insert((message, name.to_owned(), tag, type_));
+            }
+        }
+
+        (messages, fields)
+    }
+
+    /// Reads the `#[prost]` attributes of `v1.rs`.
+    fn parse_v1(source: &str) -> Schema {
+        let mut messages = BTreeSet::new();
+        let mut fields = BTreeSet::new();
+        let mut message = String::new();
+        let mut lines = source.lines().map(str::trim);
+
+        while let Some(line) = lines.next() {
+            if let Some(name) = line.strip_prefix("pub struct ") {
+                message = name.trim_end_matches(['{', '}', ' ']).to_owned();
+                messages.insert(message.clone());
+                continue;
+            }
+
+            // oneof variants are in a module named after their message
+            if let Some(name) = line.strip_prefix("pub mod ") {
+                message = camel_case(name.trim_end_matches(['{', ' ']));
+                continue;
+            }
+
+            if !line.starts_with("#[prost(") {
+                continue;
+            }
+
+            let mut attribute = line.to_owned();
+
+            while !attribute.ends_with(")]") {
+                attribute.push_str(lines.next().unwrap());
+            }
+
+            let attribute = attribute
+                .trim_start_matches("#[prost(")
+                .trim_end_matches(")]");
+            let item = lines.next().unwrap();
+
+            // a struct field or an enum variant
+            let (name, rust_type) = match item.strip_prefix("pub ") {
+                Some(field) => {
+                    let (name, rust_type) = field.split_once(": ").unwrap();
+                    (name.trim_start_matches("r#").to_owned(), rust_type)
+                }
+                None => {
+                    let (variant, rust_type) = item.split_once('(').unwrap();
+                    (snake_case(variant), rust_type)
+                }
+            };
+
+            if attribute.starts_with("oneof") {
+                fields.insert((message.clone(), name, 0, "oneof".to_owned()));
+                continue;
+            }
+
+            let tag = attribute
+                .rsplit_once("tag = \"")
+                .unwrap()
+                .1
+                .trim_end_matches('"')
+                .parse()
+                .unwrap();
+            let message_name = rust_type
+                .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .rfind(|part| part.starts_with(|c: char| c.is_ascii_uppercase()))
+                .unwrap_or_default();
+
+            let type_ = if attribute.starts_with("btree_map") {
+                format!("map<string,{message_name}>")
+            } else {
+                let kind = attribute.split([',', ' ']).next().unwrap();
+                let kind = if kind == "message" {
+                    message_name
+                } else {
+                    kind
+                };
+                let label = ["optional", "repeated"]
+                    .into_iter()
+                    .find(|label| attribute.contains(&format!(", {label},")))
+                    .unwrap_or_default();
+
+                field_type(label, kind)
+            };
+
+            fields.insert((message.clone(), name, tag, type_));
+        }
+
+        (messages, fields)
+    }
+
+    #[test]
+    fn schema_matches_v1() {
+        let (schema_messages, schema_fields) = parse_schema(SCHEMA);
+        let (v1_messages, v1_fields) = parse_v1(include_str!("v1.rs"));
+
+        assert_eq!(schema_messages.len(), 91);
+        assert_eq!(
+            schema_messages
+                .symmetric_difference(&v1_messages)
+                .collect::<Vec<_>>(),
+            Vec::<&String>::new()
+        );
+        assert_eq!(
+            schema_fields
+                .symmetric_difference(&v1_fields)
+                .collect::<Vec<_>>(),
+            Vec::<&(String, String, u32, String)>::new()
+        );
+    }
+}
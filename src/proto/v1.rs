@@ -0,0 +1,1499 @@
+//! Messages of `proto/dem.proto`, written as `prost-build` would generate them.
+//!
+//! They are written out so the crate builds without `protoc`. When the schema changes, this file
+//! changes with it.
+use std::collections::BTreeMap;
+
+/// A whole demo. `schema_version` is `SCHEMA_VERSION` of the writer.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Demo {
+    #[prost(uint32, tag = "1")]
+    pub schema_version: u32,
+    #[prost(message, optional, tag = "2")]
+    pub header: Option<Header>,
+    #[prost(message, repeated, tag = "3")]
+    pub entries: Vec<DirectoryEntry>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Header {
+    #[prost(bytes = "vec", tag = "1")]
+    pub magic: Vec<u8>,
+    #[prost(int32, tag = "2")]
+    pub demo_protocol: i32,
+    #[prost(int32, tag = "3")]
+    pub network_protocol: i32,
+    #[prost(bytes = "vec", tag = "4")]
+    pub map_name: Vec<u8>,
+    #[prost(bytes = "vec", tag = "5")]
+    pub game_directory: Vec<u8>,
+    #[prost(uint32, tag = "6")]
+    pub map_checksum: u32,
+    #[prost(int32, tag = "7")]
+    pub directory_offset: i32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DirectoryEntry {
+    #[prost(int32, tag = "1")]
+    pub r#type: i32,
+    #[prost(bytes = "vec", tag = "2")]
+    pub description: Vec<u8>,
+    #[prost(int32, tag = "3")]
+    pub flags: i32,
+    #[prost(int32, tag = "4")]
+    pub cd_track: i32,
+    #[prost(float, tag = "5")]
+    pub track_time: f32,
+    #[prost(int32, tag = "6")]
+    pub frame_count: i32,
+    #[prost(int32, tag = "7")]
+    pub frame_offset: i32,
+    #[prost(int32, tag = "8")]
+    pub file_length: i32,
+    #[prost(message, repeated, tag = "9")]
+    pub frames: Vec<Frame>,
+}
+
+/// One frame. Frames can be sent on their own for streaming.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Frame {
+    #[prost(float, tag = "1")]
+    pub time: f32,
+    #[prost(int32, tag = "2")]
+    pub frame: i32,
+    #[prost(oneof = "frame::Data", tags = "3, 4, 5, 6, 7, 8, 9, 10, 11")]
+    pub data: Option<frame::Data>,
+}
+
+/// Nested types of [`Frame`].
+pub mod frame {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Data {
+        #[prost(message, boxed, tag = "3")]
+        NetworkMessage(Box<super::NetworkMessage>),
+        #[prost(message, tag = "4")]
+        DemoStart(super::Empty),
+        #[prost(message, tag = "5")]
+        ConsoleCommand(super::ConsoleCommand),
+        #[prost(message, tag = "6")]
+        ClientData(super::ClientData),
+        #[prost(message, tag = "7")]
+        NextSection(super::Empty),
+        #[prost(message, tag = "8")]
+        Event(super::Event),
+        #[prost(message, tag = "9")]
+        WeaponAnimation(super::WeaponAnimation),
+        #[prost(message, tag = "10")]
+        Sound(super::Sound),
+        #[prost(message, tag = "11")]
+        DemoBuffer(super::DemoBuffer),
+    }
+}
+
+/// Variants without data.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Empty {}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConsoleCommand {
+    #[prost(bytes = "vec", tag = "1")]
+    pub command: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ClientData {
+    #[prost(float, repeated, tag = "1")]
+    pub origin: Vec<f32>,
+    #[prost(float, repeated, tag = "2")]
+    pub viewangles: Vec<f32>,
+    #[prost(int32, tag = "3")]
+    pub weapon_bits: i32,
+    #[prost(float, tag = "4")]
+    pub fov: f32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Event {
+    #[prost(int32, tag = "1")]
+    pub flags: i32,
+    #[prost(int32, tag = "2")]
+    pub index: i32,
+    #[prost(float, tag = "3")]
+    pub delay: f32,
+    #[prost(message, optional, tag = "4")]
+    pub args: Option<EventArgs>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EventArgs {
+    #[prost(int32, tag = "1")]
+    pub flags: i32,
+    #[prost(int32, tag = "2")]
+    pub entity_index: i32,
+    #[prost(float, repeated, tag = "3")]
+    pub origin: Vec<f32>,
+    #[prost(float, repeated, tag = "4")]
+    pub angles: Vec<f32>,
+    #[prost(float, repeated, tag = "5")]
+    pub velocity: Vec<f32>,
+    #[prost(int32, tag = "6")]
+    pub ducking: i32,
+    #[prost(float, tag = "7")]
+    pub fparam1: f32,
+    #[prost(float, tag = "8")]
+    pub fparam2: f32,
+    #[prost(int32, tag = "9")]
+    pub iparam1: i32,
+    #[prost(int32, tag = "10")]
+    pub iparam2: i32,
+    #[prost(int32, tag = "11")]
+    pub bparam1: i32,
+    #[prost(int32, tag = "12")]
+    pub bparam2: i32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Sound {
+    #[prost(int32, tag = "1")]
+    pub channel: i32,
+    #[prost(bytes = "vec", tag = "2")]
+    pub sample: Vec<u8>,
+    #[prost(float, tag = "3")]
+    pub attenuation: f32,
+    #[prost(float, tag = "4")]
+    pub volume: f32,
+    #[prost(int32, tag = "5")]
+    pub flags: i32,
+    #[prost(int32, tag = "6")]
+    pub pitch: i32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WeaponAnimation {
+    #[prost(int32, tag = "1")]
+    pub sequence: i32,
+    #[prost(int32, tag = "2")]
+    pub body: i32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DemoBuffer {
+    #[prost(bytes = "vec", tag = "1")]
+    pub buffer: Vec<u8>,
+}
+
+/// `frame_type` is 0 for start frames, 1 for normal frames and the type byte of anything else.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NetworkMessage {
+    #[prost(uint32, tag = "1")]
+    pub frame_type: u32,
+    #[prost(message, optional, tag = "2")]
+    pub info: Option<DemoInfo>,
+    #[prost(message, optional, tag = "3")]
+    pub sequence_info: Option<SequenceInfo>,
+    #[prost(uint32, tag = "4")]
+    pub message_length: u32,
+    #[prost(oneof = "network_message::Messages", tags = "5, 6")]
+    pub messages: Option<network_message::Messages>,
+}
+
+/// Nested types of [`NetworkMessage`].
+pub mod network_message {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Messages {
+        #[prost(message, tag = "5")]
+        Parsed(super::NetMessages),
+        #[prost(bytes = "vec", tag = "6")]
+        Raw(Vec<u8>),
+    }
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NetMessages {
+    #[prost(message, repeated, tag = "1")]
+    pub messages: Vec<NetMessage>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DemoInfo {
+    #[prost(float, tag = "1")]
+    pub timestamp: f32,
+    #[prost(message, optional, tag = "2")]
+    pub refparams: Option<RefParams>,
+    #[prost(message, optional, tag = "3")]
+    pub usercmd: Option<UserCmd>,
+    #[prost(message, optional, tag = "4")]
+    pub movevars: Option<MoveVars>,
+    #[prost(float, repeated, tag = "5")]
+    pub view: Vec<f32>,
+    #[prost(int32, tag = "6")]
+    pub viewmodel: i32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RefParams {
+    #[prost(float, repeated, tag = "1")]
+    pub view_origin: Vec<f32>,
+    #[prost(float, repeated, tag = "2")]
+    pub view_angles: Vec<f32>,
+    #[prost(float, repeated, tag = "3")]
+    pub forward: Vec<f32>,
+    #[prost(float, repeated, tag = "4")]
+    pub right: Vec<f32>,
+    #[prost(float, repeated, tag = "5")]
+    pub up: Vec<f32>,
+    #[prost(float, tag = "6")]
+    pub frame_time: f32,
+    #[prost(float, tag = "7")]
+    pub time: f32,
+    #[prost(int32, tag = "8")]
+    pub intermission: i32,
+    #[prost(int32, tag = "9")]
+    pub paused: i32,
+    #[prost(int32, tag = "10")]
+    pub spectator: i32,
+    #[prost(int32, tag = "11")]
+    pub on_ground: i32,
+    #[prost(int32, tag = "12")]
+    pub water_level: i32,
+    #[prost(float, repeated, tag = "13")]
+    pub sim_vel: Vec<f32>,
+    #[prost(float, repeated, tag = "14")]
+    pub sim_org: Vec<f32>,
+    #[prost(float, repeated, tag = "15")]
+    pub view_height: Vec<f32>,
+    #[prost(float, tag = "16")]
+    pub ideal_pitch: f32,
+    #[prost(float, repeated, tag = "17")]
+    pub cl_viewangles: Vec<f32>,
+    #[prost(int32, tag = "18")]
+    pub health: i32,
+    #[prost(float, repeated, tag = "19")]
+    pub crosshair_angle: Vec<f32>,
+    #[prost(float, tag = "20")]
+    pub view_size: f32,
+    #[prost(float, repeated, tag = "21")]
+    pub punch_angle: Vec<f32>,
+    #[prost(int32, tag = "22")]
+    pub max_clients: i32,
+    #[prost(int32, tag = "23")]
+    pub view_entity: i32,
+    #[prost(int32, tag = "24")]
+    pub player_num: i32,
+    #[prost(int32, tag = "25")]
+    pub max_entities: i32,
+    #[prost(int32, tag = "26")]
+    pub demo_playback: i32,
+    #[prost(int32, tag = "27")]
+    pub hardware: i32,
+    #[prost(int32, tag = "28")]
+    pub smoothing: i32,
+    #[prost(int32, tag = "29")]
+    pub ptr_cmd: i32,
+    #[prost(int32, tag = "30")]
+    pub ptr_move_vars: i32,
+    #[prost(int32, repeated, tag = "31")]
+    pub view_port: Vec<i32>,
+    #[prost(int32, tag = "32")]
+    pub next_view: i32,
+    #[prost(int32, tag = "33")]
+    pub only_client_draw: i32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserCmd {
+    #[prost(int32, tag = "1")]
+    pub lerp_msec: i32,
+    #[prost(uint32, tag = "2")]
+    pub msec: u32,
+    #[prost(uint32, tag = "3")]
+    pub unknown1: u32,
+    #[prost(float, repeated, tag = "4")]
+    pub view_angles: Vec<f32>,
+    #[prost(float, tag = "5")]
+    pub forward_move: f32,
+    #[prost(float, tag = "6")]
+    pub side_move: f32,
+    #[prost(float, tag = "7")]
+    pub up_move: f32,
+    #[prost(int32, tag = "8")]
+    pub light_level: i32,
+    #[prost(uint32, tag = "9")]
+    pub unknown2: u32,
+    #[prost(uint32, tag = "10")]
+    pub buttons: u32,
+    #[prost(int32, tag = "11")]
+    pub impulse: i32,
+    #[prost(int32, tag = "12")]
+    pub weapon_select: i32,
+    #[prost(uint32, tag = "13")]
+    pub unknown3: u32,
+    #[prost(uint32, tag = "14")]
+    pub unknown4: u32,
+    #[prost(int32, tag = "15")]
+    pub impact_index: i32,
+    #[prost(float, repeated, tag = "16")]
+    pub impact_position: Vec<f32>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MoveVars {
+    #[prost(float, tag = "1")]
+    pub gravity: f32,
+    #[prost(float, tag = "2")]
+    pub stopspeed: f32,
+    #[prost(float, tag = "3")]
+    pub maxspeed: f32,
+    #[prost(float, tag = "4")]
+    pub spectatormaxspeed: f32,
+    #[prost(float, tag = "5")]
+    pub accelerate: f32,
+    #[prost(float, tag = "6")]
+    pub airaccelerate: f32,
+    #[prost(float, tag = "7")]
+    pub wateraccelerate: f32,
+    #[prost(float, tag = "8")]
+    pub friction: f32,
+    #[prost(float, tag = "9")]
+    pub edgefriction: f32,
+    #[prost(float, tag = "10")]
+    pub waterfriction: f32,
+    #[prost(float, tag = "11")]
+    pub entgravity: f32,
+    #[prost(float, tag = "12")]
+    pub bounce: f32,
+    #[prost(float, tag = "13")]
+    pub stepsize: f32,
+    #[prost(float, tag = "14")]
+    pub maxvelocity: f32,
+    #[prost(float, tag = "15")]
+    pub zmax: f32,
+    #[prost(float, tag = "16")]
+    pub wave_height: f32,
+    #[prost(int32, tag = "17")]
+    pub footsteps: i32,
+    #[prost(bytes = "vec", tag = "18")]
+    pub sky_name: Vec<u8>,
+    #[prost(float, tag = "19")]
+    pub rollangle: f32,
+    #[prost(float, tag = "20")]
+    pub rollspeed: f32,
+    #[prost(float, repeated, tag = "21")]
+    pub skycolor: Vec<f32>,
+    #[prost(float, repeated, tag = "22")]
+    pub skyvec: Vec<f32>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SequenceInfo {
+    #[prost(int32, tag = "1")]
+    pub incoming_sequence: i32,
+    #[prost(int32, tag = "2")]
+    pub incoming_acknowledged: i32,
+    #[prost(int32, tag = "3")]
+    pub incoming_reliable_acknowledged: i32,
+    #[prost(int32, tag = "4")]
+    pub incoming_reliable_sequence: i32,
+    #[prost(int32, tag = "5")]
+    pub outgoing_sequence: i32,
+    #[prost(int32, tag = "6")]
+    pub reliable_sequence: i32,
+    #[prost(int32, tag = "7")]
+    pub last_reliable_sequence: i32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NetMessage {
+    #[prost(oneof = "net_message::Message", tags = "1, 2")]
+    pub message: Option<net_message::Message>,
+}
+
+/// Nested types of [`NetMessage`].
+pub mod net_message {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Message {
+        #[prost(message, tag = "1")]
+        UserMessage(super::UserMessage),
+        #[prost(message, tag = "2")]
+        EngineMessage(super::EngineMessage),
+    }
+}
+
+/// Custom messages are kept as bytes, as in `MessageData` of the parser.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UserMessage {
+    #[prost(uint32, tag = "1")]
+    pub id: u32,
+    #[prost(bytes = "vec", tag = "2")]
+    pub name: Vec<u8>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub data: Vec<u8>,
+}
+
+/// Field names keep their null terminator, like the decoders of the demo.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Delta {
+    #[prost(btree_map = "string, message", tag = "1")]
+    pub fields: BTreeMap<String, DeltaValue>,
+}
+
+/// The variant says how the field is encoded in the demo.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeltaValue {
+    #[prost(oneof = "delta_value::Value", tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10")]
+    pub value: Option<delta_value::Value>,
+}
+
+/// Nested types of [`DeltaValue`].
+pub mod delta_value {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Value {
+        #[prost(int32, tag = "1")]
+        ByteSigned(i32),
+        #[prost(uint32, tag = "2")]
+        ByteUnsigned(u32),
+        #[prost(int32, tag = "3")]
+        ShortSigned(i32),
+        #[prost(uint32, tag = "4")]
+        ShortUnsigned(u32),
+        #[prost(int32, tag = "5")]
+        IntSigned(i32),
+        #[prost(uint32, tag = "6")]
+        IntUnsigned(u32),
+        #[prost(float, tag = "7")]
+        FloatSigned(f32),
+        #[prost(float, tag = "8")]
+        FloatUnsigned(f32),
+        #[prost(float, tag = "9")]
+        Angle(f32),
+        #[prost(string, tag = "10")]
+        String(String),
+    }
+}
+
+/// Field numbers are the message id plus one.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EngineMessage {
+    #[prost(
+        oneof = "engine_message::Message",
+        tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59"
+    )]
+    pub message: Option<engine_message::Message>,
+}
+
+/// Nested types of [`EngineMessage`].
+pub mod engine_message {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Message {
+        #[prost(message, tag = "1")]
+        SvcBad(super::Empty),
+        #[prost(message, tag = "2")]
+        SvcNop(super::Empty),
+        #[prost(message, tag = "3")]
+        SvcDisconnect(super::SvcDisconnect),
+        #[prost(message, tag = "4")]
+        SvcEvent(super::SvcEvent),
+        #[prost(message, tag = "5")]
+        SvcVersion(super::SvcVersion),
+        #[prost(message, tag = "6")]
+        SvcSetView(super::SvcSetView),
+        #[prost(message, tag = "7")]
+        SvcSound(super::SvcSound),
+        #[prost(message, tag = "8")]
+        SvcTime(super::SvcTime),
+        #[prost(message, tag = "9")]
+        SvcPrint(super::SvcPrint),
+        #[prost(message, tag = "10")]
+        SvcStuffText(super::SvcStuffText),
+        #[prost(message, tag = "11")]
+        SvcSetAngle(super::SvcSetAngle),
+        #[prost(message, tag = "12")]
+        SvcServerInfo(super::SvcServerInfo),
+        #[prost(message, tag = "13")]
+        SvcLightStyle(super::SvcLightStyle),
+        #[prost(message, tag = "14")]
+        SvcUpdateUserInfo(super::SvcUpdateUserInfo),
+        #[prost(message, tag = "15")]
+        SvcDeltaDescription(super::SvcDeltaDescription),
+        #[prost(message, tag = "16")]
+        SvcClientData(super::SvcClientData),
+        #[prost(message, tag = "17")]
+        SvcStopSound(super::SvcStopSound),
+        #[prost(message, tag = "18")]
+        SvcPings(super::SvcPings),
+        #[prost(message, tag = "19")]
+        SvcParticle(super::SvcParticle),
+        #[prost(message, tag = "20")]
+        SvcDamage(super::Empty),
+        #[prost(message, tag = "21")]
+        SvcSpawnStatic(super::SvcSpawnStatic),
+        #[prost(message, tag = "22")]
+        SvcEventReliable(super::SvcEventReliable),
+        #[prost(message, tag = "23")]
+        SvcSpawnBaseline(super::SvcSpawnBaseline),
+        #[prost(message, tag = "24")]
+        SvcTempEntity(super::SvcTempEntity),
+        #[prost(message, tag = "25")]
+        SvcSetPause(super::SvcSetPause),
+        #[prost(message, tag = "26")]
+        SvcSignOnNum(super::SvcSignOnNum),
+        #[prost(message, tag = "27")]
+        SvcCenterPrint(super::SvcCenterPrint),
+        #[prost(message, tag = "28")]
+        SvcKilledMonster(super::Empty),
+        #[prost(message, tag = "29")]
+        SvcFoundSecret(super::Empty),
+        #[prost(message, tag = "30")]
+        SvcSpawnStaticSound(super::SvcSpawnStaticSound),
+        #[prost(message, tag = "31")]
+        SvcIntermission(super::Empty),
+        #[prost(message, tag = "32")]
+        SvcFinale(super::SvcFinale),
+        #[prost(message, tag = "33")]
+        SvcCdTrack(super::SvcCdTrack),
+        #[prost(message, tag = "34")]
+        SvcRestore(super::SvcRestore),
+        #[prost(message, tag = "35")]
+        SvcCutscene(super::SvcCutscene),
+        #[prost(message, tag = "36")]
+        SvcWeaponAnim(super::SvcWeaponAnim),
+        #[prost(message, tag = "37")]
+        SvcDecalName(super::SvcDecalName),
+        #[prost(message, tag = "38")]
+        SvcRoomType(super::SvcRoomType),
+        #[prost(message, tag = "39")]
+        SvcAddAngle(super::SvcAddAngle),
+        #[prost(message, tag = "40")]
+        SvcNewUserMsg(super::SvcNewUserMsg),
+        #[prost(message, tag = "41")]
+        SvcPacketEntities(super::SvcPacketEntities),
+        #[prost(message, tag = "42")]
+        SvcDeltaPacketEntities(super::SvcDeltaPacketEntities),
+        #[prost(message, tag = "43")]
+        SvcChoke(super::Empty),
+        #[prost(message, tag = "44")]
+        SvcResourceList(super::SvcResourceList),
+        #[prost(message, tag = "45")]
+        SvcNewMovevars(super::SvcNewMovevars),
+        #[prost(message, tag = "46")]
+        SvcResourceRequest(super::SvcResourceRequest),
+        #[prost(message, tag = "47")]
+        SvcCustomization(super::SvcCustomization),
+        #[prost(message, tag = "48")]
+        SvcCrosshairAngle(super::SvcCrosshairAngle),
+        #[prost(message, tag = "49")]
+        SvcSoundFade(super::SvcSoundFade),
+        #[prost(message, tag = "50")]
+        SvcFileTxferFailed(super::SvcFileTxferFailed),
+        #[prost(message, tag = "51")]
+        SvcHltv(super::SvcHltv),
+        #[prost(message, tag = "52")]
+        SvcDirector(super::SvcDirector),
+        #[prost(message, tag = "53")]
+        SvcVoiceInit(super::SvcVoiceInit),
+        #[prost(message, tag = "54")]
+        SvcVoiceData(super::SvcVoiceData),
+        #[prost(message, tag = "55")]
+        SvcSendExtraInfo(super::SvcSendExtraInfo),
+        #[prost(message, tag = "56")]
+        SvcTimeScale(super::SvcTimeScale),
+        #[prost(message, tag = "57")]
+        SvcResourceLocation(super::SvcResourceLocation),
+        #[prost(message, tag = "58")]
+        SvcSendCvarValue(super::SvcSendCvarValue),
+        #[prost(message, tag = "59")]
+        SvcSendCvarValue2(super::SvcSendCvarValue2),
+    }
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcDisconnect {
+    #[prost(bytes = "vec", tag = "1")]
+    pub reason: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcEvent {
+    #[prost(uint32, tag = "1")]
+    pub event_count: u32,
+    #[prost(message, repeated, tag = "2")]
+    pub events: Vec<EventS>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EventS {
+    #[prost(uint32, tag = "1")]
+    pub event_index: u32,
+    #[prost(bool, tag = "2")]
+    pub has_packet_index: bool,
+    #[prost(uint32, optional, tag = "3")]
+    pub packet_index: Option<u32>,
+    #[prost(bool, optional, tag = "4")]
+    pub has_delta: Option<bool>,
+    #[prost(message, optional, tag = "5")]
+    pub delta: Option<Delta>,
+    #[prost(bool, tag = "6")]
+    pub has_fire_time: bool,
+    #[prost(uint32, optional, tag = "7")]
+    pub fire_time: Option<u32>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcVersion {
+    #[prost(uint32, tag = "1")]
+    pub protocol_version: u32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcSetView {
+    #[prost(int32, tag = "1")]
+    pub entity_index: i32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcSound {
+    #[prost(uint32, tag = "1")]
+    pub flags: u32,
+    #[prost(uint32, optional, tag = "2")]
+    pub volume: Option<u32>,
+    #[prost(uint32, optional, tag = "3")]
+    pub attenuation: Option<u32>,
+    #[prost(uint32, tag = "4")]
+    pub channel: u32,
+    #[prost(uint32, tag = "5")]
+    pub entity_index: u32,
+    #[prost(uint32, optional, tag = "6")]
+    pub sound_index_long: Option<u32>,
+    #[prost(uint32, optional, tag = "7")]
+    pub sound_index_short: Option<u32>,
+    #[prost(bool, tag = "8")]
+    pub has_x: bool,
+    #[prost(bool, tag = "9")]
+    pub has_y: bool,
+    #[prost(bool, tag = "10")]
+    pub has_z: bool,
+    #[prost(message, optional, tag = "11")]
+    pub origin_x: Option<OriginCoord>,
+    #[prost(message, optional, tag = "12")]
+    pub origin_y: Option<OriginCoord>,
+    #[prost(message, optional, tag = "13")]
+    pub origin_z: Option<OriginCoord>,
+    #[prost(uint32, tag = "14")]
+    pub pitch: u32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OriginCoord {
+    #[prost(bool, tag = "1")]
+    pub int_flag: bool,
+    #[prost(bool, tag = "2")]
+    pub fraction_flag: bool,
+    #[prost(bool, optional, tag = "3")]
+    pub is_negative: Option<bool>,
+    #[prost(uint32, optional, tag = "4")]
+    pub int_value: Option<u32>,
+    #[prost(uint32, optional, tag = "5")]
+    pub fraction_value: Option<u32>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcTime {
+    #[prost(float, tag = "1")]
+    pub time: f32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcPrint {
+    #[prost(bytes = "vec", tag = "1")]
+    pub message: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcStuffText {
+    #[prost(bytes = "vec", tag = "1")]
+    pub command: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcSetAngle {
+    #[prost(int32, tag = "1")]
+    pub pitch: i32,
+    #[prost(int32, tag = "2")]
+    pub yaw: i32,
+    #[prost(int32, tag = "3")]
+    pub roll: i32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcServerInfo {
+    #[prost(int32, tag = "1")]
+    pub protocol: i32,
+    #[prost(int32, tag = "2")]
+    pub spawn_count: i32,
+    #[prost(int32, tag = "3")]
+    pub map_checksum: i32,
+    #[prost(bytes = "vec", tag = "4")]
+    pub client_dll_hash: Vec<u8>,
+    #[prost(uint32, tag = "5")]
+    pub max_players: u32,
+    #[prost(uint32, tag = "6")]
+    pub player_index: u32,
+    #[prost(uint32, tag = "7")]
+    pub is_deathmatch: u32,
+    #[prost(bytes = "vec", tag = "8")]
+    pub game_dir: Vec<u8>,
+    #[prost(bytes = "vec", tag = "9")]
+    pub hostname: Vec<u8>,
+    #[prost(bytes = "vec", tag = "10")]
+    pub map_file_name: Vec<u8>,
+    #[prost(bytes = "vec", tag = "11")]
+    pub map_cycle: Vec<u8>,
+    #[prost(uint32, tag = "12")]
+    pub unknown: u32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcLightStyle {
+    #[prost(uint32, tag = "1")]
+    pub index: u32,
+    #[prost(bytes = "vec", tag = "2")]
+    pub light_info: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcUpdateUserInfo {
+    #[prost(uint32, tag = "1")]
+    pub index: u32,
+    #[prost(uint32, tag = "2")]
+    pub id: u32,
+    #[prost(bytes = "vec", tag = "3")]
+    pub user_info: Vec<u8>,
+    #[prost(bytes = "vec", tag = "4")]
+    pub cd_key_hash: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcDeltaDescription {
+    #[prost(bytes = "vec", tag = "1")]
+    pub name: Vec<u8>,
+    #[prost(uint32, tag = "2")]
+    pub total_fields: u32,
+    #[prost(message, repeated, tag = "3")]
+    pub fields: Vec<DeltaField>,
+    #[prost(bytes = "vec", tag = "4")]
+    pub clone: Vec<u8>,
+}
+
+/// A field of a delta decoder.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeltaField {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(uint32, tag = "2")]
+    pub bits: u32,
+    #[prost(float, tag = "3")]
+    pub divisor: f32,
+    #[prost(uint32, tag = "4")]
+    pub flags: u32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcClientData {
+    #[prost(bool, tag = "1")]
+    pub has_delta_update_mask: bool,
+    #[prost(uint32, optional, tag = "2")]
+    pub delta_update_mask: Option<u32>,
+    #[prost(message, optional, tag = "3")]
+    pub client_data: Option<Delta>,
+    #[prost(message, optional, tag = "4")]
+    pub weapon_data: Option<WeaponDataList>,
+}
+
+/// Sets `SvcClientData.weapon_data` apart from an empty list.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WeaponDataList {
+    #[prost(message, repeated, tag = "1")]
+    pub weapons: Vec<ClientDataWeaponData>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ClientDataWeaponData {
+    #[prost(uint32, tag = "1")]
+    pub weapon_index: u32,
+    #[prost(message, optional, tag = "2")]
+    pub weapon_data: Option<Delta>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcStopSound {
+    #[prost(int32, tag = "1")]
+    pub entity_index: i32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcPings {
+    #[prost(message, repeated, tag = "1")]
+    pub pings: Vec<PingS>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PingS {
+    #[prost(bool, tag = "1")]
+    pub has_ping_data: bool,
+    #[prost(uint32, optional, tag = "2")]
+    pub player_id: Option<u32>,
+    #[prost(uint32, optional, tag = "3")]
+    pub ping: Option<u32>,
+    #[prost(uint32, optional, tag = "4")]
+    pub loss: Option<u32>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcParticle {
+    #[prost(int32, repeated, tag = "1")]
+    pub origin: Vec<i32>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub direction: Vec<u8>,
+    #[prost(uint32, tag = "3")]
+    pub count: u32,
+    #[prost(uint32, tag = "4")]
+    pub color: u32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcSpawnStatic {
+    #[prost(int32, tag = "1")]
+    pub model_index: i32,
+    #[prost(int32, tag = "2")]
+    pub sequence: i32,
+    #[prost(int32, tag = "3")]
+    pub frame: i32,
+    #[prost(int32, tag = "4")]
+    pub color_map: i32,
+    #[prost(int32, tag = "5")]
+    pub skin: i32,
+    #[prost(int32, tag = "6")]
+    pub origin_x: i32,
+    #[prost(int32, tag = "7")]
+    pub rotation_x: i32,
+    #[prost(int32, tag = "8")]
+    pub origin_y: i32,
+    #[prost(int32, tag = "9")]
+    pub rotation_y: i32,
+    #[prost(int32, tag = "10")]
+    pub origin_z: i32,
+    #[prost(int32, tag = "11")]
+    pub rotation_z: i32,
+    #[prost(int32, tag = "12")]
+    pub has_render_mode: i32,
+    #[prost(bytes = "vec", optional, tag = "13")]
+    pub render_color: Option<Vec<u8>>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcEventReliable {
+    #[prost(uint32, tag = "1")]
+    pub event_index: u32,
+    #[prost(message, optional, tag = "2")]
+    pub event_args: Option<Delta>,
+    #[prost(bool, tag = "3")]
+    pub has_fire_time: bool,
+    #[prost(uint32, optional, tag = "4")]
+    pub fire_time: Option<u32>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcSpawnBaseline {
+    #[prost(message, repeated, tag = "1")]
+    pub entities: Vec<EntityS>,
+    #[prost(uint32, tag = "2")]
+    pub total_extra_data: u32,
+    #[prost(message, repeated, tag = "3")]
+    pub extra_data: Vec<Delta>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EntityS {
+    #[prost(uint32, tag = "1")]
+    pub entity_index: u32,
+    #[prost(uint32, tag = "2")]
+    pub index: u32,
+    #[prost(uint32, tag = "3")]
+    pub r#type: u32,
+    #[prost(message, optional, tag = "4")]
+    pub delta: Option<Delta>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcTempEntity {
+    #[prost(uint32, tag = "1")]
+    pub entity_type: u32,
+    #[prost(message, optional, tag = "2")]
+    pub entity: Option<TempEntity>,
+}
+
+/// Field numbers are the temporary entity id plus one. Entities the parser does not split into fields are bytes.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TempEntity {
+    #[prost(
+        oneof = "temp_entity::Entity",
+        tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 18, 19, 20, 21, 22, 23, 24, 25, 26, 28, 29, 30, 31, 32, 100, 101, 102, 103, 104, 105, 106, 107, 108, 109, 110, 111, 112, 113, 114, 115, 116, 117, 118, 119, 120, 121, 122, 123, 124, 125, 126, 127, 128"
+    )]
+    pub entity: Option<temp_entity::Entity>,
+}
+
+/// Nested types of [`TempEntity`].
+pub mod temp_entity {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Entity {
+        #[prost(message, tag = "1")]
+        TeBeamPoints(super::TeBeamPoints),
+        #[prost(bytes = "vec", tag = "2")]
+        TeBeamEntPoint(Vec<u8>),
+        #[prost(bytes = "vec", tag = "3")]
+        TeGunshot(Vec<u8>),
+        #[prost(bytes = "vec", tag = "4")]
+        TeExplosion(Vec<u8>),
+        #[prost(bytes = "vec", tag = "5")]
+        TeTarExplosion(Vec<u8>),
+        #[prost(bytes = "vec", tag = "6")]
+        TeSmoke(Vec<u8>),
+        #[prost(bytes = "vec", tag = "7")]
+        TeTracer(Vec<u8>),
+        #[prost(bytes = "vec", tag = "8")]
+        TeLightning(Vec<u8>),
+        #[prost(bytes = "vec", tag = "9")]
+        TeBeamEnts(Vec<u8>),
+        #[prost(bytes = "vec", tag = "10")]
+        TeSparks(Vec<u8>),
+        #[prost(bytes = "vec", tag = "11")]
+        TeLavaSplash(Vec<u8>),
+        #[prost(bytes = "vec", tag = "12")]
+        TeTeleport(Vec<u8>),
+        #[prost(bytes = "vec", tag = "13")]
+        TeExplosion2(Vec<u8>),
+        #[prost(message, tag = "14")]
+        TeBspDecal(super::TeBspDecal),
+        #[prost(bytes = "vec", tag = "15")]
+        TeImplosion(Vec<u8>),
+        #[prost(bytes = "vec", tag = "16")]
+        TeSpriteTrail(Vec<u8>),
+        #[prost(bytes = "vec", tag = "18")]
+        TeSprite(Vec<u8>),
+        #[prost(bytes = "vec", tag = "19")]
+        TeBeamSprite(Vec<u8>),
+        #[prost(bytes = "vec", tag = "20")]
+        TeBeamTorus(Vec<u8>),
+        #[prost(bytes = "vec", tag = "21")]
+        TeBeamDisk(Vec<u8>),
+        #[prost(bytes = "vec", tag = "22")]
+        TeBeamCylinder(Vec<u8>),
+        #[prost(bytes = "vec", tag = "23")]
+        TeBeamFollow(Vec<u8>),
+        #[prost(bytes = "vec", tag = "24")]
+        TeGlowSprite(Vec<u8>),
+        #[prost(bytes = "vec", tag = "25")]
+        TeBeamRing(Vec<u8>),
+        #[prost(bytes = "vec", tag = "26")]
+        TeStreakSplash(Vec<u8>),
+        #[prost(bytes = "vec", tag = "28")]
+        TeDLight(Vec<u8>),
+        #[prost(bytes = "vec", tag = "29")]
+        TeELight(Vec<u8>),
+        #[prost(message, tag = "30")]
+        TeTextMessage(super::TeTextMessage),
+        #[prost(bytes = "vec", tag = "31")]
+        TeLine(Vec<u8>),
+        #[prost(bytes = "vec", tag = "32")]
+        TeBox(Vec<u8>),
+        #[prost(bytes = "vec", tag = "100")]
+        TeKillBeam(Vec<u8>),
+        #[prost(bytes = "vec", tag = "101")]
+        TeLargeFunnel(Vec<u8>),
+        #[prost(bytes = "vec", tag = "102")]
+        TeBloodStream(Vec<u8>),
+        #[prost(bytes = "vec", tag = "103")]
+        TeShowLine(Vec<u8>),
+        #[prost(bytes = "vec", tag = "104")]
+        TeBlood(Vec<u8>),
+        #[prost(bytes = "vec", tag = "105")]
+        TeDecal(Vec<u8>),
+        #[prost(bytes = "vec", tag = "106")]
+        TeFizz(Vec<u8>),
+        #[prost(bytes = "vec", tag = "107")]
+        TeModel(Vec<u8>),
+        #[prost(bytes = "vec", tag = "108")]
+        TeExplodeModel(Vec<u8>),
+        #[prost(bytes = "vec", tag = "109")]
+        TeBreakModel(Vec<u8>),
+        #[prost(bytes = "vec", tag = "110")]
+        TeGunshotDecal(Vec<u8>),
+        #[prost(bytes = "vec", tag = "111")]
+        TeSpriteSpray(Vec<u8>),
+        #[prost(bytes = "vec", tag = "112")]
+        TeArmorRicochet(Vec<u8>),
+        #[prost(bytes = "vec", tag = "113")]
+        TePlayerDecal(Vec<u8>),
+        #[prost(bytes = "vec", tag = "114")]
+        TeBubbles(Vec<u8>),
+        #[prost(bytes = "vec", tag = "115")]
+        TeBubbleTrail(Vec<u8>),
+        #[prost(bytes = "vec", tag = "116")]
+        TeBloodSprite(Vec<u8>),
+        #[prost(bytes = "vec", tag = "117")]
+        TeWorldDecal(Vec<u8>),
+        #[prost(bytes = "vec", tag = "118")]
+        TeWorldDecalHigh(Vec<u8>),
+        #[prost(bytes = "vec", tag = "119")]
+        TeDecalHigh(Vec<u8>),
+        #[prost(bytes = "vec", tag = "120")]
+        TeProjectile(Vec<u8>),
+        #[prost(bytes = "vec", tag = "121")]
+        TeSpray(Vec<u8>),
+        #[prost(bytes = "vec", tag = "122")]
+        TePlayerSprites(Vec<u8>),
+        #[prost(bytes = "vec", tag = "123")]
+        TeParticleBurst(Vec<u8>),
+        #[prost(bytes = "vec", tag = "124")]
+        TeFireField(Vec<u8>),
+        #[prost(bytes = "vec", tag = "125")]
+        TePlayerAttachment(Vec<u8>),
+        #[prost(bytes = "vec", tag = "126")]
+        TeKillPlayerAttachment(Vec<u8>),
+        #[prost(bytes = "vec", tag = "127")]
+        TeMultigunShot(Vec<u8>),
+        #[prost(bytes = "vec", tag = "128")]
+        TeUserTracer(Vec<u8>),
+    }
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TeBeamPoints {
+    #[prost(int32, repeated, tag = "1")]
+    pub start_position: Vec<i32>,
+    #[prost(int32, repeated, tag = "2")]
+    pub end_position: Vec<i32>,
+    #[prost(int32, tag = "3")]
+    pub sprite_index: i32,
+    #[prost(uint32, tag = "4")]
+    pub start_frame: u32,
+    #[prost(uint32, tag = "5")]
+    pub frame_rate: u32,
+    #[prost(uint32, tag = "6")]
+    pub life: u32,
+    #[prost(uint32, tag = "7")]
+    pub width: u32,
+    #[prost(uint32, tag = "8")]
+    pub noise: u32,
+    #[prost(bytes = "vec", tag = "9")]
+    pub color: Vec<u8>,
+    #[prost(uint32, tag = "10")]
+    pub speed: u32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TeBspDecal {
+    #[prost(bytes = "vec", tag = "1")]
+    pub unknown1: Vec<u8>,
+    #[prost(int32, tag = "2")]
+    pub entity_index: i32,
+    #[prost(bytes = "vec", optional, tag = "3")]
+    pub unknown2: Option<Vec<u8>>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TeTextMessage {
+    #[prost(int32, tag = "1")]
+    pub channel: i32,
+    #[prost(int32, tag = "2")]
+    pub x: i32,
+    #[prost(int32, tag = "3")]
+    pub y: i32,
+    #[prost(int32, tag = "4")]
+    pub effect: i32,
+    #[prost(bytes = "vec", tag = "5")]
+    pub text_color: Vec<u8>,
+    #[prost(bytes = "vec", tag = "6")]
+    pub effect_color: Vec<u8>,
+    #[prost(int32, tag = "7")]
+    pub fade_in_time: i32,
+    #[prost(int32, tag = "8")]
+    pub fade_out_time: i32,
+    #[prost(int32, tag = "9")]
+    pub hold_time: i32,
+    #[prost(int32, optional, tag = "10")]
+    pub effect_time: Option<i32>,
+    #[prost(bytes = "vec", tag = "11")]
+    pub message: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcSetPause {
+    #[prost(int32, tag = "1")]
+    pub is_paused: i32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcSignOnNum {
+    #[prost(int32, tag = "1")]
+    pub sign: i32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcCenterPrint {
+    #[prost(bytes = "vec", tag = "1")]
+    pub message: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcSpawnStaticSound {
+    #[prost(int32, repeated, tag = "1")]
+    pub origin: Vec<i32>,
+    #[prost(uint32, tag = "2")]
+    pub sound_index: u32,
+    #[prost(uint32, tag = "3")]
+    pub volume: u32,
+    #[prost(uint32, tag = "4")]
+    pub attenuation: u32,
+    #[prost(uint32, tag = "5")]
+    pub entity_index: u32,
+    #[prost(uint32, tag = "6")]
+    pub pitch: u32,
+    #[prost(uint32, tag = "7")]
+    pub flags: u32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcFinale {
+    #[prost(bytes = "vec", tag = "1")]
+    pub text: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcCdTrack {
+    #[prost(int32, tag = "1")]
+    pub track: i32,
+    #[prost(int32, tag = "2")]
+    pub loop_track: i32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcRestore {
+    #[prost(bytes = "vec", tag = "1")]
+    pub save_name: Vec<u8>,
+    #[prost(uint32, tag = "2")]
+    pub map_count: u32,
+    #[prost(bytes = "vec", repeated, tag = "3")]
+    pub map_names: Vec<Vec<u8>>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcCutscene {
+    #[prost(bytes = "vec", tag = "1")]
+    pub text: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcWeaponAnim {
+    #[prost(int32, tag = "1")]
+    pub sequence_number: i32,
+    #[prost(int32, tag = "2")]
+    pub weapon_model_body_group: i32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcDecalName {
+    #[prost(uint32, tag = "1")]
+    pub position_index: u32,
+    #[prost(bytes = "vec", tag = "2")]
+    pub decal_name: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcRoomType {
+    #[prost(uint32, tag = "1")]
+    pub room_type: u32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcAddAngle {
+    #[prost(int32, tag = "1")]
+    pub angle_to_add: i32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcNewUserMsg {
+    #[prost(uint32, tag = "1")]
+    pub index: u32,
+    #[prost(int32, tag = "2")]
+    pub size: i32,
+    #[prost(bytes = "vec", tag = "3")]
+    pub name: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcPacketEntities {
+    #[prost(uint32, tag = "1")]
+    pub entity_count: u32,
+    #[prost(message, repeated, tag = "2")]
+    pub entity_states: Vec<EntityState>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EntityState {
+    #[prost(uint32, tag = "1")]
+    pub entity_index: u32,
+    #[prost(bool, tag = "2")]
+    pub increment_entity_number: bool,
+    #[prost(bool, optional, tag = "3")]
+    pub is_absolute_entity_index: Option<bool>,
+    #[prost(uint32, optional, tag = "4")]
+    pub absolute_entity_index: Option<u32>,
+    #[prost(uint32, optional, tag = "5")]
+    pub entity_index_difference: Option<u32>,
+    #[prost(bool, tag = "6")]
+    pub has_custom_delta: bool,
+    #[prost(bool, tag = "7")]
+    pub has_baseline_index: bool,
+    #[prost(uint32, optional, tag = "8")]
+    pub baseline_index: Option<u32>,
+    #[prost(message, optional, tag = "9")]
+    pub delta: Option<Delta>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcDeltaPacketEntities {
+    #[prost(uint32, tag = "1")]
+    pub entity_count: u32,
+    #[prost(uint32, tag = "2")]
+    pub delta_sequence: u32,
+    #[prost(message, repeated, tag = "3")]
+    pub entity_states: Vec<EntityStateDelta>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EntityStateDelta {
+    #[prost(uint32, tag = "1")]
+    pub entity_index: u32,
+    #[prost(bool, tag = "2")]
+    pub remove_entity: bool,
+    #[prost(bool, tag = "3")]
+    pub is_absolute_entity_index: bool,
+    #[prost(uint32, optional, tag = "4")]
+    pub absolute_entity_index: Option<u32>,
+    #[prost(uint32, optional, tag = "5")]
+    pub entity_index_difference: Option<u32>,
+    #[prost(bool, optional, tag = "6")]
+    pub has_custom_delta: Option<bool>,
+    #[prost(message, optional, tag = "7")]
+    pub delta: Option<Delta>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcResourceList {
+    #[prost(uint32, tag = "1")]
+    pub resource_count: u32,
+    #[prost(message, repeated, tag = "2")]
+    pub resources: Vec<Resource>,
+    #[prost(message, repeated, tag = "3")]
+    pub consistencies: Vec<Consistency>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Resource {
+    #[prost(uint32, tag = "1")]
+    pub r#type: u32,
+    #[prost(string, tag = "2")]
+    pub name: String,
+    #[prost(uint32, tag = "3")]
+    pub index: u32,
+    #[prost(uint32, tag = "4")]
+    pub size: u32,
+    #[prost(uint32, tag = "5")]
+    pub flags: u32,
+    #[prost(bytes = "vec", optional, tag = "6")]
+    pub md5_hash: Option<Vec<u8>>,
+    #[prost(bool, tag = "7")]
+    pub has_extra_info: bool,
+    #[prost(bytes = "vec", optional, tag = "8")]
+    pub extra_info: Option<Vec<u8>>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Consistency {
+    #[prost(bool, optional, tag = "1")]
+    pub is_short_index: Option<bool>,
+    #[prost(uint32, optional, tag = "2")]
+    pub short_index: Option<u32>,
+    #[prost(uint32, optional, tag = "3")]
+    pub long_index: Option<u32>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcNewMovevars {
+    #[prost(float, tag = "1")]
+    pub gravity: f32,
+    #[prost(float, tag = "2")]
+    pub stop_speed: f32,
+    #[prost(float, tag = "3")]
+    pub max_speed: f32,
+    #[prost(float, tag = "4")]
+    pub spectator_max_speed: f32,
+    #[prost(float, tag = "5")]
+    pub accelerate: f32,
+    #[prost(float, tag = "6")]
+    pub airaccelerate: f32,
+    #[prost(float, tag = "7")]
+    pub water_accelerate: f32,
+    #[prost(float, tag = "8")]
+    pub friction: f32,
+    #[prost(float, tag = "9")]
+    pub edge_friction: f32,
+    #[prost(float, tag = "10")]
+    pub water_friction: f32,
+    #[prost(float, tag = "11")]
+    pub ent_gravity: f32,
+    #[prost(float, tag = "12")]
+    pub bounce: f32,
+    #[prost(float, tag = "13")]
+    pub step_size: f32,
+    #[prost(float, tag = "14")]
+    pub max_velocity: f32,
+    #[prost(float, tag = "15")]
+    pub z_max: f32,
+    #[prost(float, tag = "16")]
+    pub wave_height: f32,
+    #[prost(uint32, tag = "17")]
+    pub footsteps: u32,
+    #[prost(float, tag = "18")]
+    pub roll_angle: f32,
+    #[prost(float, tag = "19")]
+    pub roll_speed: f32,
+    #[prost(float, repeated, tag = "20")]
+    pub sky_color: Vec<f32>,
+    #[prost(float, repeated, tag = "21")]
+    pub sky_vec: Vec<f32>,
+    #[prost(bytes = "vec", tag = "22")]
+    pub sky_name: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcResourceRequest {
+    #[prost(int32, tag = "1")]
+    pub spawn_count: i32,
+    #[prost(bytes = "vec", tag = "2")]
+    pub unknown: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcCustomization {
+    #[prost(uint32, tag = "1")]
+    pub player_index: u32,
+    #[prost(uint32, tag = "2")]
+    pub r#type: u32,
+    #[prost(bytes = "vec", tag = "3")]
+    pub name: Vec<u8>,
+    #[prost(uint32, tag = "4")]
+    pub index: u32,
+    #[prost(uint32, tag = "5")]
+    pub download_size: u32,
+    #[prost(uint32, tag = "6")]
+    pub flags: u32,
+    #[prost(bytes = "vec", optional, tag = "7")]
+    pub md5_hash: Option<Vec<u8>>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcCrosshairAngle {
+    #[prost(int32, tag = "1")]
+    pub pitch: i32,
+    #[prost(int32, tag = "2")]
+    pub yaw: i32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcSoundFade {
+    #[prost(uint32, tag = "1")]
+    pub initial_percent: u32,
+    #[prost(uint32, tag = "2")]
+    pub hold_time: u32,
+    #[prost(uint32, tag = "3")]
+    pub fade_out_time: u32,
+    #[prost(uint32, tag = "4")]
+    pub fade_in_time: u32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcFileTxferFailed {
+    #[prost(bytes = "vec", tag = "1")]
+    pub file_name: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcHltv {
+    #[prost(uint32, tag = "1")]
+    pub mode: u32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcDirector {
+    #[prost(uint32, tag = "1")]
+    pub length: u32,
+    #[prost(uint32, tag = "2")]
+    pub command: u32,
+    #[prost(bytes = "vec", tag = "3")]
+    pub message: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcVoiceInit {
+    #[prost(bytes = "vec", tag = "1")]
+    pub codec_name: Vec<u8>,
+    #[prost(int32, tag = "2")]
+    pub quality: i32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcVoiceData {
+    #[prost(uint32, tag = "1")]
+    pub player_index: u32,
+    #[prost(uint32, tag = "2")]
+    pub size: u32,
+    #[prost(bytes = "vec", tag = "3")]
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcSendExtraInfo {
+    #[prost(bytes = "vec", tag = "1")]
+    pub fallback_dir: Vec<u8>,
+    #[prost(uint32, tag = "2")]
+    pub can_cheat: u32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcTimeScale {
+    #[prost(float, tag = "1")]
+    pub time_scale: f32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcResourceLocation {
+    #[prost(bytes = "vec", tag = "1")]
+    pub download_url: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcSendCvarValue {
+    #[prost(bytes = "vec", tag = "1")]
+    pub name: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SvcSendCvarValue2 {
+    #[prost(uint32, tag = "1")]
+    pub request_id: u32,
+    #[prost(bytes = "vec", tag = "2")]
+    pub name: Vec<u8>,
+}
@@ -0,0 +1,491 @@
+//! Salvaging demos that were cut off or corrupted, such as when the game crashed while recording.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let (demo, report) =
+//!     Demo::repair_from_file("./src/tests/crashed.dem", MessageDataParseMode::Parse).unwrap();
+//!
+//! println!("{} frames, {} bytes lost", report.frame_count, report.lost_bytes());
+//! demo.write_to_file("./src/tests/repaired.dem").unwrap();
+//! ```
+use std::{ffi::OsStr, fs::OpenOptions, io::Read, ops::Range, path::Path};
+
+use nom::{
+    bytes::complete::take,
+    number::complete::{le_f32, le_i32, le_u32},
+    sequence::tuple,
+};
+
+use crate::{
+    demo_parser::{parse_frame, parse_header, parse_netmsg},
+    error::DemoError,
+    types::{
+        ByteString, Demo, DemoState, Directory, DirectoryEntry, Frame, FrameData, MessageData,
+        MessageDataParseMode, NetworkMessageType,
+    },
+};
+
+const HEADER_LENGTH: usize = 544;
+const DIRECTORY_ENTRY_LENGTH: usize = 92;
+const MAX_DIRECTORY_ENTRIES: u32 = 1024;
+/// Later than any recording would go, anything past this is garbage.
+const MAX_FRAME_TIME: f32 = 1_000_000.;
+/// Frames in a row that have to parse before bytes after garbage are trusted again.
+const RESYNC_FRAMES: usize = 3;
+/// Seconds of recording that can be lost in skipped bytes.
+const MAX_RESYNC_GAP: f32 = 60.;
+
+#[derive(Debug, Clone, Default)]
+pub struct RepairReport {
+    /// Frames kept, not counting an added [`FrameData::NextSection`].
+    pub frame_count: usize,
+    /// Byte ranges thrown away to get to the next frame.
+    pub skipped: Vec<Range<usize>>,
+    /// Bytes after the last frame, usually a frame that was cut off.
+    pub trailing_bytes: usize,
+    /// Network frames whose messages did not parse and are kept as
+    /// [`MessageData::Raw`].
+    pub unparsed_frames: usize,
+    /// Whether the directory at the end of the file was usable. Without it, entries are split at
+    /// every [`FrameData::NextSection`].
+    pub has_directory: bool,
+}
+
+impl RepairReport {
+    /// Bytes skipped or left at the end.
+    pub fn lost_bytes(&self) -> usize {
+        self.skipped.iter().map(|range| range.len()).sum::<usize>() + self.trailing_bytes
+    }
+
+    /// True if the demo could have been opened as it is.
+    pub fn is_intact(&self) -> bool {
+        self.has_directory && self.lost_bytes() == 0 && self.unparsed_frames == 0
+    }
+}
+
+/// What the directory says about an entry, besides where its frames are.
+struct EntryInfo {
+    type_: i32,
+    description: ByteString,
+    flags: i32,
+    cd_track: i32,
+}
+
+impl Demo {
+    /// Like [`Demo::parse_from_file`] but salvages what it can instead of failing.
+    pub fn repair_from_file(
+        path: impl AsRef<OsStr> + AsRef<Path>,
+        netmsg_parse_mode: MessageDataParseMode,
+    ) -> Result<(Self, RepairReport), DemoError> {
+        let mut file = OpenOptions::new().read(true).open(path)?;
+        let mut bytes: Vec<u8> = vec![];
+
+        file.read_to_end(&mut bytes)?;
+
+        Self::repair_from_bytes(bytes.as_slice(), netmsg_parse_mode)
+    }
+
+    /// Reads frames one after another from the header on, without trusting the directory for
+    /// where they are.
+    ///
+    /// When a frame does not parse, bytes are skipped until two frames in a row parse again.
+    /// The directory is rebuilt from the frames found, keeping the type and description of the
+    /// original entries if the directory is still readable.
+    ///
+    /// Network messages are parsed after the frames are found. A frame whose messages do not
+    /// parse is kept as [`MessageData::Raw`].
+    ///
+    /// Fails if the header is broken or there is not a single frame.
+    pub fn repair_from_bytes(
+        demo_bytes: &[u8],
+        netmsg_parse_mode: MessageDataParseMode,
+    ) -> Result<(Self, RepairReport), DemoError> {
+        let (_, header) = parse_header(demo_bytes).map_err(|_| DemoError::ParseError)?;
+
+        let entry_infos = parse_entry_infos(demo_bytes, header.directory_offset);
+        let frames_end = match entry_infos {
+            Some(_) => header.directory_offset as usize,
+            None => demo_bytes.len(),
+        };
+        let frames_bytes = &demo_bytes[..frames_end];
+
+        let mut report = RepairReport {
+            has_directory: entry_infos.is_some(),
+            ..Default::default()
+        };
+
+        // (start offset, end offset, frames)
+        let mut entries: Vec<(usize, usize, Vec<Frame>)> =
+            vec![(HEADER_LENGTH, HEADER_LENGTH, vec![])];
+        let mut offset = HEADER_LENGTH;
+        // frames are read without their network messages, which leaves it as it is
+        let mut raw_state = DemoState::new_raw();
+
+        while offset < frames_end {
+            let Some((next_offset, frame)) = read_frame(frames_bytes, offset, &mut raw_state)
+            else {
+                match resync(
+                    frames_bytes,
+                    offset + 1,
+                    entries.last().and_then(|entry| entry.2.last()),
+                    &mut raw_state,
+                ) {
+                    Some(next_offset) => {
+                        report.skipped.push(offset..next_offset);
+                        offset = next_offset;
+                        continue;
+                    }
+                    None => {
+                        report.trailing_bytes = frames_end - offset;
+                        break;
+                    }
+                }
+            };
+
+            let is_next_section = matches!(frame.frame_data, FrameData::NextSection);
+            let entry = entries.last_mut().unwrap();
+
+            entry.1 = next_offset;
+            entry.2.push(frame);
+            report.frame_count += 1;
+            offset = next_offset;
+
+            if is_next_section {
+                entries.push((offset, offset, vec![]));
+            }
+        }
+
+        entries.retain(|(_, _, frames)| !frames.is_empty());
+
+        if entries.is_empty() {
+            return Err(DemoError::ParseError);
+        }
+
+        let mut state = DemoState::new_raw();
+
+        let entries = entries
+            .into_iter()
+            .enumerate()
+            .map(|(index, (start, end, mut frames))| {
+                for frame in &mut frames {
+                    if let FrameData::NetworkMessage(box_type) = &mut frame.frame_data
+                        && !parse_messages(&mut box_type.1.messages, netmsg_parse_mode, &mut state)
+                    {
+                        report.unparsed_frames += 1;
+                    }
+                }
+
+                let last = frames.last().unwrap();
+
+                if !matches!(last.frame_data, FrameData::NextSection) {
+                    frames.push(Frame {
+                        time: last.time,
+                        frame: last.frame,
+                        frame_data: FrameData::NextSection,
+                    });
+                }
+
+                let info = entry_infos
+                    .as_ref()
+                    .and_then(|infos| infos.get(index))
+                    .map(|info| {
+                        (
+                            info.type_,
+                            info.description.clone(),
+                            info.flags,
+                            info.cd_track,
+                        )
+                    })
+                    .unwrap_or_else(|| {
+                        let (type_, description) = if index == 0 {
+                            (0, "LOADING")
+                        } else {
+                            (1, "Playback")
+                        };
+
+                        (type_, description.into(), -1, -1)
+                    });
+
+                let track_time = if info.0 == 0 {
+                    0.
+                } else {
+                    frames.last().unwrap().time
+                };
+
+                DirectoryEntry {
+                    type_: info.0,
+                    description: info.1,
+                    flags: info.2,
+                    cd_track: info.3,
+                    track_time,
+                    frame_count: frames.len() as i32,
+                    frame_offset: start as i32,
+                    file_length: (end - start) as i32,
+                    frames,
+                }
+            })
+            .collect();
+
+        Ok((
+            Demo {
+                header,
+                directory: Directory { entries },
+                _state: None,
+            },
+            report,
+        ))
+    }
+}
+
+/// Reads the entries of the directory if the directory looks intact.
+fn parse_entry_infos(demo_bytes: &[u8], directory_offset: i32) -> Option<Vec<EntryInfo>> {
+    let directory_offset = usize::try_from(directory_offset).ok()?;
+
+    if directory_offset < HEADER_LENGTH {
+        return None;
+    }
+
+    let (i, entry_count) = le_u32::<_, ()>(demo_bytes.get(directory_offset..)?).ok()?;
+
+    if entry_count == 0 || entry_count > MAX_DIRECTORY_ENTRIES {
+        return None;
+    }
+
+    if i.len() < entry_count as usize * DIRECTORY_ENTRY_LENGTH {
+        return None;
+    }
+
+    (0..entry_count as usize)
+        .map(|index| {
+            let i = &i[index * DIRECTORY_ENTRY_LENGTH..];
+
+            let (_, (type_, description, flags, cd_track, _, _, frame_offset, _)) =
+                tuple((
+                    le_i32::<_, ()>,
+                    take(64usize),
+                    le_i32,
+                    le_i32,
+                    le_f32,
+                    le_i32,
+                    le_i32,
+                    le_i32,
+                ))(i)
+                .ok()?;
+
+            let frame_offset = usize::try_from(frame_offset).ok()?;
+
+            (HEADER_LENGTH..=directory_offset)
+                .contains(&frame_offset)
+                .then(|| EntryInfo {
+                    type_,
+                    description: description.into(),
+                    flags,
+                    cd_track,
+                })
+        })
+        .collect()
+}
+
+/// Parses the frame at `offset` without its network messages, returning where the next frame
+/// starts.
+///
+/// `state` is not touched as network messages are not parsed.
+fn read_frame(bytes: &[u8], offset: usize, state: &mut DemoState) -> Option<(usize, Frame)> {
+    let (rest, frame) = parse_frame(&bytes[offset..], MessageDataParseMode::Raw, state).ok()?;
+
+    let is_plausible = frame.time.is_finite()
+        && (0. ..=MAX_FRAME_TIME).contains(&frame.time)
+        && frame.frame >= 0
+        && !matches!(
+            &frame.frame_data,
+            FrameData::NetworkMessage(box_type)
+                if matches!(box_type.0, NetworkMessageType::Unknown(_))
+        );
+
+    is_plausible.then(|| (bytes.len() - rest.len(), frame))
+}
+
+/// First offset from `start` where [`RESYNC_FRAMES`] frames in a row parse, or fewer if they reach
+/// the end.
+///
+/// The first of them should carry on from the last good frame, unless no such frame is found and
+/// a new entry could have started in the skipped bytes.
+fn resync(
+    bytes: &[u8],
+    start: usize,
+    last: Option<&Frame>,
+    state: &mut DemoState,
+) -> Option<usize> {
+    let is_chain = |offset: usize, state: &mut DemoState| {
+        let mut offset = offset;
+
+        for _ in 0..RESYNC_FRAMES {
+            if offset == bytes.len() {
+                return true;
+            }
+
+            let Some((next_offset, _)) = read_frame(bytes, offset, state) else {
+                return false;
+            };
+
+            offset = next_offset;
+        }
+
+        true
+    };
+
+    let carries_on = |offset: usize, state: &mut DemoState| {
+        let Some(last) = last else {
+            return true;
+        };
+
+        read_frame(bytes, offset, state).is_some_and(|(_, frame)| {
+            frame.frame >= last.frame
+                && (last.time..=last.time + MAX_RESYNC_GAP).contains(&frame.time)
+        })
+    };
+
+    (start..bytes.len())
+        .find(|&offset| carries_on(offset, state) && is_chain(offset, state))
+        .or_else(|| (start..bytes.len()).find(|&offset| is_chain(offset, state)))
+}
+
+/// Turns raw messages into the requested mode. Returns false if they do not parse, in which case
+/// they stay raw and the state is unchanged.
+fn parse_messages(
+    messages: &mut MessageData,
+    netmsg_parse_mode: MessageDataParseMode,
+    state: &mut DemoState,
+) -> bool {
+    let MessageData::Raw(bytes) = messages else {
+        return true;
+    };
+
+    match netmsg_parse_mode {
+        MessageDataParseMode::Raw => true,
+        MessageDataParseMode::None => {
+            *messages = MessageData::None;
+            true
+        }
+        MessageDataParseMode::Parse => {
+            let mut new_state = state.clone();
+
+            match parse_netmsg(bytes, &mut new_state) {
+                Ok((_, parsed)) => {
+                    *messages = MessageData::Parsed(parsed);
+                    *state = new_state;
+                    true
+                }
+                Err(_) => false,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::open_demo_from_bytes;
+
+    const WEAPON: &[u8] = include_bytes!("./tests/weapon.dem");
+
+    #[test]
+    fn intact() {
+        let (demo, report) = Demo::repair_from_bytes(WEAPON, MessageDataParseMode::Parse).unwrap();
+        let original = open_demo_from_bytes(WEAPON).unwrap();
+
+        assert!(report.is_intact());
+        assert_eq!(
+            demo.directory.entries.len(),
+            original.directory.entries.len()
+        );
+        assert_eq!(
+            demo.directory.entries[1].frames.len(),
+            original.directory.entries[1].frames.len()
+        );
+    }
+
+    #[test]
+    fn truncated() {
+        let bytes = &WEAPON[..WEAPON.len() * 2 / 3];
+
+        assert!(open_demo_from_bytes(bytes).is_err());
+
+        let (demo, report) = Demo::repair_from_bytes(bytes, MessageDataParseMode::Parse).unwrap();
+
+        assert!(!report.has_directory);
+        assert!(report.trailing_bytes > 0);
+        assert!(matches!(
+            demo.directory
+                .entries
+                .last()
+                .unwrap()
+                .frames
+                .last()
+                .unwrap()
+                .frame_data,
+            FrameData::NextSection
+        ));
+
+        let demo = open_demo_from_bytes(&demo.write_to_bytes()).unwrap();
+
+        assert_eq!(demo.directory.entries.len(), 2);
+    }
+
+    #[test]
+    fn corrupted() {
+        let mut bytes = WEAPON.to_vec();
+        let middle = bytes.len() / 2;
+
+        bytes[middle..middle + 200].fill(0xAB);
+
+        let (demo, report) = Demo::repair_from_bytes(&bytes, MessageDataParseMode::Parse).unwrap();
+        let original = open_demo_from_bytes(WEAPON).unwrap();
+
+        assert!(report.has_directory);
+        assert_eq!(report.skipped.len(), 1);
+        assert!(report.skipped[0].contains(&middle));
+
+        // only the frames around the garbage are lost
+        let lost =
+            original.directory.entries[1].frames.len() - demo.directory.entries[1].frames.len();
+
+        assert!(lost > 0 && lost < 5);
+    }
+
+    #[test]
+    fn cut_off_messages() {
+        let (mut demo, _) = Demo::repair_from_bytes(WEAPON, MessageDataParseMode::Raw).unwrap();
+        let mut state = DemoState::new_raw();
+        let mut failed = 0;
+
+        for frame in demo
+            .directory
+            .entries
+            .iter_mut()
+            .flat_map(|entry| &mut entry.frames)
+        {
+            let FrameData::NetworkMessage(box_type) = &mut frame.frame_data else {
+                continue;
+            };
+            let MessageData::Raw(bytes) = &box_type.1.messages else {
+                unreachable!();
+            };
+
+            // cuts through bit packed messages such as packet entities, which fail instead of
+            // reading past the end
+            for length in [bytes.len() / 2, bytes.len().saturating_sub(1)] {
+                failed += parse_netmsg(&bytes[..length], &mut state.clone()).is_err() as usize;
+            }
+
+            assert!(parse_messages(
+                &mut box_type.1.messages,
+                MessageDataParseMode::Parse,
+                &mut state
+            ));
+        }
+
+        assert!(failed > 0);
+    }
+}
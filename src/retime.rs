@@ -0,0 +1,188 @@
+//! Changing the speed of a demo or smoothing its frame times.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut demo = open_demo("./src/tests/demotest.dem").unwrap();
+//!
+//! // half speed
+//! demo.retime(|time| time * 2.);
+//!
+//! // or snap every frame to 100 fps
+//! demo.retime(|time| (time * 100.).round() / 100.);
+//! ```
+use crate::{
+    error::DemoError,
+    types::{Demo, DirectoryEntry, EngineMessage, FrameData},
+};
+
+/// Time step for the slope of the mapping where there is no interval to measure it over.
+const SLOPE_STEP: f32 = 0.001;
+
+impl Demo {
+    /// Maps every time value in the playback entries through `f`, which takes the old
+    /// [`crate::types::Frame::time`] and returns the new one.
+    ///
+    /// Clocks that run along with the frame time, [`crate::types::SvcTime`],
+    /// [`crate::types::RefParams::time`] and [`crate::types::DemoInfo::timestamp`], keep their
+    /// distance from the frame time. Durations, [`crate::types::RefParams::frame_time`],
+    /// [`crate::types::UserCmd::msec`], event delays and fire times, become the length of their
+    /// interval after mapping. [`crate::types::SvcTimeScale`] is divided by how much time is
+    /// stretched around the frame.
+    ///
+    /// `f` should not go backwards. LOADING entries are left alone.
+    pub fn retime(&mut self, mut f: impl FnMut(f32) -> f32) {
+        for entry in &mut self.directory.entries {
+            if !entry.is_loading() {
+                retime_entry(entry, &mut f);
+            }
+        }
+    }
+
+    /// Plays the demo `speed` times as fast. 0.5 is half speed.
+    ///
+    /// `speed` must be positive and finite.
+    pub fn set_speed(&mut self, speed: f32) -> Result<(), DemoError> {
+        if !(speed > 0. && speed.is_finite()) {
+            return Err(DemoError::InvalidSpeed);
+        }
+
+        self.retime(|time| time / speed);
+
+        Ok(())
+    }
+}
+
+/// [`Demo::retime`] of one entry, which also sets its
+/// [`crate::types::DirectoryEntry::track_time`].
+pub(crate) fn retime_entry(entry: &mut DirectoryEntry, mut f: impl FnMut(f32) -> f32) {
+    for frame in &mut entry.frames {
+        let old_time = frame.time;
+        let new_time = f(old_time);
+        let shift = new_time - old_time;
+
+        frame.time = new_time;
+
+        match &mut frame.frame_data {
+            FrameData::Event(event) => {
+                event.delay = map_duration(&mut f, old_time, event.delay);
+            }
+            FrameData::NetworkMessage(box_type) => {
+                let data = &mut box_type.1;
+                let refparams = &mut data.info.refparams;
+
+                data.info.timestamp += shift;
+                refparams.time += shift;
+                // frame time and msec lead up to this frame
+                refparams.frame_time =
+                    -map_duration(&mut f, old_time, -refparams.frame_time).min(0.);
+
+                let msec = data.info.usercmd.msec as f32 / 1000.;
+                data.info.usercmd.msec = (-map_duration(&mut f, old_time, -msec) * 1000.)
+                    .round()
+                    .clamp(0., 255.) as u8;
+
+                let slope = map_duration(&mut f, old_time, SLOPE_STEP) / SLOPE_STEP;
+
+                let Some(messages) = data.messages.get_parsed_messages_mut() else {
+                    continue;
+                };
+
+                for message in messages {
+                    match message.get_engine_message_mut() {
+                        Some(EngineMessage::SvcTime(time)) => time.time += shift,
+                        Some(EngineMessage::SvcTimeScale(time_scale)) if slope > 0. => {
+                            time_scale.time_scale /= slope
+                        }
+                        Some(EngineMessage::SvcEvent(event)) => {
+                            for event in &mut event.events {
+                                retime_fire_time(&mut event.fire_time, |delay| {
+                                    map_duration(&mut f, old_time, delay)
+                                });
+                            }
+                        }
+                        Some(EngineMessage::SvcEventReliable(event)) => {
+                            retime_fire_time(&mut event.fire_time, |delay| {
+                                map_duration(&mut f, old_time, delay)
+                            });
+                        }
+                        _ => (),
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    entry.track_time = entry
+        .frames
+        .last()
+        .map(|frame| frame.time)
+        .unwrap_or(entry.track_time);
+}
+
+fn map_duration(f: &mut impl FnMut(f32) -> f32, time: f32, duration: f32) -> f32 {
+    f(time + duration) - f(time)
+}
+
+/// Fire time is sent in hundredths of a second.
+fn retime_fire_time(fire_time: &mut Option<u16>, mut map_duration: impl FnMut(f32) -> f32) {
+    if let Some(fire_time) = fire_time {
+        let delay = map_duration(*fire_time as f32 / 100.);
+
+        *fire_time = (delay * 100.).round().clamp(0., u16::MAX as f32) as u16;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{error::DemoError, open_demo, types::FrameData};
+
+    #[test]
+    fn half_speed() {
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let mut slow = demo.clone();
+
+        slow.set_speed(0.5).unwrap();
+
+        let frames = demo.directory.entries[1].frames.iter();
+        let slow_frames = slow.directory.entries[1].frames.iter();
+
+        for (frame, slow_frame) in frames.zip(slow_frames) {
+            assert!((slow_frame.time - frame.time * 2.).abs() < 0.001);
+
+            if let (FrameData::NetworkMessage(old), FrameData::NetworkMessage(new)) =
+                (&frame.frame_data, &slow_frame.frame_data)
+            {
+                let old = &old.1.info;
+                let new = &new.1.info;
+
+                assert!((new.refparams.frame_time - old.refparams.frame_time * 2.).abs() < 0.001);
+                assert!(
+                    new.usercmd
+                        .msec
+                        .abs_diff(old.usercmd.msec.saturating_mul(2))
+                        <= 1
+                );
+            }
+        }
+
+        // loading is untouched
+        assert_eq!(
+            demo.directory.entries[0].frames[0].time,
+            slow.directory.entries[0].frames[0].time
+        );
+    }
+
+    #[test]
+    fn invalid_speed() {
+        let mut demo = open_demo("./src/tests/weapon.dem").unwrap();
+
+        for speed in [0., -1., f32::NAN, f32::INFINITY] {
+            assert!(matches!(
+                demo.set_speed(speed),
+                Err(DemoError::InvalidSpeed)
+            ));
+        }
+    }
+}
@@ -0,0 +1,291 @@
+//! Players in a demo, tracked from [`SvcUpdateUserInfo`] and [`SvcServerInfo`].
+//!
+//! # Example
+//!
+//! ```ignore
+//! let demo = open_demo("./src/tests/demotest.dem").unwrap();
+//! let roster = demo.player_roster();
+//!
+//! for event in roster.history() {
+//!     println!("{} {} {:?}", event.time, event.slot, event.kind);
+//! }
+//! ```
+use std::collections::BTreeMap;
+
+use crate::{
+    infostring::InfoString,
+    types::{Demo, EngineMessage, FrameData, SvcServerInfo, SvcUpdateUserInfo},
+};
+
+#[derive(Debug, Clone)]
+pub struct PlayerInfo {
+    /// 0-based player slot.
+    pub slot: u8,
+    pub user_id: u32,
+    pub name: String,
+    pub model: Option<String>,
+    pub team: Option<String>,
+    pub top_color: Option<i32>,
+    pub bottom_color: Option<i32>,
+    /// `*sid`, the SteamID64 of the player.
+    pub steam_id: Option<String>,
+    /// `*hltv` is set for HLTV proxies.
+    pub is_hltv: bool,
+    pub info: InfoString,
+}
+
+impl PlayerInfo {
+    fn new(slot: u8, user_id: u32, info: InfoString) -> Self {
+        let number = |key| info.get_lossy(key).and_then(|value| value.parse().ok());
+
+        Self {
+            slot,
+            user_id,
+            name: info.get_lossy("name").unwrap_or_default(),
+            model: info.get_lossy("model"),
+            team: info.get_lossy("team"),
+            top_color: number("topcolor"),
+            bottom_color: number("bottomcolor"),
+            steam_id: info.get_lossy("*sid"),
+            is_hltv: info.contains_key("*hltv"),
+            info,
+        }
+    }
+
+    /// Entity index of the player, which is what user messages usually refer to.
+    pub fn entity_index(&self) -> u16 {
+        self.slot as u16 + 1
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RosterEventKind {
+    Join,
+    Leave,
+    Rename {
+        old_name: String,
+    },
+    /// Other infostring values changed.
+    Update,
+}
+
+#[derive(Debug, Clone)]
+pub struct RosterEvent {
+    /// [`crate::types::Frame::time`] of the frame containing the message.
+    pub time: f32,
+    pub entry_index: usize,
+    pub frame_index: usize,
+    pub slot: u8,
+    pub kind: RosterEventKind,
+    /// Player after the event. None if the player left.
+    pub player: Option<PlayerInfo>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PlayerRoster {
+    /// From the latest [`SvcServerInfo`].
+    pub max_players: u8,
+    /// Slot of the recording client from the latest [`SvcServerInfo`].
+    pub player_index: Option<u8>,
+    players: BTreeMap<u8, PlayerInfo>,
+    history: Vec<RosterEvent>,
+}
+
+impl PlayerRoster {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds the roster from every parsed network message in the demo.
+    pub fn from_demo(demo: &Demo) -> Self {
+        let mut roster = Self::new();
+
+        for (entry_index, entry) in demo.directory.entries.iter().enumerate() {
+            for (frame_index, frame) in entry.frames.iter().enumerate() {
+                let FrameData::NetworkMessage(box_type) = &frame.frame_data else {
+                    continue;
+                };
+
+                let Some(messages) = box_type.1.messages.get_parsed_messages() else {
+                    continue;
+                };
+
+                messages
+                    .iter()
+                    .filter_map(|message| message.get_engine_message())
+                    .for_each(|message| {
+                        roster.apply(message, frame.time, entry_index, frame_index)
+                    });
+            }
+        }
+
+        roster
+    }
+
+    /// Updates the roster with a message. Messages other than [`SvcServerInfo`] and
+    /// [`SvcUpdateUserInfo`] are ignored.
+    ///
+    /// This is for callers walking the demo themselves who want the roster as of the current
+    /// frame.
+    pub fn apply(
+        &mut self,
+        message: &EngineMessage,
+        time: f32,
+        entry_index: usize,
+        frame_index: usize,
+    ) {
+        match message {
+            EngineMessage::SvcServerInfo(info) => self.apply_server_info(info),
+            EngineMessage::SvcUpdateUserInfo(info) => {
+                self.apply_user_info(info, time, entry_index, frame_index)
+            }
+            _ => (),
+        }
+    }
+
+    fn apply_server_info(&mut self, info: &SvcServerInfo) {
+        self.max_players = info.max_players;
+        self.player_index = Some(info.player_index);
+    }
+
+    fn apply_user_info(
+        &mut self,
+        info: &SvcUpdateUserInfo,
+        time: f32,
+        entry_index: usize,
+        frame_index: usize,
+    ) {
+        let slot = info.index;
+        let infostring = InfoString::from(&info.user_info);
+
+        let push = |roster: &mut Self, kind, player: Option<PlayerInfo>| {
+            roster.history.push(RosterEvent {
+                time,
+                entry_index,
+                frame_index,
+                slot,
+                kind,
+                player,
+            })
+        };
+
+        // empty infostring means the slot is freed
+        if infostring.is_empty() {
+            if self.players.remove(&slot).is_some() {
+                push(self, RosterEventKind::Leave, None);
+            }
+
+            return;
+        }
+
+        let player = PlayerInfo::new(slot, info.id, infostring);
+
+        let kind = match self.players.get(&slot) {
+            None => RosterEventKind::Join,
+            // different user id means someone else took the slot
+            Some(old) if old.user_id != player.user_id => {
+                push(self, RosterEventKind::Leave, None);
+                RosterEventKind::Join
+            }
+            Some(old) if old.name != player.name => RosterEventKind::Rename {
+                old_name: old.name.clone(),
+            },
+            Some(old) if old.info.to_bytes() == player.info.to_bytes() => return,
+            Some(_) => RosterEventKind::Update,
+        };
+
+        push(self, kind, Some(player.clone()));
+        self.players.insert(slot, player);
+    }
+
+    /// Players currently in the server.
+    pub fn players(&self) -> impl Iterator<Item = &PlayerInfo> {
+        self.players.values()
+    }
+
+    /// Every join, leave, rename and update in the order they happen.
+    pub fn history(&self) -> &[RosterEvent] {
+        &self.history
+    }
+
+    pub fn by_slot(&self, slot: u8) -> Option<&PlayerInfo> {
+        self.players.get(&slot)
+    }
+
+    pub fn by_user_id(&self, user_id: u32) -> Option<&PlayerInfo> {
+        self.players
+            .values()
+            .find(|player| player.user_id == user_id)
+    }
+
+    pub fn by_entity_index(&self, entity_index: u16) -> Option<&PlayerInfo> {
+        let slot = entity_index.checked_sub(1)?;
+
+        self.players.get(&u8::try_from(slot).ok()?)
+    }
+
+    /// Player in the slot as of the given entry and frame time, looking through the history.
+    ///
+    /// Frame time restarts with each directory entry so the entry is needed as well.
+    pub fn by_slot_at(&self, slot: u8, entry_index: usize, time: f32) -> Option<&PlayerInfo> {
+        self.history
+            .iter()
+            .take_while(|event| {
+                event.entry_index < entry_index
+                    || (event.entry_index == entry_index && event.time <= time)
+            })
+            .filter(|event| event.slot == slot)
+            .last()
+            .and_then(|event| event.player.as_ref())
+    }
+}
+
+impl Demo {
+    /// Tracks every player in the demo. See [`PlayerRoster`].
+    pub fn player_roster(&self) -> PlayerRoster {
+        PlayerRoster::from_demo(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::types::ByteString;
+
+    fn user_info(index: u8, id: u32, info: &str) -> EngineMessage {
+        EngineMessage::SvcUpdateUserInfo(SvcUpdateUserInfo {
+            index,
+            id,
+            user_info: info.into(),
+            cd_key_hash: ByteString(vec![0; 16]),
+        })
+    }
+
+    #[test]
+    fn join_rename_leave() {
+        let mut roster = PlayerRoster::new();
+
+        roster.apply(&user_info(0, 5, "\\name\\foo\\*sid\\1\0"), 1., 1, 0);
+        roster.apply(&user_info(0, 5, "\\name\\bar\\*sid\\1\0"), 2., 1, 1);
+
+        assert_eq!(roster.by_entity_index(1).unwrap().name, "bar");
+        assert_eq!(roster.by_user_id(5).unwrap().steam_id.as_deref(), Some("1"));
+
+        roster.apply(&user_info(0, 5, "\0"), 3., 1, 2);
+
+        assert!(roster.by_slot(0).is_none());
+        assert_eq!(roster.by_slot_at(0, 1, 1.5).unwrap().name, "foo");
+
+        let kinds: Vec<_> = roster.history().iter().map(|event| &event.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                &RosterEventKind::Join,
+                &RosterEventKind::Rename {
+                    old_name: "foo".to_owned()
+                },
+                &RosterEventKind::Leave
+            ]
+        );
+    }
+}
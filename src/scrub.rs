@@ -0,0 +1,380 @@
+//! Removing personal data from a demo before it is published.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut demo = open_demo("./src/tests/demotest.dem").unwrap();
+//!
+//! demo.scrub(&ScrubOptions {
+//!     chat: false,
+//!     ..Default::default()
+//! });
+//!
+//! demo.write_to_file("./src/tests/public.dem").unwrap();
+//! ```
+use std::collections::HashMap;
+
+use crate::{
+    infostring::InfoString,
+    types::{ByteString, ByteVec, Demo, EngineMessage, FrameData, NetMessage},
+};
+
+#[derive(Debug, Clone)]
+pub struct ScrubOptions {
+    /// Replaces player names with pseudonyms, in user info and as whole words in any text.
+    pub names: bool,
+    /// Prefix of the pseudonyms, followed by a number in order of appearance.
+    pub pseudonym_prefix: String,
+    /// Removes `*sid` from user info.
+    pub steam_ids: bool,
+    /// Zeroes [`crate::types::SvcUpdateUserInfo::cd_key_hash`].
+    pub cd_key_hashes: bool,
+    /// Replaces [`crate::types::SvcServerInfo::hostname`].
+    pub hostname: Option<String>,
+    /// Removes [`crate::types::SvcCustomization`], which carry spray logos and their hashes.
+    pub customizations: bool,
+    /// Removes [`crate::types::SvcVoiceData`].
+    pub voice: bool,
+    /// Removes [`crate::types::SvcSendCvarValue`] and [`crate::types::SvcSendCvarValue2`].
+    pub cvar_queries: bool,
+    /// Removes `SayText` and chat `TextMsg`.
+    pub chat: bool,
+}
+
+impl Default for ScrubOptions {
+    fn default() -> Self {
+        Self {
+            names: true,
+            pseudonym_prefix: "Player ".to_owned(),
+            steam_ids: true,
+            cd_key_hashes: true,
+            hostname: Some("Half-Life".to_owned()),
+            customizations: true,
+            voice: true,
+            cvar_queries: true,
+            chat: true,
+        }
+    }
+}
+
+/// `TextMsg` destination for chat.
+const HUD_PRINTTALK: u8 = 3;
+
+impl Demo {
+    /// Rewrites or removes personal data in every parsed network message.
+    ///
+    /// A player keeps the same pseudonym through renames and reconnects as long as their `*sid`
+    /// stays the same. Without `*sid`, the user id is used instead. Names in text get the pseudonym
+    /// of the first player to use the name.
+    pub fn scrub(&mut self, options: &ScrubOptions) {
+        let pseudonyms = self.pseudonyms(&options.pseudonym_prefix);
+
+        // longest first so a name is not replaced inside a longer one
+        let mut names: Vec<(&[u8], &[u8])> = pseudonyms
+            .by_name
+            .iter()
+            .filter(|(name, _)| !name.is_empty())
+            .map(|(name, pseudonym)| (name.as_bytes(), pseudonym.as_bytes()))
+            .collect();
+        names.sort_by_key(|(name, _)| std::cmp::Reverse(name.len()));
+
+        let replace_names = |text: &[u8]| -> ByteVec {
+            if !options.names {
+                return text.to_vec();
+            }
+
+            replace_words(text, &names)
+        };
+
+        self.retain_messages(|ctx, message| match message {
+            NetMessage::UserMessage(user_message) => {
+                let name = user_message.name_str().to_owned();
+
+                if options.chat {
+                    let is_chat = name == "SayText"
+                        || (name == "TextMsg" && user_message.data.first() == Some(&HUD_PRINTTALK));
+
+                    if is_chat {
+                        return false;
+                    }
+                }
+
+                // only variable sized text can change length
+                let is_variable = ctx
+                    .state
+                    .custom_messages
+                    .get(&user_message.id)
+                    .is_some_and(|registered| registered.size == -1);
+
+                if options.names && is_variable && matches!(name.as_str(), "SayText" | "TextMsg") {
+                    let data = replace_names(&user_message.data);
+
+                    // length is sent in a byte
+                    if data.len() > u8::MAX as usize {
+                        return false;
+                    }
+
+                    user_message.data = data;
+                }
+
+                true
+            }
+            NetMessage::EngineMessage(engine_message) => {
+                scrub_engine_message(engine_message, options, &pseudonyms, replace_names)
+            }
+        });
+    }
+
+    /// Pseudonyms of every player, found by [`identity`].
+    fn pseudonyms(&self, prefix: &str) -> Pseudonyms {
+        let mut res = Pseudonyms::default();
+
+        for entry in &self.directory.entries {
+            for frame in &entry.frames {
+                let FrameData::NetworkMessage(box_type) = &frame.frame_data else {
+                    continue;
+                };
+
+                let Some(messages) = box_type.1.messages.get_parsed_messages() else {
+                    continue;
+                };
+
+                for message in messages {
+                    let Some(EngineMessage::SvcUpdateUserInfo(user_info)) =
+                        message.get_engine_message()
+                    else {
+                        continue;
+                    };
+
+                    let info = InfoString::from(&user_info.user_info);
+                    let Some(name) = info.get_lossy("name") else {
+                        continue;
+                    };
+
+                    let count = res.by_identity.len();
+                    let pseudonym = res
+                        .by_identity
+                        .entry(identity(&info, user_info.id))
+                        .or_insert_with(|| format!("{prefix}{}", count + 1));
+
+                    res.by_name.entry(name).or_insert_with(|| pseudonym.clone());
+                }
+            }
+        }
+
+        res
+    }
+}
+
+#[derive(Debug, Default)]
+struct Pseudonyms {
+    /// For user info.
+    by_identity: HashMap<String, String>,
+    /// For names in text, where the first player to use a name keeps it.
+    by_name: HashMap<String, String>,
+}
+
+/// `*sid` of the player, or their user id in `#1` form without one.
+fn identity(info: &InfoString, user_id: u32) -> String {
+    info.get_lossy("*sid")
+        .filter(|sid| !sid.is_empty() && sid != "0")
+        .unwrap_or_else(|| format!("#{user_id}"))
+}
+
+/// Returns false if the message should be removed.
+fn scrub_engine_message(
+    message: &mut EngineMessage,
+    options: &ScrubOptions,
+    pseudonyms: &Pseudonyms,
+    replace_names: impl Fn(&[u8]) -> ByteVec,
+) -> bool {
+    match message {
+        EngineMessage::SvcUpdateUserInfo(user_info) => {
+            let mut info = InfoString::from(&user_info.user_info);
+
+            if !info.is_empty() {
+                if options.names
+                    && info.get_lossy("name").is_some()
+                    && let Some(pseudonym) =
+                        pseudonyms.by_identity.get(&identity(&info, user_info.id))
+                {
+                    info.set("name", pseudonym.as_str());
+                }
+
+                if options.steam_ids {
+                    info.remove("*sid");
+                }
+
+                user_info.user_info = ByteString::from(&info);
+            }
+
+            if options.cd_key_hashes {
+                user_info.cd_key_hash = ByteString(vec![0; 16]);
+            }
+        }
+        EngineMessage::SvcServerInfo(server_info) => {
+            if let Some(hostname) = &options.hostname {
+                server_info.hostname = hostname.bytes().chain([0]).collect();
+            }
+        }
+        EngineMessage::SvcPrint(print) => {
+            print.message = replace_names(print.message.as_slice()).into()
+        }
+        EngineMessage::SvcCenterPrint(print) => print.message = replace_names(&print.message),
+        EngineMessage::SvcCustomization(_) => return !options.customizations,
+        EngineMessage::SvcVoiceData(_) => return !options.voice,
+        EngineMessage::SvcSendCvarValue(_) | EngineMessage::SvcSendCvarValue2(_) => {
+            return !options.cvar_queries;
+        }
+        _ => (),
+    }
+
+    true
+}
+
+fn is_word_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_'
+}
+
+/// Replaces every whole word match of a name with its pseudonym in one pass over `haystack`, so
+/// inserted pseudonyms are never matched again. The first matching name wins at each position.
+fn replace_words(haystack: &[u8], words: &[(&[u8], &[u8])]) -> ByteVec {
+    let mut res = vec![];
+    let mut i = 0;
+
+    while i < haystack.len() {
+        let starts_word = i == 0 || !is_word_byte(haystack[i - 1]);
+
+        let found = words.iter().find(|(word, _)| {
+            let end = i + word.len();
+
+            starts_word
+                && !word.is_empty()
+                && haystack[i..].starts_with(word)
+                && haystack.get(end).is_none_or(|&byte| !is_word_byte(byte))
+        });
+
+        match found {
+            Some((word, replacement)) => {
+                res.extend_from_slice(replacement);
+                i += word.len();
+            }
+            None => {
+                res.push(haystack[i]);
+                i += 1;
+            }
+        }
+    }
+
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        open_demo, open_demo_from_bytes,
+        roster::PlayerRoster,
+        types::{MessageData, SvcUpdateUserInfo},
+    };
+
+    fn user_info(index: u8, id: u32, name: &str, sid: &str) -> NetMessage {
+        NetMessage::EngineMessage(Box::new(EngineMessage::SvcUpdateUserInfo(
+            SvcUpdateUserInfo {
+                index,
+                id,
+                user_info: format!("\\name\\{name}\\*sid\\{sid}\0").into_bytes().into(),
+                cd_key_hash: ByteString(vec![1; 16]),
+            },
+        )))
+    }
+
+    #[test]
+    fn replace() {
+        assert_eq!(
+            replace_words(b"foo: hi foo\0", &[(b"foo", b"bar")]),
+            b"bar: hi bar\0"
+        );
+    }
+
+    #[test]
+    fn replace_whole_words() {
+        let words: [(&[u8], &[u8]); 2] = [(b"abc", b"Player 1"), (b"1", b"Player 2")];
+
+        // "1" is part of the pseudonym inserted for "abc"
+        assert_eq!(replace_words(b"abc: hi", &words), b"Player 1: hi");
+        assert_eq!(
+            replace_words(b"1 killed abc", &words),
+            b"Player 2 killed Player 1"
+        );
+        assert_eq!(replace_words(b"abcd 10 x1", &words), b"abcd 10 x1");
+    }
+
+    #[test]
+    fn scrub_names() {
+        let mut demo = open_demo("./src/tests/weapon.dem").unwrap();
+        demo.scrub(&ScrubOptions::default());
+
+        let demo = open_demo_from_bytes(&demo.write_to_bytes()).unwrap();
+        let roster = PlayerRoster::from_demo(&demo);
+
+        assert!(!roster.history().is_empty());
+        assert!(roster.history().iter().all(|event| {
+            event.player.as_ref().is_none_or(|player| {
+                player.name.starts_with("Player ") && player.steam_id.is_none()
+            })
+        }));
+    }
+
+    #[test]
+    fn same_name() {
+        let mut demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let index = demo.directory.entries[1]
+            .frames
+            .iter()
+            .position(|frame| matches!(frame.frame_data, FrameData::NetworkMessage(_)))
+            .unwrap();
+
+        let FrameData::NetworkMessage(box_type) =
+            &mut demo.directory.entries[1].frames[index].frame_data
+        else {
+            panic!("not a network frame");
+        };
+
+        // two players called Player, then one takes the name of a third who left
+        box_type.1.messages = MessageData::Parsed(vec![
+            user_info(1, 10, "Player", "111"),
+            user_info(2, 11, "Player", "222"),
+            user_info(3, 12, "Bob", "333"),
+            user_info(1, 10, "Bob", "111"),
+        ]);
+
+        demo.scrub(&ScrubOptions {
+            pseudonym_prefix: "Anon ".to_owned(),
+            ..Default::default()
+        });
+
+        let FrameData::NetworkMessage(box_type) =
+            &demo.directory.entries[1].frames[index].frame_data
+        else {
+            panic!("not a network frame");
+        };
+
+        let names: Vec<String> = box_type
+            .1
+            .messages
+            .get_parsed_messages()
+            .unwrap()
+            .iter()
+            .filter_map(|message| match message.get_engine_message() {
+                Some(EngineMessage::SvcUpdateUserInfo(user_info)) => {
+                    InfoString::from(&user_info.user_info).get_lossy("name")
+                }
+                _ => None,
+            })
+            .collect();
+
+        // the recording player is Anon 1
+        assert_eq!(names, ["Anon 2", "Anon 3", "Anon 4", "Anon 2"]);
+    }
+}
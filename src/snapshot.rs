@@ -0,0 +1,378 @@
+//! Full entity and client data state rebuilt from baselines and deltas.
+//!
+//! The client keeps the state of recent frames by their incoming sequence and the server deltas
+//! [`SvcDeltaPacketEntities`] and [`SvcClientData`] against one of them. [`SnapshotTracker`] does
+//! the same so the complete state at any frame can be known and written back out as a message
+//! that does not depend on earlier frames.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let demo = open_demo("./src/tests/demotest.dem").unwrap();
+//! let mut tracker = SnapshotTracker::new();
+//!
+//! for entry in &demo.directory.entries {
+//!     for frame in &entry.frames {
+//!         let FrameData::NetworkMessage(box_type) = &frame.frame_data else {
+//!             continue;
+//!         };
+//!
+//!         tracker.apply_network_message(&box_type.1);
+//!     }
+//! }
+//!
+//! println!("{} entities at the end", tracker.current().entities.len());
+//! ```
+use std::collections::{BTreeMap, HashMap};
+
+use crate::types::{
+    ClientDataWeaponData, Delta, DemoState, EngineMessage, EntityState, NetworkMessage,
+    SvcClientData, SvcDeltaPacketEntities, SvcPacketEntities, SvcSpawnBaseline,
+};
+
+#[derive(Debug, Clone)]
+pub struct EntitySnapshot {
+    pub has_custom_delta: bool,
+    /// Every field received for the entity, baseline included.
+    pub state: Delta,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    pub entities: BTreeMap<u16, EntitySnapshot>,
+    pub client_data: Delta,
+    /// Weapon index to its `weapon_data_t`.
+    pub weapon_data: BTreeMap<u8, Delta>,
+}
+
+impl Snapshot {
+    /// Writes every entity as a full update from nothing.
+    ///
+    /// Fields not in the delta decoder of the entity are left out.
+    pub fn to_packet_entities(&self, state: &DemoState) -> SvcPacketEntities {
+        let entity_states = self
+            .entities
+            .iter()
+            .map(|(&entity_index, entity)| {
+                let decoder_name = if entity_index > 0 && entity_index <= state.max_client as u16 {
+                    "entity_state_player_t\0"
+                } else if entity.has_custom_delta {
+                    "custom_entity_state_t\0"
+                } else {
+                    "entity_state_t\0"
+                };
+
+                EntityState {
+                    entity_index,
+                    increment_entity_number: false,
+                    is_absolute_entity_index: Some(true),
+                    absolute_entity_index: Some(entity_index),
+                    entity_index_difference: None,
+                    has_custom_delta: entity.has_custom_delta,
+                    has_baseline_index: false,
+                    baseline_index: None,
+                    delta: filter_delta(&entity.state, state, decoder_name),
+                }
+            })
+            .collect();
+
+        SvcPacketEntities {
+            entity_count: self.entities.len() as u16,
+            entity_states,
+        }
+    }
+
+    /// Writes the client data and every weapon without a delta frame.
+    pub fn to_client_data(&self, state: &DemoState) -> SvcClientData {
+        let weapon_data: Vec<ClientDataWeaponData> = self
+            .weapon_data
+            .iter()
+            .map(|(&weapon_index, weapon_data)| ClientDataWeaponData {
+                weapon_index,
+                weapon_data: filter_delta(weapon_data, state, "weapon_data_t\0"),
+            })
+            .collect();
+
+        SvcClientData {
+            has_delta_update_mask: false,
+            delta_update_mask: None,
+            client_data: filter_delta(&self.client_data, state, "clientdata_t\0"),
+            weapon_data: (!weapon_data.is_empty()).then_some(weapon_data),
+        }
+    }
+}
+
+fn filter_delta(delta: &Delta, state: &DemoState, decoder_name: &str) -> Delta {
+    let Some(decoder) = state.delta_decoders.get(decoder_name) else {
+        return delta.clone();
+    };
+
+    delta
+        .iter()
+        .filter(|(key, _)| decoder.iter().any(|field| &field.name == *key))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+fn merge_delta(base: &mut Delta, delta: &Delta) {
+    base.extend(
+        delta
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone())),
+    );
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotTracker {
+    baselines: HashMap<u16, EntitySnapshot>,
+    instanced_baselines: Vec<Delta>,
+    /// Snapshot at the end of each frame by `incoming_sequence & 0xFF`.
+    frames: HashMap<u8, Snapshot>,
+    current: Snapshot,
+}
+
+impl SnapshotTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// State after the latest applied message.
+    pub fn current(&self) -> &Snapshot {
+        &self.current
+    }
+
+    /// State at the end of the frame with the sequence, if it is recent enough.
+    pub fn frame(&self, sequence: u8) -> Option<&Snapshot> {
+        self.frames.get(&sequence)
+    }
+
+    /// Applies every message of the frame and remembers the result under its incoming sequence.
+    pub fn apply_network_message(&mut self, network_message: &NetworkMessage) {
+        let Some(messages) = network_message.messages.get_parsed_messages() else {
+            return;
+        };
+
+        messages
+            .iter()
+            .filter_map(|message| message.get_engine_message())
+            .for_each(|message| self.apply(message));
+
+        self.end_frame(network_message.sequence_info.incoming_sequence);
+    }
+
+    /// Updates the current state with a message. Messages other than [`SvcSpawnBaseline`],
+    /// [`SvcPacketEntities`], [`SvcDeltaPacketEntities`] and [`SvcClientData`] are ignored.
+    ///
+    /// Call [`Self::end_frame`] after the last message of a frame.
+    pub fn apply(&mut self, message: &EngineMessage) {
+        match message {
+            EngineMessage::SvcSpawnBaseline(baseline) => self.apply_baseline(baseline),
+            EngineMessage::SvcPacketEntities(packet) => self.apply_packet_entities(packet),
+            EngineMessage::SvcDeltaPacketEntities(packet) => {
+                self.apply_delta_packet_entities(packet)
+            }
+            EngineMessage::SvcClientData(client_data) => self.apply_client_data(client_data),
+            _ => (),
+        }
+    }
+
+    /// Remembers the current state for later deltas referring to this sequence.
+    pub fn end_frame(&mut self, incoming_sequence: i32) {
+        self.frames
+            .insert((incoming_sequence & 0xFF) as u8, self.current.clone());
+    }
+
+    fn apply_baseline(&mut self, baseline: &SvcSpawnBaseline) {
+        for entity in &baseline.entities {
+            self.baselines.insert(
+                entity.index,
+                EntitySnapshot {
+                    has_custom_delta: entity.type_ & 1 == 0,
+                    state: entity.delta.clone(),
+                },
+            );
+        }
+
+        self.instanced_baselines = baseline.extra_data.clone();
+    }
+
+    fn baseline(&self, entity_index: u16) -> Delta {
+        self.baselines
+            .get(&entity_index)
+            .map(|baseline| baseline.state.clone())
+            .unwrap_or_default()
+    }
+
+    fn apply_packet_entities(&mut self, packet: &SvcPacketEntities) {
+        let mut entities = BTreeMap::new();
+
+        for entity in &packet.entity_states {
+            let mut state = match entity.baseline_index {
+                Some(index) if entity.has_baseline_index => self
+                    .instanced_baselines
+                    .get(index as usize)
+                    .cloned()
+                    .unwrap_or_default(),
+                _ => self.baseline(entity.entity_index),
+            };
+
+            merge_delta(&mut state, &entity.delta);
+
+            entities.insert(
+                entity.entity_index,
+                EntitySnapshot {
+                    has_custom_delta: entity.has_custom_delta,
+                    state,
+                },
+            );
+        }
+
+        self.current.entities = entities;
+    }
+
+    fn apply_delta_packet_entities(&mut self, packet: &SvcDeltaPacketEntities) {
+        // the client would ask for a full update here, the latest state is the best guess
+        let mut entities = self
+            .frames
+            .get(&packet.delta_sequence)
+            .map(|frame| frame.entities.clone())
+            .unwrap_or_else(|| self.current.entities.clone());
+
+        for entity in &packet.entity_states {
+            if entity.remove_entity {
+                entities.remove(&entity.entity_index);
+                continue;
+            }
+
+            let has_custom_delta = entity.has_custom_delta.unwrap_or_default();
+            let baseline = self.baseline(entity.entity_index);
+
+            let snapshot = entities
+                .entry(entity.entity_index)
+                .or_insert_with(|| EntitySnapshot {
+                    has_custom_delta,
+                    state: baseline,
+                });
+
+            snapshot.has_custom_delta = has_custom_delta;
+
+            if let Some(delta) = &entity.delta {
+                merge_delta(&mut snapshot.state, delta);
+            }
+        }
+
+        self.current.entities = entities;
+    }
+
+    fn apply_client_data(&mut self, client_data: &SvcClientData) {
+        // without a delta frame, the client data starts from nothing
+        let (mut client, mut weapons) = match client_data.delta_update_mask {
+            Some(sequence) if client_data.has_delta_update_mask => self
+                .frames
+                .get(&sequence)
+                .map(|frame| (frame.client_data.clone(), frame.weapon_data.clone()))
+                .unwrap_or_else(|| {
+                    (
+                        self.current.client_data.clone(),
+                        self.current.weapon_data.clone(),
+                    )
+                }),
+            _ => Default::default(),
+        };
+
+        merge_delta(&mut client, &client_data.client_data);
+
+        for weapon in client_data.weapon_data.iter().flatten() {
+            merge_delta(
+                weapons.entry(weapon.weapon_index).or_default(),
+                &weapon.weapon_data,
+            );
+        }
+
+        self.current.client_data = client;
+        self.current.weapon_data = weapons;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::types::{DeltaValue, EntityS, EntityStateDelta};
+
+    fn delta(fields: &[(&str, i32)]) -> Delta {
+        fields
+            .iter()
+            .map(|(key, value)| (key.to_string(), DeltaValue::IntSigned(*value)))
+            .collect()
+    }
+
+    fn update(entity_index: u16, remove_entity: bool, fields: &[(&str, i32)]) -> EntityStateDelta {
+        EntityStateDelta {
+            entity_index,
+            remove_entity,
+            is_absolute_entity_index: true,
+            absolute_entity_index: Some(entity_index),
+            entity_index_difference: None,
+            has_custom_delta: (!remove_entity).then_some(false),
+            delta: (!remove_entity).then(|| delta(fields)),
+        }
+    }
+
+    fn get(snapshot: &Snapshot, entity_index: u16, key: &str) -> Option<i32> {
+        match snapshot.entities.get(&entity_index)?.state.get(key)? {
+            DeltaValue::IntSigned(x) => Some(*x),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn delta_from_sequence() {
+        let mut tracker = SnapshotTracker::new();
+
+        tracker.apply(&EngineMessage::SvcSpawnBaseline(SvcSpawnBaseline {
+            entities: vec![EntityS {
+                entity_index: 5,
+                index: 5,
+                type_: 1,
+                delta: delta(&[("modelindex", 3), ("body", 1)]),
+            }],
+            total_extra_data: 0,
+            extra_data: vec![],
+        }));
+
+        tracker.apply(&EngineMessage::SvcDeltaPacketEntities(
+            SvcDeltaPacketEntities {
+                entity_count: 2,
+                delta_sequence: 0,
+                entity_states: vec![update(5, false, &[("body", 2)]), update(6, false, &[])],
+            },
+        ));
+        tracker.end_frame(10);
+
+        tracker.apply(&EngineMessage::SvcDeltaPacketEntities(
+            SvcDeltaPacketEntities {
+                entity_count: 1,
+                delta_sequence: 10,
+                entity_states: vec![update(6, true, &[])],
+            },
+        ));
+        tracker.end_frame(11);
+
+        // delta from frame 10 again, where entity 6 still exists
+        tracker.apply(&EngineMessage::SvcDeltaPacketEntities(
+            SvcDeltaPacketEntities {
+                entity_count: 2,
+                delta_sequence: 10,
+                entity_states: vec![update(5, false, &[("skin", 4)])],
+            },
+        ));
+
+        let current = tracker.current();
+
+        assert_eq!(get(current, 5, "modelindex"), Some(3));
+        assert_eq!(get(current, 5, "body"), Some(2));
+        assert_eq!(get(current, 5, "skin"), Some(4));
+        assert!(current.entities.contains_key(&6));
+        assert!(!tracker.frame(11).unwrap().entities.contains_key(&6));
+    }
+}
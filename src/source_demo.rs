@@ -0,0 +1,346 @@
+//! Player movement of a GoldSrc demo as a Source engine demo, for Source demo tools.
+//!
+//! The result is the smallest demo those tools read: the header, a signon with no server
+//! messages, and for every network frame a `dem_packet` with the view of the recording player
+//! followed by a `dem_usercmd`. There are no entities or string tables, so the game itself does
+//! not play it back.
+//!
+//! One tick is written for each network frame of the playback entries. The view comes from the
+//! latest [`ClientData`] frame when the demo has them, and from
+//! [`crate::types::RefParams`] otherwise. The layout is demo protocol 3, the one of the Orange
+//! Box engine.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let demo = open_demo("./src/tests/demotest.dem").unwrap();
+//! let bytes = demo.to_source_demo(&SourceDemoOptions::default());
+//!
+//! std::fs::write("./src/tests/demotest_source.dem", bytes).unwrap();
+//! ```
+use crate::{
+    bit::BitWriter,
+    byte_writer::ByteWriter,
+    types::{Buttons, ClientData, Demo, FrameData, UserCmd},
+};
+
+const SOURCE_MAGIC: &[u8; 8] = b"HL2DEMO\0";
+const SOURCE_DEMO_PROTOCOL: i32 = 3;
+/// Length of the strings in the header.
+const SOURCE_PATH_LENGTH: usize = 260;
+
+const DEM_SIGNON: u8 = 1;
+const DEM_PACKET: u8 = 2;
+const DEM_SYNCTICK: u8 = 3;
+const DEM_USERCMD: u8 = 5;
+const DEM_STOP: u8 = 7;
+
+/// `IN_SCORE` moved up a bit to make room for `IN_ALT2`.
+const SOURCE_IN_SCORE: u32 = 1 << 16;
+/// `MAX_EDICT_BITS`
+const WEAPON_SELECT_BITS: u32 = 11;
+
+#[derive(Debug, Clone)]
+pub struct SourceDemoOptions {
+    /// Network protocol in the header. Tools use it to pick the message layout.
+    pub network_protocol: i32,
+    pub server_name: String,
+}
+
+impl Default for SourceDemoOptions {
+    /// Protocol 24 of the Orange Box engine.
+    fn default() -> Self {
+        Self {
+            network_protocol: 24,
+            server_name: "localhost".to_owned(),
+        }
+    }
+}
+
+/// `democmdinfo_t` with only the first split screen player.
+struct CmdInfo {
+    view_origin: [f32; 3],
+    view_angles: [f32; 3],
+    local_view_angles: [f32; 3],
+}
+
+impl CmdInfo {
+    fn write(&self, writer: &mut ByteWriter) {
+        // FDEMO_NORMAL
+        writer.append_i32(0);
+        writer.append_f32_slice(&self.view_origin);
+        writer.append_f32_slice(&self.view_angles);
+        writer.append_f32_slice(&self.local_view_angles);
+        // the interpolated view is the same
+        writer.append_f32_slice(&self.view_origin);
+        writer.append_f32_slice(&self.view_angles);
+        writer.append_f32_slice(&self.local_view_angles);
+    }
+}
+
+impl Demo {
+    /// Writes the demo as a Source engine demo. See the [module docs](self) for what is kept.
+    pub fn to_source_demo(&self, options: &SourceDemoOptions) -> Vec<u8> {
+        let roster = self.player_roster();
+        let client_name = roster
+            .player_index
+            .and_then(|slot| roster.by_slot(slot))
+            .map(|player| player.name.clone())
+            .unwrap_or_default();
+
+        let mut body = ByteWriter::new();
+
+        // signon
+        write_command(&mut body, DEM_SIGNON, 0);
+        CmdInfo {
+            view_origin: [0.; 3],
+            view_angles: [0.; 3],
+            local_view_angles: [0.; 3],
+        }
+        .write(&mut body);
+        // sequence in, sequence out and no messages
+        body.append_i32_slice(&[0, 0, 0]);
+
+        let signon_length = body.data.len();
+
+        write_command(&mut body, DEM_SYNCTICK, 0);
+
+        let mut tick = 0;
+        let mut playback_time = 0.;
+        let mut client_data: Option<&ClientData> = None;
+
+        for entry in self
+            .directory
+            .entries
+            .iter()
+            .filter(|entry| !entry.is_loading())
+        {
+            let start_time = playback_time;
+
+            for frame in &entry.frames {
+                let box_type = match &frame.frame_data {
+                    FrameData::ClientData(new_client_data) => {
+                        client_data = Some(new_client_data);
+                        continue;
+                    }
+                    FrameData::NetworkMessage(box_type) => box_type,
+                    _ => continue,
+                };
+
+                let network_message = &box_type.1;
+                let refparams = &network_message.info.refparams;
+
+                let (view_origin, view_angles) = match client_data {
+                    Some(client_data) => (&client_data.origin, &client_data.viewangles),
+                    None => (&refparams.view_origin, &refparams.view_angles),
+                };
+
+                write_command(&mut body, DEM_PACKET, tick);
+                CmdInfo {
+                    view_origin: vec3(view_origin),
+                    view_angles: vec3(view_angles),
+                    local_view_angles: vec3(&refparams.cl_viewangles),
+                }
+                .write(&mut body);
+                body.append_i32(network_message.sequence_info.incoming_sequence);
+                body.append_i32(network_message.sequence_info.outgoing_sequence);
+                body.append_i32(0);
+
+                let user_cmd = write_user_cmd(&network_message.info.usercmd, tick + 1, tick);
+
+                write_command(&mut body, DEM_USERCMD, tick);
+                body.append_i32(tick + 1);
+                body.append_i32(user_cmd.len() as i32);
+                body.append_u8_slice(&user_cmd);
+
+                tick += 1;
+                playback_time = start_time + frame.time;
+            }
+        }
+
+        write_command(&mut body, DEM_STOP, tick);
+
+        let mut writer = ByteWriter::new();
+
+        writer.append_u8_slice(SOURCE_MAGIC);
+        writer.append_i32(SOURCE_DEMO_PROTOCOL);
+        writer.append_i32(options.network_protocol);
+        writer.append_u8_slice(&header_string(&options.server_name));
+        writer.append_u8_slice(&header_string(&client_name));
+        writer.append_u8_slice(&header_string(
+            self.header.map_name.to_str().unwrap_or_default(),
+        ));
+        writer.append_u8_slice(&header_string(
+            self.header.game_directory.to_str().unwrap_or_default(),
+        ));
+        writer.append_f32(playback_time);
+        writer.append_i32(tick);
+        writer.append_i32(tick);
+        writer.append_i32(signon_length as i32);
+        writer.append_u8_slice(&body.data);
+
+        writer.data
+    }
+}
+
+fn write_command(writer: &mut ByteWriter, command: u8, tick: i32) {
+    writer.append_u8(command);
+    writer.append_i32(tick);
+}
+
+fn header_string(value: &str) -> Vec<u8> {
+    let mut bytes = value.as_bytes().to_vec();
+
+    // keeps the null terminator
+    bytes.truncate(SOURCE_PATH_LENGTH - 1);
+    bytes.resize(SOURCE_PATH_LENGTH, 0);
+    bytes
+}
+
+/// `WriteUsercmd` against an empty command, which is how demos store them.
+fn write_user_cmd(user_cmd: &UserCmd, command_number: i32, tick_count: i32) -> Vec<u8> {
+    let mut writer = BitWriter::new();
+
+    // each field is sent if it differs from the empty command, and the counters if they do not
+    // follow it
+    let mut write_u32 = |value: u32, from: u32| {
+        writer.append_bit(value != from);
+
+        if value != from {
+            writer.append_u32(value);
+        }
+    };
+
+    write_u32(command_number as u32, 1);
+    write_u32(tick_count as u32, 1);
+
+    let view_angles = vec3(&user_cmd.view_angles);
+
+    [
+        view_angles[0],
+        view_angles[1],
+        view_angles[2],
+        user_cmd.forward_move,
+        user_cmd.side_move,
+        user_cmd.up_move,
+    ]
+    .iter()
+    .for_each(|value| write_u32(value.to_bits(), 0));
+
+    write_u32(source_buttons(user_cmd.button_flags()), 0);
+
+    writer.append_bit(user_cmd.impulse != 0);
+    if user_cmd.impulse != 0 {
+        writer.append_u8(user_cmd.impulse as u8);
+    }
+
+    writer.append_bit(user_cmd.weapon_select != 0);
+    if user_cmd.weapon_select != 0 {
+        writer.append_u32_nbit(user_cmd.weapon_select as u32, WEAPON_SELECT_BITS);
+        // no weapon subtype
+        writer.append_bit(false);
+    }
+
+    // no mouse movement
+    writer.append_bit(false);
+    writer.append_bit(false);
+
+    writer.get_u8_vec()
+}
+
+fn source_buttons(buttons: Buttons) -> u32 {
+    let score = if buttons.contains(Buttons::SCORE) {
+        SOURCE_IN_SCORE
+    } else {
+        0
+    };
+
+    (buttons - Buttons::SCORE).bits() as u32 | score
+}
+
+fn vec3(point: &[f32]) -> [f32; 3] {
+    [0, 1, 2].map(|index| point.get(index).copied().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{bit::BitReader, bit::BitSliceCast, open_demo};
+
+    #[test]
+    fn header() {
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let bytes = demo.to_source_demo(&SourceDemoOptions::default());
+
+        let i32_at =
+            |offset: usize| i32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+        let network_frames = demo
+            .directory
+            .entries
+            .iter()
+            .filter(|entry| !entry.is_loading())
+            .flat_map(|entry| &entry.frames)
+            .filter(|frame| matches!(frame.frame_data, FrameData::NetworkMessage(_)))
+            .count();
+
+        assert_eq!(&bytes[..8], SOURCE_MAGIC);
+        assert_eq!(i32_at(8), SOURCE_DEMO_PROTOCOL);
+        assert!(bytes[16 + 2 * SOURCE_PATH_LENGTH..].starts_with(b"crossfire\0"));
+        // ticks and frames
+        assert_eq!(i32_at(1060) as usize, network_frames);
+        assert_eq!(i32_at(1064) as usize, network_frames);
+        // signon with an empty cmdinfo and no data
+        assert_eq!(i32_at(1068), 5 + 76 + 12);
+        assert_eq!(bytes[1072], DEM_SIGNON);
+        assert_eq!(bytes[bytes.len() - 5], DEM_STOP);
+    }
+
+    #[test]
+    fn user_cmd() {
+        let mut user_cmd = open_demo("./src/tests/weapon.dem")
+            .unwrap()
+            .directory
+            .entries[1]
+            .frames
+            .iter()
+            .find_map(|frame| frame.frame_data.get_network_message())
+            .unwrap()
+            .1
+            .info
+            .usercmd
+            .clone();
+
+        user_cmd.view_angles = vec![0., 90., 0.];
+        user_cmd.forward_move = 400.;
+        user_cmd.side_move = 0.;
+        user_cmd.up_move = 0.;
+        user_cmd.buttons = (Buttons::FORWARD | Buttons::SCORE).bits();
+        user_cmd.impulse = 0;
+        user_cmd.weapon_select = 0;
+
+        let bytes = write_user_cmd(&user_cmd, 1, 0);
+        let mut reader = BitReader::new(&bytes);
+
+        // command number follows the empty command
+        assert!(!reader.read_1_bit());
+        // tick count
+        assert!(reader.read_1_bit());
+        assert_eq!(reader.read_n_bit(32).to_u32(), 0);
+        // pitch
+        assert!(!reader.read_1_bit());
+        // yaw
+        assert!(reader.read_1_bit());
+        assert_eq!(f32::from_bits(reader.read_n_bit(32).to_u32()), 90.);
+        // roll
+        assert!(!reader.read_1_bit());
+        // forward move
+        assert!(reader.read_1_bit());
+        assert_eq!(f32::from_bits(reader.read_n_bit(32).to_u32()), 400.);
+        // side and up move
+        assert!(!reader.read_1_bit());
+        assert!(!reader.read_1_bit());
+        // buttons
+        assert!(reader.read_1_bit());
+        assert_eq!(reader.read_n_bit(32).to_u32(), 8 | SOURCE_IN_SCORE);
+    }
+}
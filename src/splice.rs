@@ -0,0 +1,883 @@
+//! Inserting part of one demo into another demo on the same map.
+//!
+//! The two demos come from different connections, so user message ids, delta decoders, resource
+//! indices and entity baselines do not agree. The inserted frames are rewritten against the state
+//! of the demo they are inserted into.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let demo = open_demo("./src/tests/demotest.dem").unwrap();
+//! let other = open_demo("./src/tests/demo2test.dem").unwrap();
+//!
+//! // 10 seconds of the other demo, 30 seconds in
+//! let highlight = demo.splice(&other, 30., 50., 60.).unwrap();
+//!
+//! highlight.write_to_file("./src/tests/highlight.dem").unwrap();
+//! ```
+use std::{
+    collections::{HashMap, HashSet},
+    mem::discriminant,
+    ops::{Add, Sub},
+};
+
+use crate::{
+    cut::CarriedMessages,
+    delta::{delta_number, delta_value, entity_decoder},
+    error::DemoError,
+    snapshot::SnapshotTracker,
+    types::{
+        Delta, DeltaDecoder, Demo, DemoState, Directory, EngineMessage, Frame, FrameData,
+        MessageData, NetMessage, NetworkMessage, SvcResourceList, UserMessage,
+    },
+};
+
+/// `t_sound` in `resourcetype_t`.
+const RESOURCE_SOUND: u8 = 0;
+/// `t_model` in `resourcetype_t`.
+const RESOURCE_MODEL: u8 = 2;
+/// `t_eventscript` in `resourcetype_t`.
+const RESOURCE_EVENT: u8 = 5;
+/// `SND_LARGE_INDEX`
+const SOUND_LARGE_INDEX: u16 = 1 << 2;
+/// `SND_SENTENCE`, the sound index is a sentence number and not a resource.
+const SOUND_SENTENCE: u16 = 1 << 4;
+/// Delta fields holding a model index.
+const MODEL_FIELDS: &[&str] = &["modelindex\0", "weaponmodel\0", "viewmodel\0"];
+
+impl Demo {
+    /// Inserts the frames of `other` between `start` and `end` seconds into the playback entry at
+    /// `at` seconds of [`Frame::time`]. Frames after `at` are pushed back by the length of the
+    /// inserted part.
+    ///
+    /// The inserted part is cut with [`Demo::cut`], then rewritten against the state of this
+    /// demo:
+    /// - user messages get the id this demo registered under the same name, and are dropped if it
+    ///   did not register them or registered them with another size
+    /// - models, sounds and events get the index of the resource with the same name
+    /// - deltas are written with the delta decoders of this demo and fields it does not know are
+    ///   dropped
+    /// - entities appearing in the inserted part carry every field, so they do not depend on the
+    ///   baselines of `other`
+    /// - registrations, baselines and other connection messages are dropped
+    ///
+    /// The first frame after the inserted part sends the entities, client data and reliable state
+    /// of this demo again. Temporary entities keep their model indices.
+    ///
+    /// Both demos need the same protocols, game directory and map.
+    pub fn splice(&self, other: &Demo, at: f32, start: f32, end: f32) -> Result<Demo, DemoError> {
+        let is_compatible = self.header.demo_protocol == other.header.demo_protocol
+            && self.header.network_protocol == other.header.network_protocol
+            && self.header.game_directory.to_str() == other.header.game_directory.to_str()
+            && self.header.map_name.to_str() == other.header.map_name.to_str();
+
+        if !is_compatible {
+            return Err(DemoError::IncompatibleDemos);
+        }
+
+        let (loading, playback) = match self.directory.entries.as_slice() {
+            [loading, playback, ..] => (loading, playback),
+            _ => return Err(DemoError::MissingDirectoryEntry),
+        };
+
+        let clip = other.cut(start, end)?;
+        let (clip_loading, clip_playback) =
+            (&clip.directory.entries[0], &clip.directory.entries[1]);
+
+        let mut state = DemoState::new_raw();
+        let mut tracker = SnapshotTracker::new();
+        let mut carried = CarriedMessages::default();
+        let mut resources: Vec<SvcResourceList> = vec![];
+
+        let split = playback
+            .frames
+            .iter()
+            .position(|frame| {
+                matches!(frame.frame_data, FrameData::NextSection)
+                    || frame.time >= at && !matches!(frame.frame_data, FrameData::DemoStart)
+            })
+            .unwrap_or(playback.frames.len());
+
+        let mut frames: Vec<Frame> = playback.frames[..split].to_vec();
+        let mut server_time = 0.;
+        let mut before = None;
+
+        let frames_before = loading
+            .frames
+            .iter()
+            .map(|frame| (true, frame))
+            .chain(frames.iter().map(|frame| (false, frame)));
+
+        for (is_loading, frame) in frames_before {
+            let FrameData::NetworkMessage(box_type) = &frame.frame_data else {
+                continue;
+            };
+
+            let network_message = &box_type.1;
+
+            for message in network_message
+                .messages
+                .get_parsed_messages()
+                .into_iter()
+                .flatten()
+            {
+                state.apply(message);
+
+                if let Some(message) = message.get_engine_message() {
+                    carried.push(message);
+
+                    if let EngineMessage::SvcResourceList(resource_list) = message {
+                        resources.push(resource_list.clone());
+                    }
+                }
+            }
+
+            tracker.apply_network_message(network_message);
+
+            let clocks = Clocks::read(frame, server_time);
+
+            if !is_loading {
+                before = Some(clocks);
+            }
+
+            server_time = clocks.server_time;
+        }
+
+        // state of the other demo, for the user message names and entity baselines
+        let mut clip_state = DemoState::new_raw();
+        let mut clip_tracker = SnapshotTracker::new();
+        let mut remap = Remap::default();
+
+        for frame in &clip_loading.frames {
+            let FrameData::NetworkMessage(box_type) = &frame.frame_data else {
+                continue;
+            };
+
+            for message in box_type
+                .1
+                .messages
+                .get_parsed_messages()
+                .into_iter()
+                .flatten()
+            {
+                clip_state.apply(message);
+
+                if let Some(EngineMessage::SvcResourceList(resource_list)) =
+                    message.get_engine_message()
+                {
+                    remap.add_resources(resource_list, &resources);
+                }
+            }
+
+            clip_tracker.apply_network_message(&box_type.1);
+        }
+
+        let next = playback.frames[split..]
+            .iter()
+            .find(|frame| matches!(frame.frame_data, FrameData::NetworkMessage(_)))
+            .map(|frame| Clocks::read(frame, server_time));
+
+        // the gap between the frames at the splice is kept on both sides of the inserted part
+        let (before, step) = match (before, next) {
+            (Some(before), Some(next)) => (before, next - before),
+            (Some(before), None) => (before, Clocks::default()),
+            (None, Some(next)) => (next, Clocks::default()),
+            (None, None) => (Clocks::default(), Clocks::default()),
+        };
+
+        let (clip_start, clip_end) = clip_span(&clip_playback.frames);
+        let clip_shift = before + step - clip_start;
+        let rest_shift = clip_end - clip_start + step;
+
+        for frame in &clip_playback.frames {
+            let mut frame = frame.clone();
+
+            match &mut frame.frame_data {
+                FrameData::DemoStart | FrameData::NextSection => continue,
+                FrameData::NetworkMessage(box_type) => {
+                    let network_message = &mut box_type.1;
+                    let messages = network_message
+                        .messages
+                        .get_parsed_messages()
+                        .ok_or(DemoError::UnparsedMessages)?;
+
+                    let mut new_messages = vec![];
+
+                    for message in messages {
+                        new_messages.extend(remap.message(
+                            message,
+                            &state,
+                            &clip_state,
+                            &mut clip_tracker,
+                        ));
+
+                        clip_state.apply(message);
+                    }
+
+                    clip_tracker.end_frame(network_message.sequence_info.incoming_sequence);
+
+                    network_message.info.viewmodel = remap
+                        .resource(RESOURCE_MODEL, network_message.info.viewmodel as u16)
+                        as i32;
+                    network_message.messages = MessageData::Parsed(new_messages);
+                }
+                FrameData::Event(event) => {
+                    event.index = remap.resource(RESOURCE_EVENT, event.index as u16) as i32;
+                }
+                _ => (),
+            }
+
+            clip_shift.shift(&mut frame);
+            frames.push(frame);
+        }
+
+        // deltas after the inserted part can only refer to frames sent after it
+        let mut kept_sequences: HashSet<u8> = HashSet::new();
+        let mut is_resumed = false;
+
+        for frame in &playback.frames[split..] {
+            let mut frame = frame.clone();
+
+            if let FrameData::NetworkMessage(box_type) = &mut frame.frame_data {
+                let network_message = &mut box_type.1;
+                let messages = network_message
+                    .messages
+                    .get_parsed_messages()
+                    .ok_or(DemoError::UnparsedMessages)?;
+
+                let mut new_messages: Vec<NetMessage> = vec![];
+
+                if !is_resumed {
+                    is_resumed = true;
+                    new_messages.extend(carried.take().into_iter().filter(|message| {
+                        !message
+                            .get_engine_message()
+                            .is_some_and(is_connection_message)
+                    }));
+                }
+
+                for message in messages {
+                    state.apply(message);
+
+                    let Some(engine_message) = message.get_engine_message() else {
+                        new_messages.push(message.clone());
+                        continue;
+                    };
+
+                    tracker.apply(engine_message);
+
+                    let full = match engine_message {
+                        EngineMessage::SvcDeltaPacketEntities(packet)
+                            if !kept_sequences.contains(&packet.delta_sequence) =>
+                        {
+                            EngineMessage::SvcPacketEntities(
+                                tracker.current().to_packet_entities(&state),
+                            )
+                        }
+                        EngineMessage::SvcClientData(client_data)
+                            if client_data
+                                .delta_update_mask
+                                .is_none_or(|sequence| !kept_sequences.contains(&sequence)) =>
+                        {
+                            EngineMessage::SvcClientData(tracker.current().to_client_data(&state))
+                        }
+                        _ => {
+                            new_messages.push(message.clone());
+                            continue;
+                        }
+                    };
+
+                    new_messages.push(NetMessage::EngineMessage(Box::new(full)));
+                }
+
+                tracker.end_frame(network_message.sequence_info.incoming_sequence);
+                kept_sequences
+                    .insert((network_message.sequence_info.incoming_sequence & 0xFF) as u8);
+
+                network_message.messages = MessageData::Parsed(new_messages);
+            }
+
+            rest_shift.shift(&mut frame);
+            frames.push(frame);
+        }
+
+        let mut playback = playback.clone();
+        playback.track_time = frames.last().map(|frame| frame.time).unwrap_or_default();
+        playback.frame_count = frames.len() as i32;
+        playback.frames = frames;
+
+        Ok(Demo {
+            header: self.header.clone(),
+            directory: Directory {
+                entries: vec![loading.clone(), playback],
+            },
+            _state: None,
+        })
+    }
+}
+
+/// Messages setting up the connection, which the demo being inserted into already has.
+fn is_connection_message(message: &EngineMessage) -> bool {
+    matches!(
+        message,
+        EngineMessage::SvcServerInfo(_)
+            | EngineMessage::SvcSpawnStatic(_)
+            | EngineMessage::SvcSpawnBaseline(_)
+            | EngineMessage::SvcSignOnNum(_)
+            | EngineMessage::SvcSpawnStaticSound(_)
+            | EngineMessage::SvcNewUserMsg(_)
+            | EngineMessage::SvcResourceList(_)
+            | EngineMessage::SvcNewMovevars(_)
+            | EngineMessage::SvcResourceRequest(_)
+            | EngineMessage::SvcVoiceInit(_)
+            | EngineMessage::SvcSendExtraInfo(_)
+            | EngineMessage::SvcResourceLocation(_)
+            | EngineMessage::SvcDeltaDescription(_)
+    )
+}
+
+/// Resource indices of the inserted demo to those of the demo it is inserted into.
+#[derive(Debug, Default)]
+struct Remap {
+    resources: HashMap<(u8, u16), u16>,
+}
+
+impl Remap {
+    fn add_resources(&mut self, resource_list: &SvcResourceList, targets: &[SvcResourceList]) {
+        for resource in &resource_list.resources {
+            let target = targets
+                .iter()
+                .flat_map(|target| &target.resources)
+                .find(|target| target.type_ == resource.type_ && target.name == resource.name);
+
+            if let Some(target) = target {
+                self.resources
+                    .insert((resource.type_, resource.index), target.index);
+            }
+        }
+    }
+
+    /// Index in the demo inserted into. Resources it does not have keep their index.
+    fn resource(&self, type_: u8, index: u16) -> u16 {
+        self.resources
+            .get(&(type_, index))
+            .copied()
+            .unwrap_or(index)
+    }
+
+    /// Keeps the fields in `decoder` with the type it expects and remaps model indices.
+    fn delta(&self, delta: &Delta, decoder: Option<&DeltaDecoder>) -> Delta {
+        let Some(decoder) = decoder else {
+            return Delta::new();
+        };
+
+        decoder
+            .iter()
+            .filter_map(|description| {
+                let value = delta.get(&description.name)?;
+
+                let value = match delta_number(value) {
+                    Some(number) if MODEL_FIELDS.contains(&description.name.as_str()) => {
+                        let index = self.resource(RESOURCE_MODEL, number as u16);
+                        delta_value(description, index as f32)
+                    }
+                    Some(number)
+                        if discriminant(value) != discriminant(&delta_value(description, 0.)) =>
+                    {
+                        delta_value(description, number)
+                    }
+                    _ => value.clone(),
+                };
+
+                Some((description.name.clone(), value))
+            })
+            .collect()
+    }
+
+    /// Rewrites a message of the inserted demo. `from` and `tracker` are the state of the inserted
+    /// demo before the message, `to` is the state of the demo inserted into.
+    fn message(
+        &self,
+        message: &NetMessage,
+        to: &DemoState,
+        from: &DemoState,
+        tracker: &mut SnapshotTracker,
+    ) -> Option<NetMessage> {
+        let engine_message = match message {
+            NetMessage::UserMessage(user_message) => {
+                return user_message_to(user_message, to, from).map(NetMessage::UserMessage);
+            }
+            NetMessage::EngineMessage(engine_message) => engine_message.as_ref(),
+        };
+
+        if is_connection_message(engine_message) {
+            return None;
+        }
+
+        // entities the frame refers to, so the new ones can be told apart
+        let previous: Option<HashSet<u16>> = match engine_message {
+            EngineMessage::SvcDeltaPacketEntities(packet) => tracker
+                .frame(packet.delta_sequence)
+                .map(|snapshot| snapshot.entities.keys().copied().collect()),
+            _ => None,
+        };
+
+        tracker.apply(engine_message);
+
+        let mut engine_message = engine_message.clone();
+
+        match &mut engine_message {
+            EngineMessage::SvcPacketEntities(packet) => {
+                *packet = tracker.current().to_packet_entities(from);
+
+                for entity in &mut packet.entity_states {
+                    let decoder = entity_decoder(to, entity.entity_index, entity.has_custom_delta);
+                    entity.delta = self.delta(&entity.delta, decoder);
+                }
+            }
+            EngineMessage::SvcDeltaPacketEntities(packet) => {
+                for entity in &mut packet.entity_states {
+                    let Some(delta) = &mut entity.delta else {
+                        continue;
+                    };
+
+                    if !previous
+                        .as_ref()
+                        .is_some_and(|previous| previous.contains(&entity.entity_index))
+                        && let Some(snapshot) = tracker.current().entities.get(&entity.entity_index)
+                    {
+                        *delta = snapshot.state.clone();
+                    }
+
+                    let decoder = entity_decoder(
+                        to,
+                        entity.entity_index,
+                        entity.has_custom_delta.unwrap_or_default(),
+                    );
+                    *delta = self.delta(delta, decoder);
+                }
+            }
+            EngineMessage::SvcClientData(client_data) => {
+                client_data.client_data = self.delta(
+                    &client_data.client_data,
+                    to.delta_decoders.get("clientdata_t\0"),
+                );
+
+                for weapon in client_data.weapon_data.iter_mut().flatten() {
+                    weapon.weapon_data = self.delta(
+                        &weapon.weapon_data,
+                        to.delta_decoders.get("weapon_data_t\0"),
+                    );
+                }
+            }
+            EngineMessage::SvcSound(sound) if sound.flags & SOUND_SENTENCE == 0 => {
+                let index = sound
+                    .sound_index_long
+                    .or(sound.sound_index_short.map(u16::from))
+                    .unwrap_or_default();
+                let index = self.resource(RESOURCE_SOUND, index);
+
+                if index > u8::MAX as u16 {
+                    sound.flags |= SOUND_LARGE_INDEX;
+                    sound.sound_index_long = Some(index);
+                    sound.sound_index_short = None;
+                } else {
+                    sound.flags &= !SOUND_LARGE_INDEX;
+                    sound.sound_index_long = None;
+                    sound.sound_index_short = Some(index as u8);
+                }
+            }
+            EngineMessage::SvcEvent(event) => {
+                for event in &mut event.events {
+                    event.event_index = self.resource(RESOURCE_EVENT, event.event_index);
+
+                    if let Some(delta) = &mut event.delta {
+                        *delta = self.delta(delta, to.delta_decoders.get("event_t\0"));
+                    }
+                }
+            }
+            EngineMessage::SvcEventReliable(event) => {
+                event.event_index = self.resource(RESOURCE_EVENT, event.event_index);
+                event.event_args =
+                    self.delta(&event.event_args, to.delta_decoders.get("event_t\0"));
+            }
+            _ => (),
+        }
+
+        Some(NetMessage::EngineMessage(Box::new(engine_message)))
+    }
+}
+
+/// The user message with the id `to` registered for its name in `from`.
+fn user_message_to(
+    user_message: &UserMessage,
+    to: &DemoState,
+    from: &DemoState,
+) -> Option<UserMessage> {
+    let registered = from.custom_messages.get(&user_message.id)?;
+    let target = to.find_user_message(registered.name.to_str().ok()?)?;
+
+    if target.size != registered.size {
+        return None;
+    }
+
+    Some(UserMessage {
+        id: target.index,
+        name: target.name.clone(),
+        data: user_message.data.clone(),
+    })
+}
+
+/// Every clock of a frame that has to keep going across the splice.
+#[derive(Debug, Clone, Copy, Default)]
+struct Clocks {
+    time: f32,
+    frame: i32,
+    refparams_time: f32,
+    timestamp: f32,
+    /// [`crate::types::SvcTime`]
+    server_time: f32,
+}
+
+impl Clocks {
+    /// Clocks of a network frame. `server_time` is used when the frame has no
+    /// [`crate::types::SvcTime`].
+    fn read(frame: &Frame, server_time: f32) -> Self {
+        let FrameData::NetworkMessage(box_type) = &frame.frame_data else {
+            return Self {
+                time: frame.time,
+                frame: frame.frame,
+                server_time,
+                ..Default::default()
+            };
+        };
+
+        let network_message = &box_type.1;
+
+        Self {
+            time: frame.time,
+            frame: frame.frame,
+            refparams_time: network_message.info.refparams.time,
+            timestamp: network_message.info.timestamp,
+            server_time: svc_time(network_message).unwrap_or(server_time),
+        }
+    }
+
+    fn shift(&self, frame: &mut Frame) {
+        frame.time += self.time;
+        frame.frame += self.frame;
+
+        let FrameData::NetworkMessage(box_type) = &mut frame.frame_data else {
+            return;
+        };
+
+        let network_message = &mut box_type.1;
+
+        network_message.info.refparams.time += self.refparams_time;
+        network_message.info.timestamp += self.timestamp;
+
+        for message in network_message
+            .messages
+            .get_parsed_messages_mut()
+            .into_iter()
+            .flatten()
+        {
+            if let Some(EngineMessage::SvcTime(time)) = message.get_engine_message_mut() {
+                time.time += self.server_time;
+            }
+        }
+    }
+}
+
+impl Add for Clocks {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            time: self.time + rhs.time,
+            frame: self.frame + rhs.frame,
+            refparams_time: self.refparams_time + rhs.refparams_time,
+            timestamp: self.timestamp + rhs.timestamp,
+            server_time: self.server_time + rhs.server_time,
+        }
+    }
+}
+
+impl Sub for Clocks {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            time: self.time - rhs.time,
+            frame: self.frame - rhs.frame,
+            refparams_time: self.refparams_time - rhs.refparams_time,
+            timestamp: self.timestamp - rhs.timestamp,
+            server_time: self.server_time - rhs.server_time,
+        }
+    }
+}
+
+fn svc_time(network_message: &NetworkMessage) -> Option<f32> {
+    network_message
+        .messages
+        .get_parsed_messages()?
+        .iter()
+        .find_map(|message| match message.get_engine_message() {
+            Some(EngineMessage::SvcTime(time)) => Some(time.time),
+            _ => None,
+        })
+}
+
+/// Clocks of the first and last network frame.
+fn clip_span(frames: &[Frame]) -> (Clocks, Clocks) {
+    let mut span: Option<(Clocks, Clocks)> = None;
+
+    for frame in frames {
+        if !matches!(frame.frame_data, FrameData::NetworkMessage(_)) {
+            continue;
+        }
+
+        span = Some(match span {
+            Some((first, last)) => (first, Clocks::read(frame, last.server_time)),
+            None => {
+                let server_time = frames
+                    .iter()
+                    .filter_map(|frame| frame.frame_data.get_network_message())
+                    .find_map(|box_type| svc_time(&box_type.1))
+                    .unwrap_or_default();
+                let first = Clocks::read(frame, server_time);
+
+                (first, first)
+            }
+        });
+    }
+
+    span.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{open_demo, open_demo_from_bytes};
+
+    #[test]
+    fn splice_into_itself() {
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let original = &demo.directory.entries[1];
+        let original_end = original.frames.last().unwrap().time;
+
+        let spliced = demo.splice(&demo, 3., 2., 5.).unwrap();
+        let spliced = open_demo_from_bytes(&spliced.write_to_bytes()).unwrap();
+        let playback = &spliced.directory.entries[1];
+
+        assert!(playback.frames.len() > original.frames.len());
+        assert!(playback.frames.last().unwrap().time > original_end + 2.);
+        assert!(
+            playback
+                .frames
+                .windows(2)
+                .all(|pair| pair[0].time <= pair[1].time)
+        );
+
+        let server_times: Vec<f32> = playback
+            .frames
+            .iter()
+            .filter_map(|frame| frame.frame_data.get_network_message())
+            .filter_map(|box_type| svc_time(&box_type.1))
+            .collect();
+
+        assert!(
+            server_times
+                .windows(2)
+                .all(|pair| pair[0] <= pair[1] + 0.001)
+        );
+    }
+
+    /// Engine messages that are not dropped when inserted, and user messages.
+    fn inserted_messages(frame: &Frame) -> Vec<&NetMessage> {
+        frame
+            .frame_data
+            .get_network_message()
+            .and_then(|box_type| box_type.1.messages.get_parsed_messages())
+            .into_iter()
+            .flatten()
+            .filter(|message| {
+                !message
+                    .get_engine_message()
+                    .is_some_and(is_connection_message)
+            })
+            .collect()
+    }
+
+    fn sound_index(sound: &crate::types::SvcSound) -> u16 {
+        sound
+            .sound_index_long
+            .or(sound.sound_index_short.map(u16::from))
+            .unwrap()
+    }
+
+    #[test]
+    fn splice_remapped() {
+        let other = open_demo("./src/tests/weapon.dem").unwrap();
+        let clip = other.cut(2., 5.).unwrap();
+
+        // a client data field the inserted part has, which the demo inserted into does not know
+        let dropped_field = clip.directory.entries[1]
+            .frames
+            .iter()
+            .flat_map(inserted_messages)
+            .find_map(|message| match message.get_engine_message() {
+                Some(EngineMessage::SvcClientData(client_data)) => {
+                    client_data.client_data.keys().next().cloned()
+                }
+                _ => None,
+            })
+            .unwrap();
+
+        // same recording, registered under other user message ids and resource indices
+        let mut demo = other.clone();
+
+        for message in demo.directory.entries[0]
+            .frames
+            .iter_mut()
+            .filter_map(|frame| frame.frame_data.get_network_message_mut())
+            .filter_map(|box_type| box_type.1.messages.get_parsed_messages_mut())
+            .flatten()
+        {
+            match message.get_engine_message_mut() {
+                Some(EngineMessage::SvcNewUserMsg(user_message)) => {
+                    user_message.index = (319 - user_message.index as u16) as u8;
+                }
+                Some(EngineMessage::SvcResourceList(resource_list)) => {
+                    for resource in &mut resource_list.resources {
+                        resource.index += 100;
+                    }
+                }
+                Some(EngineMessage::SvcDeltaDescription(description))
+                    if description.name == b"clientdata_t\0" =>
+                {
+                    description
+                        .fields
+                        .retain(|field| field.name != dropped_field);
+                }
+                _ => (),
+            }
+        }
+
+        let split = demo.directory.entries[1]
+            .frames
+            .iter()
+            .position(|frame| frame.time >= 3. && !matches!(frame.frame_data, FrameData::DemoStart))
+            .unwrap();
+        let before = &demo.directory.entries[1].frames[split - 1];
+
+        let spliced = demo.splice(&other, 3., 2., 5.).unwrap();
+
+        let clip_frames: Vec<&Frame> = clip.directory.entries[1]
+            .frames
+            .iter()
+            .filter(|frame| {
+                !matches!(
+                    frame.frame_data,
+                    FrameData::DemoStart | FrameData::NextSection
+                )
+            })
+            .collect();
+        let inserted = &spliced.directory.entries[1].frames[split..split + clip_frames.len()];
+
+        let (mut user_messages, mut sounds, mut events, mut client_data) = (0, 0, 0, 0);
+        let mut server_shift = None;
+
+        for (frame, clip_frame) in inserted.iter().zip(&clip_frames) {
+            // one shift for every frame, starting after the frame before the splice
+            assert!(
+                (frame.time - clip_frame.time - (inserted[0].time - clip_frames[0].time)).abs()
+                    < 0.001
+            );
+            assert_eq!(
+                frame.frame - clip_frame.frame,
+                inserted[0].frame - clip_frames[0].frame
+            );
+
+            if let (FrameData::Event(event), FrameData::Event(clip_event)) =
+                (&frame.frame_data, &clip_frame.frame_data)
+            {
+                assert_eq!(event.index, clip_event.index + 100);
+                events += 1;
+            }
+
+            let messages = inserted_messages(frame);
+            let clip_messages = inserted_messages(clip_frame);
+
+            assert_eq!(messages.len(), clip_messages.len());
+
+            for (message, clip_message) in messages.into_iter().zip(clip_messages) {
+                match (message, clip_message) {
+                    (NetMessage::UserMessage(message), NetMessage::UserMessage(clip_message)) => {
+                        assert_eq!(message.id as u16, 319 - clip_message.id as u16);
+                        assert_eq!(message.data, clip_message.data);
+                        user_messages += 1;
+                    }
+                    (
+                        NetMessage::EngineMessage(message),
+                        NetMessage::EngineMessage(clip_message),
+                    ) => match (message.as_ref(), clip_message.as_ref()) {
+                        (EngineMessage::SvcSound(sound), EngineMessage::SvcSound(clip_sound))
+                            if sound.flags & SOUND_SENTENCE == 0 =>
+                        {
+                            assert_eq!(sound_index(sound), sound_index(clip_sound) + 100);
+                            sounds += 1;
+                        }
+                        (EngineMessage::SvcClientData(data), EngineMessage::SvcClientData(_)) => {
+                            assert!(!data.client_data.contains_key(&dropped_field));
+                            client_data += 1;
+                        }
+                        (EngineMessage::SvcTime(time), EngineMessage::SvcTime(clip_time)) => {
+                            let shift = *server_shift.get_or_insert(time.time - clip_time.time);
+
+                            assert!((time.time - clip_time.time - shift).abs() < 0.001);
+                        }
+                        (message, clip_message) => {
+                            assert_eq!(discriminant(message), discriminant(clip_message));
+                        }
+                    },
+                    _ => panic!("message kinds differ"),
+                }
+            }
+        }
+
+        assert!(inserted[0].time > before.time);
+        assert!(server_shift.is_some_and(|shift| shift > 0.));
+        assert!(user_messages > 0 && sounds > 0 && events > 0 && client_data > 0);
+    }
+
+    #[test]
+    fn different_maps() {
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let mut other = demo.clone();
+
+        other.retarget_map("crossfire_b2", 0);
+
+        assert!(matches!(
+            demo.splice(&other, 3., 2., 5.),
+            Err(DemoError::IncompatibleDemos)
+        ));
+    }
+
+    #[test]
+    fn missing_playback() {
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let mut short = demo.clone();
+
+        short.directory.entries.truncate(1);
+
+        assert!(matches!(
+            short.splice(&demo, 3., 2., 5.),
+            Err(DemoError::MissingDirectoryEntry)
+        ));
+        assert!(matches!(
+            demo.splice(&short, 3., 2., 5.),
+            Err(DemoError::MissingDirectoryEntry)
+        ));
+    }
+}
@@ -0,0 +1,572 @@
+//! Chronological kill feed and round events.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let demo = open_demo("./src/tests/demotest.dem").unwrap();
+//!
+//! for event in demo.timeline() {
+//!     if let TimelineEventKind::Kill { killer, victim, weapon, .. } = event.kind {
+//!         println!("{} {:?} killed {:?} with {}", event.time, killer, victim, weapon);
+//!     }
+//! }
+//! ```
+use std::collections::HashMap;
+
+use crate::{
+    roster::PlayerRoster,
+    types::{Demo, EngineMessage, FrameData, NetMessage, UserMessage},
+    usermsg_doer::{
+        cstrike::{self, RoundTime, SendAudio},
+        valve::{self, TextMsg},
+    },
+};
+
+/// Round and bomb events from the text and the radio are usually sent together.
+/// Same events within this many seconds are counted once.
+const DEDUPE_WINDOW: f32 = 1.;
+
+#[derive(Debug, Clone)]
+pub struct TimelineEvent {
+    /// [`crate::types::Frame::time`] of the frame containing the message.
+    pub time: f32,
+    pub entry_index: usize,
+    pub frame_index: usize,
+    pub kind: TimelineEventKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimelineEventKind {
+    Kill {
+        /// None if the victim is killed by the world.
+        killer: Option<TimelinePlayer>,
+        victim: TimelinePlayer,
+        weapon: String,
+        headshot: bool,
+    },
+    RoundStart {
+        /// Seconds on the round timer.
+        round_time: i16,
+    },
+    RoundEnd {
+        /// None if the round is a draw or the winner cannot be told.
+        winner: Option<Team>,
+        /// Localisation token or text that ended the round.
+        reason: String,
+    },
+    BombPlanted,
+    BombDefused,
+    GameRestart,
+    TeamScore {
+        team: String,
+        score: i16,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimelinePlayer {
+    pub entity_index: u8,
+    /// Name from the latest [`crate::types::SvcUpdateUserInfo`] of the player.
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Team {
+    Terrorist,
+    CounterTerrorist,
+}
+
+impl Demo {
+    /// Collects kills, round starts and ends, bomb events and team score changes in the order
+    /// they happen.
+    ///
+    /// Only network messages parsed with [`crate::types::MessageDataParseMode::Parse`] are read.
+    pub fn timeline(&self) -> Vec<TimelineEvent> {
+        let game_dir = self.header.game_directory.to_str().unwrap_or_default();
+        let is_cstrike = matches!(game_dir, "cstrike" | "czero");
+
+        let mut builder = TimelineBuilder::new(is_cstrike);
+
+        for (entry_index, entry) in self.directory.entries.iter().enumerate() {
+            for (frame_index, frame) in entry.frames.iter().enumerate() {
+                let FrameData::NetworkMessage(box_type) = &frame.frame_data else {
+                    continue;
+                };
+
+                let Some(messages) = box_type.1.messages.get_parsed_messages() else {
+                    continue;
+                };
+
+                builder.time = frame.time;
+                builder.entry_index = entry_index;
+                builder.frame_index = frame_index;
+
+                for message in messages {
+                    builder.handle_message(message);
+                }
+            }
+        }
+
+        builder.events
+    }
+}
+
+struct TimelineBuilder {
+    is_cstrike: bool,
+    events: Vec<TimelineEvent>,
+    roster: PlayerRoster,
+    team_scores: HashMap<String, i16>,
+    is_round_active: bool,
+    time: f32,
+    entry_index: usize,
+    frame_index: usize,
+}
+
+impl TimelineBuilder {
+    fn new(is_cstrike: bool) -> Self {
+        Self {
+            is_cstrike,
+            events: vec![],
+            roster: PlayerRoster::new(),
+            team_scores: HashMap::new(),
+            is_round_active: false,
+            time: 0.,
+            entry_index: 0,
+            frame_index: 0,
+        }
+    }
+
+    fn handle_message(&mut self, message: &NetMessage) {
+        match message {
+            NetMessage::UserMessage(message) => self.handle_user_message(message),
+            NetMessage::EngineMessage(message) => match message.as_ref() {
+                EngineMessage::SvcUpdateUserInfo(_) => {
+                    self.roster
+                        .apply(message, self.time, self.entry_index, self.frame_index)
+                }
+                EngineMessage::SvcPrint(print) => {
+                    let text = String::from_utf8_lossy(print.message.as_slice());
+
+                    for line in text.lines() {
+                        self.handle_text(
+                            line.trim_matches(|c: char| c == '\0' || c.is_whitespace()),
+                        );
+                    }
+                }
+                _ => (),
+            },
+        }
+    }
+
+    fn handle_user_message(&mut self, message: &UserMessage) {
+        match message.name_str() {
+            "DeathMsg" => {
+                let kill = if self.is_cstrike {
+                    message
+                        .decode::<cstrike::DeathMsg>()
+                        .map(|msg| (msg.killer, msg.victim, msg.weapon, msg.headshot != 0))
+                } else {
+                    message
+                        .decode::<valve::DeathMsg>()
+                        .map(|msg| (msg.killer, msg.victim, msg.weapon, false))
+                };
+
+                if let Some((killer, victim, weapon, headshot)) = kill {
+                    let kind = TimelineEventKind::Kill {
+                        killer: (killer != 0).then(|| self.player(killer)),
+                        victim: self.player(victim),
+                        weapon: weapon.to_str().unwrap_or_default().to_owned(),
+                        headshot,
+                    };
+
+                    self.push(kind);
+                }
+            }
+            "TextMsg" => {
+                if let Some(msg) = message.decode::<TextMsg>() {
+                    let text = String::from_utf8_lossy(msg.message.as_slice());
+                    self.handle_text(text.trim_end_matches('\0'));
+                }
+            }
+            "SendAudio" => {
+                if let Some(msg) = message.decode::<SendAudio>() {
+                    let code = msg.code.to_str().unwrap_or_default();
+
+                    let kind = match code {
+                        "%!MRAD_BOMBPL" => TimelineEventKind::BombPlanted,
+                        "%!MRAD_BOMBDEF" => TimelineEventKind::BombDefused,
+                        "%!MRAD_terwin" => round_end(Some(Team::Terrorist), code),
+                        "%!MRAD_ctwin" => round_end(Some(Team::CounterTerrorist), code),
+                        "%!MRAD_rounddraw" => round_end(None, code),
+                        _ => return,
+                    };
+
+                    self.push_once(kind);
+                }
+            }
+            "RoundTime" => {
+                if let Some(msg) = message.decode::<RoundTime>()
+                    && !self.is_round_active
+                {
+                    self.is_round_active = true;
+                    self.push(TimelineEventKind::RoundStart {
+                        round_time: msg.time,
+                    });
+                }
+            }
+            "TeamScore" => {
+                let score = if self.is_cstrike {
+                    message
+                        .decode::<cstrike::TeamScore>()
+                        .map(|msg| (msg.team_name, msg.score))
+                } else {
+                    message
+                        .decode::<valve::TeamScore>()
+                        .map(|msg| (msg.team_name, msg.frags))
+                };
+
+                if let Some((team, score)) = score {
+                    let team = team.to_str().unwrap_or_default().to_owned();
+
+                    if self.team_scores.get(&team) != Some(&score) {
+                        self.team_scores.insert(team.clone(), score);
+                        self.push(TimelineEventKind::TeamScore { team, score });
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// Handles both localisation tokens and their English text.
+    fn handle_text(&mut self, text: &str) {
+        use Team::*;
+
+        let kinds = match text {
+            "#Bomb_Planted" | "The bomb has been planted!" => vec![TimelineEventKind::BombPlanted],
+            "#Bomb_Defused" | "The bomb has been defused." => vec![
+                TimelineEventKind::BombDefused,
+                round_end(Some(CounterTerrorist), text),
+            ],
+            "#Terrorists_Win"
+            | "Terrorists Win!"
+            | "#Target_Bombed"
+            | "Target Successfully Bombed!"
+            | "#VIP_Assassinated"
+            | "#VIP_Not_Escaped"
+            | "#Terrorists_Escaped"
+            | "#Hostages_Not_Rescued" => vec![round_end(Some(Terrorist), text)],
+            "#CTs_Win"
+            | "Counter-Terrorists Win!"
+            | "#Target_Saved"
+            | "#VIP_Escaped"
+            | "#All_Hostages_Rescued"
+            | "#CTs_PreventEscape"
+            | "#Escaping_Terrorists_Neutralized"
+            | "#Terrorists_Not_Escaped" => vec![round_end(Some(CounterTerrorist), text)],
+            "#Round_Draw" | "Round Draw!" => vec![round_end(None, text)],
+            "#Game_Commencing" | "#Game_will_restart_in" => vec![TimelineEventKind::GameRestart],
+            _ => return,
+        };
+
+        kinds.into_iter().for_each(|kind| self.push_once(kind));
+    }
+
+    fn player(&self, entity_index: u8) -> TimelinePlayer {
+        TimelinePlayer {
+            entity_index,
+            name: self
+                .roster
+                .by_entity_index(entity_index as u16)
+                .map(|player| player.name.clone()),
+        }
+    }
+
+    fn push(&mut self, kind: TimelineEventKind) {
+        match kind {
+            TimelineEventKind::RoundEnd { .. } | TimelineEventKind::GameRestart => {
+                self.is_round_active = false
+            }
+            _ => (),
+        }
+
+        self.events.push(TimelineEvent {
+            time: self.time,
+            entry_index: self.entry_index,
+            frame_index: self.frame_index,
+            kind,
+        });
+    }
+
+    /// Pushes the event unless the same kind of event just happened.
+    fn push_once(&mut self, kind: TimelineEventKind) {
+        let is_duplicate = self
+            .events
+            .iter()
+            .rev()
+            .take_while(|event| self.time - event.time <= DEDUPE_WINDOW)
+            .any(|event| std::mem::discriminant(&event.kind) == std::mem::discriminant(&kind));
+
+        if !is_duplicate {
+            self.push(kind);
+        }
+    }
+}
+
+fn round_end(winner: Option<Team>, reason: &str) -> TimelineEventKind {
+    TimelineEventKind::RoundEnd {
+        winner,
+        reason: reason.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        open_demo,
+        types::{SvcPrint, SvcUpdateUserInfo},
+        usermsg_doer::UserMsgDoer,
+    };
+
+    fn feed(builder: &mut TimelineBuilder, time: f32, message: NetMessage) {
+        builder.time = time;
+        builder.handle_message(&message);
+    }
+
+    fn user(message: impl UserMsgDoer) -> NetMessage {
+        NetMessage::UserMessage(message.to_user_message(100))
+    }
+
+    fn print(text: &str) -> NetMessage {
+        NetMessage::EngineMessage(Box::new(EngineMessage::SvcPrint(SvcPrint {
+            message: format!("{text}\0").as_str().into(),
+        })))
+    }
+
+    fn text_msg(text: &str) -> NetMessage {
+        user(TextMsg {
+            destination: 4,
+            message: format!("{text}\0").as_str().into(),
+            params: vec![],
+        })
+    }
+
+    fn radio(code: &str) -> NetMessage {
+        user(SendAudio {
+            sender: 0,
+            code: format!("{code}\0").as_str().into(),
+            pitch: 100,
+        })
+    }
+
+    fn kinds(builder: &TimelineBuilder) -> Vec<&TimelineEventKind> {
+        builder.events.iter().map(|event| &event.kind).collect()
+    }
+
+    #[test]
+    fn round_start() {
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let timeline = demo.timeline();
+
+        assert!(
+            timeline
+                .iter()
+                .any(|event| matches!(event.kind, TimelineEventKind::RoundStart { .. }))
+        );
+    }
+
+    #[test]
+    fn cstrike_kill() {
+        let mut builder = TimelineBuilder::new(true);
+
+        let user_info = EngineMessage::SvcUpdateUserInfo(SvcUpdateUserInfo {
+            index: 1,
+            id: 7,
+            user_info: "\\name\\bob\0".into(),
+            cd_key_hash: vec![0; 16].into(),
+        });
+
+        feed(
+            &mut builder,
+            1.,
+            NetMessage::EngineMessage(Box::new(user_info)),
+        );
+        feed(
+            &mut builder,
+            2.,
+            user(cstrike::DeathMsg {
+                killer: 2,
+                victim: 3,
+                headshot: 1,
+                weapon: "ak47\0".into(),
+            }),
+        );
+        feed(
+            &mut builder,
+            3.,
+            user(cstrike::DeathMsg {
+                killer: 0,
+                victim: 2,
+                headshot: 0,
+                weapon: "world\0".into(),
+            }),
+        );
+
+        assert_eq!(
+            kinds(&builder),
+            [
+                &TimelineEventKind::Kill {
+                    killer: Some(TimelinePlayer {
+                        entity_index: 2,
+                        name: Some("bob".to_owned()),
+                    }),
+                    victim: TimelinePlayer {
+                        entity_index: 3,
+                        name: None,
+                    },
+                    weapon: "ak47".to_owned(),
+                    headshot: true,
+                },
+                &TimelineEventKind::Kill {
+                    killer: None,
+                    victim: TimelinePlayer {
+                        entity_index: 2,
+                        name: Some("bob".to_owned()),
+                    },
+                    weapon: "world".to_owned(),
+                    headshot: false,
+                },
+            ]
+        );
+        assert_eq!(builder.events[0].time, 2.);
+    }
+
+    #[test]
+    fn valve_kill() {
+        let mut builder = TimelineBuilder::new(false);
+
+        feed(
+            &mut builder,
+            1.,
+            user(valve::DeathMsg {
+                killer: 1,
+                victim: 2,
+                weapon: "crowbar\0".into(),
+            }),
+        );
+
+        let [
+            TimelineEventKind::Kill {
+                killer,
+                weapon,
+                headshot,
+                ..
+            },
+        ] = kinds(&builder)[..]
+        else {
+            panic!("not a single kill");
+        };
+
+        assert_eq!(killer.as_ref().unwrap().entity_index, 1);
+        assert_eq!(weapon, "crowbar");
+        assert!(!headshot);
+    }
+
+    #[test]
+    fn bomb() {
+        let mut builder = TimelineBuilder::new(true);
+
+        // text and radio of the same plant
+        feed(&mut builder, 10., text_msg("#Bomb_Planted"));
+        feed(&mut builder, 10.5, radio("%!MRAD_BOMBPL"));
+        // a plant in a later round
+        feed(&mut builder, 20., radio("%!MRAD_BOMBPL"));
+        feed(&mut builder, 30., text_msg("#Bomb_Defused"));
+        feed(&mut builder, 30., radio("%!MRAD_BOMBDEF"));
+        feed(&mut builder, 30.2, radio("%!MRAD_ctwin"));
+
+        assert_eq!(
+            kinds(&builder),
+            [
+                &TimelineEventKind::BombPlanted,
+                &TimelineEventKind::BombPlanted,
+                &TimelineEventKind::BombDefused,
+                &round_end(Some(Team::CounterTerrorist), "#Bomb_Defused"),
+            ]
+        );
+    }
+
+    #[test]
+    fn rounds() {
+        let mut builder = TimelineBuilder::new(true);
+
+        feed(&mut builder, 0., user(RoundTime { time: 115 }));
+        // sent again on every spawn, same round
+        feed(&mut builder, 5., user(RoundTime { time: 110 }));
+        feed(&mut builder, 60., print("Terrorists Win!\n"));
+        feed(&mut builder, 65., user(RoundTime { time: 115 }));
+        feed(&mut builder, 90., radio("%!MRAD_rounddraw"));
+        feed(&mut builder, 95., text_msg("#Game_Commencing"));
+
+        assert_eq!(
+            kinds(&builder),
+            [
+                &TimelineEventKind::RoundStart { round_time: 115 },
+                &round_end(Some(Team::Terrorist), "Terrorists Win!"),
+                &TimelineEventKind::RoundStart { round_time: 115 },
+                &round_end(None, "%!MRAD_rounddraw"),
+                &TimelineEventKind::GameRestart,
+            ]
+        );
+    }
+
+    #[test]
+    fn team_score() {
+        let mut builder = TimelineBuilder::new(true);
+
+        let score = |team: &str, score| {
+            user(cstrike::TeamScore {
+                team_name: format!("{team}\0").as_str().into(),
+                score,
+            })
+        };
+
+        feed(&mut builder, 1., score("CT", 1));
+        // sent again without a change
+        feed(&mut builder, 2., score("CT", 1));
+        feed(&mut builder, 3., score("TERRORIST", 1));
+        feed(&mut builder, 4., score("CT", 2));
+
+        let scores: Vec<_> = kinds(&builder)
+            .into_iter()
+            .map(|kind| match kind {
+                TimelineEventKind::TeamScore { team, score } => (team.as_str(), *score),
+                _ => panic!("not a team score"),
+            })
+            .collect();
+
+        assert_eq!(scores, [("CT", 1), ("TERRORIST", 1), ("CT", 2)]);
+
+        // other mods send frags and deaths
+        let mut builder = TimelineBuilder::new(false);
+
+        feed(
+            &mut builder,
+            1.,
+            user(valve::TeamScore {
+                team_name: "Blue\0".into(),
+                frags: 5,
+                deaths: 2,
+            }),
+        );
+
+        assert_eq!(
+            kinds(&builder),
+            [&TimelineEventKind::TeamScore {
+                team: "Blue".to_owned(),
+                score: 5,
+            }]
+        );
+    }
+}
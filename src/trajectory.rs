@@ -0,0 +1,589 @@
+//! Paths of players through the map as OBJ or glTF polylines, for laying runs over the map in
+//! Blender or other 3D tools.
+//!
+//! Each player is one line strip through their origins. View directions can be added as short
+//! lines from the origin every so often, in a separate object named after the player with
+//! ` view` at the end.
+//!
+//! Coordinates are game units with Y up, which is what glTF requires and what Blender expects from
+//! OBJ. The game Z axis becomes Y and the game Y axis becomes -Z, so the map lines up when it is
+//! imported the same way.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let demo = open_demo("./src/tests/hltv.dem").unwrap();
+//!
+//! let mut options = TrajectoryOptions::new(TrajectorySource::Entities);
+//! options.view_marker_length = Some(32.);
+//!
+//! let file = std::fs::File::create("./src/tests/hltv.glb").unwrap();
+//! demo.write_trajectories_glb(file, &options).unwrap();
+//! ```
+use std::{collections::BTreeMap, io::Write};
+
+use crate::{
+    error::DemoError,
+    pov::entity_view,
+    snapshot::SnapshotTracker,
+    types::{Demo, FrameData},
+};
+
+const GLB_MAGIC: u32 = 0x4654_6C67;
+const GLB_CHUNK_JSON: u32 = 0x4E4F_534A;
+const GLB_CHUNK_BIN: u32 = 0x004E_4942;
+/// `mode` of a glTF primitive.
+const GLTF_LINES: u8 = 1;
+const GLTF_LINE_STRIP: u8 = 3;
+/// `ARRAY_BUFFER`, for vertex attributes.
+const GLTF_ARRAY_BUFFER: u32 = 34962;
+/// `FLOAT`
+const GLTF_FLOAT: u32 = 5126;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrajectorySource {
+    /// [`crate::types::RefParams::sim_org`] of the recording player.
+    Pov,
+    /// Player entities, for HLTV demos where there is no view of our own.
+    Entities,
+}
+
+#[derive(Debug, Clone)]
+pub struct TrajectoryOptions {
+    pub source: TrajectorySource,
+    /// Length of the view direction lines. None leaves them out.
+    pub view_marker_length: Option<f32>,
+    /// Seconds between view direction lines.
+    pub view_marker_interval: f32,
+}
+
+impl TrajectoryOptions {
+    /// Without view direction lines, or with two a second once a length is set.
+    pub fn new(source: TrajectorySource) -> Self {
+        Self {
+            source,
+            view_marker_length: None,
+            view_marker_interval: 0.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrajectoryPoint {
+    pub entry_index: usize,
+    /// [`crate::types::Frame::time`]
+    pub time: f32,
+    pub origin: [f32; 3],
+    pub view_angles: [f32; 3],
+}
+
+#[derive(Debug, Clone)]
+pub struct Trajectory {
+    /// Player name from [`crate::roster::PlayerRoster`], or `player` followed by the slot.
+    pub name: String,
+    /// Only points where the player moved or turned.
+    pub points: Vec<TrajectoryPoint>,
+}
+
+/// A named list of vertices, either joined one after another or in pairs.
+struct Polyline {
+    name: String,
+    is_strip: bool,
+    vertices: Vec<[f32; 3]>,
+}
+
+impl Demo {
+    /// Where each player was at every network frame outside of loading.
+    ///
+    /// [`TrajectorySource::Entities`] needs parsed messages. Players who never had an origin are
+    /// left out.
+    pub fn trajectories(&self, source: TrajectorySource) -> Result<Vec<Trajectory>, DemoError> {
+        let roster = self.player_roster();
+        let name = |slot: u8| {
+            roster
+                .by_slot(slot)
+                .map(|player| player.name.clone())
+                .unwrap_or_else(|| format!("player{slot}"))
+        };
+
+        // slot to points
+        let mut points: BTreeMap<u8, Vec<TrajectoryPoint>> = BTreeMap::new();
+        let mut push = |slot: u8, point: TrajectoryPoint| {
+            let slot_points = points.entry(slot).or_default();
+
+            if slot_points.last().is_none_or(|last| {
+                last.origin != point.origin || last.view_angles != point.view_angles
+            }) {
+                slot_points.push(point);
+            }
+        };
+
+        let mut tracker = SnapshotTracker::new();
+
+        for (entry_index, entry) in self.directory.entries.iter().enumerate() {
+            for frame in &entry.frames {
+                let FrameData::NetworkMessage(box_type) = &frame.frame_data else {
+                    continue;
+                };
+
+                let network_message = &box_type.1;
+
+                match source {
+                    TrajectorySource::Pov => {
+                        if entry.is_loading() {
+                            continue;
+                        }
+
+                        let refparams = &network_message.info.refparams;
+
+                        push(
+                            roster.player_index.unwrap_or_default(),
+                            TrajectoryPoint {
+                                entry_index,
+                                time: frame.time,
+                                origin: vec3(&refparams.sim_org),
+                                view_angles: vec3(&refparams.view_angles),
+                            },
+                        );
+                    }
+                    TrajectorySource::Entities => {
+                        if network_message.messages.get_parsed_messages().is_none() {
+                            return Err(DemoError::UnparsedMessages);
+                        }
+
+                        // baselines come during loading
+                        tracker.apply_network_message(network_message);
+
+                        if entry.is_loading() {
+                            continue;
+                        }
+
+                        for slot in 0..roster.max_players {
+                            let Some(entity) = tracker.current().entities.get(&(slot as u16 + 1))
+                            else {
+                                continue;
+                            };
+
+                            let Some((origin, view_angles)) = entity_view(&entity.state) else {
+                                continue;
+                            };
+
+                            push(
+                                slot,
+                                TrajectoryPoint {
+                                    entry_index,
+                                    time: frame.time,
+                                    origin,
+                                    view_angles,
+                                },
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(points
+            .into_iter()
+            .map(|(slot, points)| Trajectory {
+                name: name(slot),
+                points,
+            })
+            .collect())
+    }
+
+    /// Writes [`Demo::trajectories`] as a Wavefront OBJ file with an object for each player.
+    pub fn write_trajectories_obj(
+        &self,
+        mut writer: impl Write,
+        options: &TrajectoryOptions,
+    ) -> Result<(), DemoError> {
+        writeln!(writer, "# player trajectories, Y up")?;
+
+        // OBJ indices count from 1 across the whole file
+        let mut vertex_count = 0;
+
+        for polyline in self.polylines(options)? {
+            writeln!(writer, "o {}", polyline.name.replace(['\r', '\n'], " "))?;
+
+            for vertex in &polyline.vertices {
+                let [x, y, z] = y_up(vertex);
+                writeln!(writer, "v {x} {y} {z}")?;
+            }
+
+            let indices: Vec<usize> =
+                (vertex_count + 1..=vertex_count + polyline.vertices.len()).collect();
+
+            if polyline.is_strip {
+                let indices: Vec<String> = indices.iter().map(usize::to_string).collect();
+                writeln!(writer, "l {}", indices.join(" "))?;
+            } else {
+                for pair in indices.chunks_exact(2) {
+                    writeln!(writer, "l {} {}", pair[0], pair[1])?;
+                }
+            }
+
+            vertex_count += polyline.vertices.len();
+        }
+
+        Ok(())
+    }
+
+    /// Writes [`Demo::trajectories`] as a binary glTF file with a node for each player.
+    pub fn write_trajectories_glb(
+        &self,
+        mut writer: impl Write,
+        options: &TrajectoryOptions,
+    ) -> Result<(), DemoError> {
+        let polylines = self.polylines(options)?;
+
+        let mut bin: Vec<u8> = vec![];
+        let mut buffer_views = vec![];
+        let mut accessors = vec![];
+        let mut meshes = vec![];
+        let mut nodes = vec![];
+
+        for (index, polyline) in polylines.iter().enumerate() {
+            let vertices: Vec<[f32; 3]> = polyline.vertices.iter().map(y_up).collect();
+
+            let min = [0, 1, 2].map(|axis| {
+                vertices
+                    .iter()
+                    .map(|vertex| vertex[axis])
+                    .fold(f32::INFINITY, f32::min)
+            });
+            let max = [0, 1, 2].map(|axis| {
+                vertices
+                    .iter()
+                    .map(|vertex| vertex[axis])
+                    .fold(f32::NEG_INFINITY, f32::max)
+            });
+
+            let byte_offset = bin.len();
+            vertices
+                .iter()
+                .flatten()
+                .for_each(|value| bin.extend(value.to_le_bytes()));
+
+            buffer_views.push(format!(
+                r#"{{"buffer":0,"byteOffset":{byte_offset},"byteLength":{},"target":{GLTF_ARRAY_BUFFER}}}"#,
+                bin.len() - byte_offset
+            ));
+            accessors.push(format!(
+                r#"{{"bufferView":{index},"componentType":{GLTF_FLOAT},"count":{},"type":"VEC3","min":{},"max":{}}}"#,
+                vertices.len(),
+                json_array(&min),
+                json_array(&max)
+            ));
+            meshes.push(format!(
+                r#"{{"primitives":[{{"attributes":{{"POSITION":{index}}},"mode":{}}}]}}"#,
+                if polyline.is_strip {
+                    GLTF_LINE_STRIP
+                } else {
+                    GLTF_LINES
+                }
+            ));
+            nodes.push(format!(
+                r#"{{"name":{},"mesh":{index}}}"#,
+                json_string(&polyline.name)
+            ));
+        }
+
+        let node_indices: Vec<String> = (0..nodes.len()).map(|index| index.to_string()).collect();
+
+        let mut json = format!(
+            r#"{{"asset":{{"version":"2.0","generator":"dem"}},"scene":0,"scenes":[{{"nodes":[{}]}}],"nodes":[{}],"meshes":[{}],"accessors":[{}],"bufferViews":[{}],"buffers":[{{"byteLength":{}}}]}}"#,
+            node_indices.join(","),
+            nodes.join(","),
+            meshes.join(","),
+            accessors.join(","),
+            buffer_views.join(","),
+            bin.len()
+        )
+        .into_bytes();
+
+        // chunks are aligned to 4 bytes, JSON with spaces
+        json.resize(json.len().next_multiple_of(4), b' ');
+        bin.resize(bin.len().next_multiple_of(4), 0);
+
+        let total_length = 12 + 8 + json.len() + 8 + bin.len();
+
+        writer.write_all(&GLB_MAGIC.to_le_bytes())?;
+        writer.write_all(&2u32.to_le_bytes())?;
+        writer.write_all(&(total_length as u32).to_le_bytes())?;
+
+        writer.write_all(&(json.len() as u32).to_le_bytes())?;
+        writer.write_all(&GLB_CHUNK_JSON.to_le_bytes())?;
+        writer.write_all(&json)?;
+
+        writer.write_all(&(bin.len() as u32).to_le_bytes())?;
+        writer.write_all(&GLB_CHUNK_BIN.to_le_bytes())?;
+        writer.write_all(&bin)?;
+
+        Ok(())
+    }
+
+    fn polylines(&self, options: &TrajectoryOptions) -> Result<Vec<Polyline>, DemoError> {
+        let mut polylines = vec![];
+
+        for trajectory in self.trajectories(options.source)? {
+            // a single point is not a line
+            if trajectory.points.len() < 2 {
+                continue;
+            }
+
+            polylines.push(Polyline {
+                name: trajectory.name.clone(),
+                is_strip: true,
+                vertices: trajectory.points.iter().map(|point| point.origin).collect(),
+            });
+
+            let Some(length) = options.view_marker_length else {
+                continue;
+            };
+
+            let mut vertices = vec![];
+            let mut last_marker: Option<&TrajectoryPoint> = None;
+
+            for point in &trajectory.points {
+                // time starts over in each entry
+                if last_marker.is_some_and(|last| {
+                    last.entry_index == point.entry_index
+                        && (point.time - last.time).abs() < options.view_marker_interval
+                }) {
+                    continue;
+                }
+
+                let forward = forward(&point.view_angles);
+
+                vertices.push(point.origin);
+                vertices.push([0, 1, 2].map(|axis| point.origin[axis] + forward[axis] * length));
+
+                last_marker = Some(point);
+            }
+
+            polylines.push(Polyline {
+                name: format!("{} view", trajectory.name),
+                is_strip: false,
+                vertices,
+            });
+        }
+
+        Ok(polylines)
+    }
+}
+
+fn vec3(point: &[f32]) -> [f32; 3] {
+    [0, 1, 2].map(|index| point.get(index).copied().unwrap_or_default())
+}
+
+fn y_up(point: &[f32; 3]) -> [f32; 3] {
+    [point[0], point[2], -point[1]]
+}
+
+/// Unit vector of pitch and yaw like `AngleVectors`. Positive pitch looks down.
+fn forward(view_angles: &[f32; 3]) -> [f32; 3] {
+    let pitch = view_angles[0].to_radians();
+    let yaw = view_angles[1].to_radians();
+
+    [
+        pitch.cos() * yaw.cos(),
+        pitch.cos() * yaw.sin(),
+        -pitch.sin(),
+    ]
+}
+
+fn json_array(values: &[f32]) -> String {
+    let values: Vec<String> = values.iter().map(f32::to_string).collect();
+    format!("[{}]", values.join(","))
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::from("\"");
+
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        open_demo,
+        types::{
+            Delta, DeltaValue, EngineMessage, EntityStateDelta, NetMessage, SvcDeltaPacketEntities,
+        },
+    };
+
+    /// Moves the second player to the origin with the model angles at the end of the frame.
+    fn move_player(messages: &mut Vec<NetMessage>, origin: [f32; 3], angles: [f32; 3]) {
+        let fields = [
+            ("origin[0]\0", origin[0]),
+            ("origin[1]\0", origin[1]),
+            ("origin[2]\0", origin[2]),
+            ("angles[0]\0", angles[0]),
+            ("angles[1]\0", angles[1]),
+            ("angles[2]\0", angles[2]),
+        ];
+        let delta: Delta = fields
+            .into_iter()
+            .map(|(key, value)| (key.to_owned(), DeltaValue::FloatSigned(value)))
+            .collect();
+
+        messages.push(NetMessage::EngineMessage(Box::new(
+            EngineMessage::SvcDeltaPacketEntities(SvcDeltaPacketEntities {
+                entity_count: 1,
+                delta_sequence: 0,
+                entity_states: vec![EntityStateDelta {
+                    entity_index: 2,
+                    remove_entity: false,
+                    is_absolute_entity_index: true,
+                    absolute_entity_index: Some(2),
+                    entity_index_difference: None,
+                    has_custom_delta: Some(false),
+                    delta: Some(delta),
+                }],
+            }),
+        )));
+    }
+
+    #[test]
+    fn pov_obj() {
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let trajectories = demo.trajectories(TrajectorySource::Pov).unwrap();
+
+        assert_eq!(trajectories.len(), 1);
+        assert!(trajectories[0].points.len() > 1);
+        assert!(
+            trajectories[0]
+                .points
+                .windows(2)
+                .all(|pair| pair[0] != pair[1])
+        );
+
+        let mut options = TrajectoryOptions::new(TrajectorySource::Pov);
+        options.view_marker_length = Some(16.);
+
+        let mut bytes = vec![];
+        demo.write_trajectories_obj(&mut bytes, &options).unwrap();
+
+        let obj = String::from_utf8(bytes).unwrap();
+        let count = |prefix| obj.lines().filter(|line| line.starts_with(prefix)).count();
+        let view_lines = count("l ") - 1;
+
+        assert_eq!(count("o "), 2);
+        assert!(view_lines > 0);
+        assert_eq!(count("v "), trajectories[0].points.len() + view_lines * 2);
+    }
+
+    #[test]
+    fn entities() {
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+
+        // the server does not send the origin of the player to themselves
+        assert!(
+            demo.trajectories(TrajectorySource::Entities)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn other_player() {
+        let mut demo = open_demo("./src/tests/weapon.dem").unwrap();
+
+        for message in demo
+            .directory
+            .entries
+            .iter_mut()
+            .flat_map(|entry| &mut entry.frames)
+            .filter_map(|frame| match &mut frame.frame_data {
+                FrameData::NetworkMessage(box_type) => {
+                    box_type.1.messages.get_parsed_messages_mut()
+                }
+                _ => None,
+            })
+            .flatten()
+        {
+            if let NetMessage::EngineMessage(message) = message
+                && let EngineMessage::SvcServerInfo(info) = message.as_mut()
+            {
+                info.max_players = 2;
+            }
+        }
+
+        let mut network_frames: Vec<_> = demo.directory.entries[1]
+            .frames
+            .iter_mut()
+            .filter_map(|frame| match &mut frame.frame_data {
+                FrameData::NetworkMessage(box_type) => {
+                    box_type.1.messages.get_parsed_messages_mut()
+                }
+                _ => None,
+            })
+            .collect();
+        let [.., second_last, last] = network_frames.as_mut_slice() else {
+            panic!("too few network frames");
+        };
+
+        // looking down, then up with the pitch wrapped around
+        move_player(second_last, [10., 20., 30.], [5., 90., 0.]);
+        move_player(last, [-10., -20., 30.], [350., 180., 0.]);
+
+        let trajectories = demo.trajectories(TrajectorySource::Entities).unwrap();
+        let player = trajectories
+            .iter()
+            .find(|trajectory| trajectory.name == "player1")
+            .unwrap();
+        let [.., second_last, last] = player.points.as_slice() else {
+            panic!("too few points");
+        };
+
+        assert_eq!(second_last.origin, [10., 20., 30.]);
+        assert_eq!(second_last.view_angles, [-15., 90., 0.]);
+        assert_eq!(last.origin, [-10., -20., 30.]);
+        assert_eq!(last.view_angles, [30., 180., 0.]);
+        assert_eq!(last.entry_index, 1);
+    }
+
+    #[test]
+    fn glb() {
+        let demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let mut options = TrajectoryOptions::new(TrajectorySource::Pov);
+        options.view_marker_length = Some(16.);
+
+        let mut bytes = vec![];
+        demo.write_trajectories_glb(&mut bytes, &options).unwrap();
+
+        assert_eq!(&bytes[..4], b"glTF");
+        assert_eq!(
+            u32::from_le_bytes(bytes[8..12].try_into().unwrap()) as usize,
+            bytes.len()
+        );
+
+        let json_length = u32::from_le_bytes(bytes[12..16].try_into().unwrap()) as usize;
+        let json = std::str::from_utf8(&bytes[20..20 + json_length]).unwrap();
+
+        assert!(json.contains(r#""mode":3"#));
+        assert!(json.contains(r#""mode":1"#));
+        assert_eq!(json_length % 4, 0);
+    }
+
+    #[test]
+    fn forward_vector() {
+        let [x, y, z] = forward(&[0., 90., 0.]);
+        assert!(x.abs() < 1e-6 && (y - 1.).abs() < 1e-6 && z.abs() < 1e-6);
+
+        let [_, _, z] = forward(&[90., 0., 0.]);
+        assert!((z + 1.).abs() < 1e-6);
+    }
+}
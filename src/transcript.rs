@@ -0,0 +1,335 @@
+//! Readable transcript of chat, console and HUD text.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let demo = open_demo("./src/tests/demotest.dem").unwrap();
+//!
+//! for line in demo.transcript() {
+//!     println!("[{:.2}] {:?} {:?}: {}", line.time, line.channel, line.speaker, line.text);
+//! }
+//! ```
+use std::collections::HashMap;
+
+use crate::{
+    roster::PlayerRoster,
+    types::{ByteString, Demo, EngineMessage, FrameData, NetMessage, TempEntity, UserMessage},
+    usermsg_doer::valve::{SayText, TextMsg},
+};
+
+/// English text of common Counter-Strike localisation tokens.
+///
+/// `%s1` to `%s4` are replaced with the message arguments.
+const TOKENS: &[(&str, &str)] = &[
+    ("#Cstrike_Chat_CT", "(Counter-Terrorist) %s1 :  %s2"),
+    ("#Cstrike_Chat_T", "(Terrorist) %s1 :  %s2"),
+    (
+        "#Cstrike_Chat_CT_Dead",
+        "*DEAD*(Counter-Terrorist) %s1 :  %s2",
+    ),
+    ("#Cstrike_Chat_T_Dead", "*DEAD*(Terrorist) %s1 :  %s2"),
+    ("#Cstrike_Chat_Spec", "(Spectator) %s1 :  %s2"),
+    ("#Cstrike_Chat_All", "%s1 :  %s2"),
+    ("#Cstrike_Chat_AllDead", "*DEAD* %s1 :  %s2"),
+    ("#Cstrike_Chat_AllSpec", "*SPEC* %s1 :  %s2"),
+    ("#Cstrike_Name_Change", "* %s1 changed name to %s2"),
+    ("#Game_radio", "%s1 (RADIO): %s2"),
+    ("#Game_join_terrorist", "%s1 is joining the Terrorist force"),
+    (
+        "#Game_join_ct",
+        "%s1 is joining the Counter-Terrorist force",
+    ),
+    ("#Game_teammate_attack", "%s1 attacked a teammate"),
+    ("#Game_Commencing", "Game Commencing!"),
+    ("#Game_will_restart_in", "The game will restart in %s1 %s2"),
+    ("#SECOND", "SECOND"),
+    ("#SECONDS", "SECONDS"),
+    ("#Terrorists_Win", "Terrorists Win!"),
+    ("#CTs_Win", "Counter-Terrorists Win!"),
+    ("#Round_Draw", "Round Draw!"),
+    ("#Target_Bombed", "Target Successfully Bombed!"),
+    ("#Target_Saved", "Target has been saved!"),
+    ("#Bomb_Planted", "The bomb has been planted!"),
+    ("#Bomb_Defused", "The bomb has been defused."),
+    ("#Fire_in_the_hole", "Fire in the hole!"),
+    ("#Cover_me", "Cover me!"),
+    ("#Enemy_spotted", "Enemy spotted."),
+    ("#Affirmative", "Affirmative."),
+    ("#Negative", "Negative."),
+    ("#Need_backup", "Need backup."),
+    ("#Sector_clear", "Sector clear."),
+    ("#In_position", "I'm in position."),
+    ("#Reporting_in", "Reporting in."),
+    ("#Follow_me", "Follow me."),
+    ("#Hold_this_position", "Hold this position."),
+    ("#Regroup_team", "Regroup team."),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscriptChannel {
+    /// [`crate::types::SvcPrint`] and console [`TextMsg`].
+    Console,
+    /// [`crate::types::SvcCenterPrint`] and center [`TextMsg`].
+    Center,
+    /// Notify area [`TextMsg`].
+    Notify,
+    /// [`SayText`] and chat [`TextMsg`].
+    Chat,
+    /// [`crate::types::TeTextMessage`].
+    Hud,
+    /// [`crate::types::SvcStuffText`].
+    Command,
+}
+
+#[derive(Debug, Clone)]
+pub struct TranscriptEntry {
+    /// [`crate::types::Frame::time`] of the frame containing the message.
+    pub time: f32,
+    pub entry_index: usize,
+    pub frame_index: usize,
+    pub channel: TranscriptChannel,
+    /// Name of the player who said it, for chat.
+    pub speaker: Option<String>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct TranscriptOptions {
+    /// Localisation token to text, such as `#Cstrike_Chat_All` to `%s1 :  %s2`.
+    ///
+    /// Defaults to a small set of Counter-Strike tokens. Unknown tokens are kept as is.
+    pub tokens: HashMap<String, String>,
+}
+
+impl Default for TranscriptOptions {
+    fn default() -> Self {
+        Self {
+            tokens: TOKENS
+                .iter()
+                .map(|(token, text)| (token.to_string(), text.to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl Demo {
+    /// Collects every text message in the demo with the default [`TranscriptOptions`].
+    pub fn transcript(&self) -> Vec<TranscriptEntry> {
+        self.transcript_with(&TranscriptOptions::default())
+    }
+
+    /// Collects every text message in the demo.
+    ///
+    /// Only network messages parsed with [`crate::types::MessageDataParseMode::Parse`] are read.
+    pub fn transcript_with(&self, options: &TranscriptOptions) -> Vec<TranscriptEntry> {
+        let mut roster = PlayerRoster::new();
+        let mut res = vec![];
+
+        for (entry_index, entry) in self.directory.entries.iter().enumerate() {
+            for (frame_index, frame) in entry.frames.iter().enumerate() {
+                let FrameData::NetworkMessage(box_type) = &frame.frame_data else {
+                    continue;
+                };
+
+                let Some(messages) = box_type.1.messages.get_parsed_messages() else {
+                    continue;
+                };
+
+                for message in messages {
+                    let line = match message {
+                        NetMessage::UserMessage(message) => {
+                            user_message_line(message, &roster, options)
+                        }
+                        NetMessage::EngineMessage(message) => {
+                            roster.apply(message, frame.time, entry_index, frame_index);
+                            engine_message_line(message)
+                        }
+                    };
+
+                    let Some((channel, speaker, text)) = line else {
+                        continue;
+                    };
+
+                    if text.is_empty() {
+                        continue;
+                    }
+
+                    res.push(TranscriptEntry {
+                        time: frame.time,
+                        entry_index,
+                        frame_index,
+                        channel,
+                        speaker,
+                        text,
+                    });
+                }
+            }
+        }
+
+        res
+    }
+}
+
+type Line = (TranscriptChannel, Option<String>, String);
+
+fn engine_message_line(message: &EngineMessage) -> Option<Line> {
+    let (channel, text) = match message {
+        EngineMessage::SvcPrint(print) => (TranscriptChannel::Console, print.message.as_slice()),
+        EngineMessage::SvcCenterPrint(print) => {
+            (TranscriptChannel::Center, print.message.as_slice())
+        }
+        EngineMessage::SvcStuffText(stuff) => {
+            (TranscriptChannel::Command, stuff.command.as_slice())
+        }
+        EngineMessage::SvcTempEntity(temp_entity) => match &temp_entity.entity {
+            TempEntity::TeTextMessage(text) => (TranscriptChannel::Hud, text.message.as_slice()),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    Some((channel, None, clean_text(text)))
+}
+
+fn user_message_line(
+    message: &UserMessage,
+    roster: &PlayerRoster,
+    options: &TranscriptOptions,
+) -> Option<Line> {
+    match message.name_str() {
+        "SayText" => {
+            let say = message.decode::<SayText>()?;
+            let speaker = roster
+                .by_entity_index(say.sender as u16)
+                .map(|player| player.name.clone());
+
+            let mut params: Vec<String> = say.params.iter().map(byte_string_text).collect();
+
+            // Counter-Strike leaves the name empty for the client to fill in.
+            if let (Some(first), Some(speaker)) = (params.first_mut(), &speaker)
+                && first.is_empty()
+            {
+                first.clone_from(speaker);
+            }
+
+            let text = localise(&byte_string_text(&say.message), &params, options);
+
+            Some((TranscriptChannel::Chat, speaker, text))
+        }
+        "TextMsg" => {
+            let text_msg = message.decode::<TextMsg>()?;
+
+            let channel = match text_msg.destination {
+                1 => TranscriptChannel::Notify,
+                3 => TranscriptChannel::Chat,
+                4 => TranscriptChannel::Center,
+                _ => TranscriptChannel::Console,
+            };
+
+            let params: Vec<String> = text_msg
+                .params
+                .iter()
+                .map(|param| localise(&byte_string_text(param), &[], options))
+                .collect();
+
+            let text = localise(&byte_string_text(&text_msg.message), &params, options);
+
+            Some((channel, None, text))
+        }
+        _ => None,
+    }
+}
+
+/// Expands a localisation token and its arguments. Text that is not a known token only has its
+/// arguments replaced.
+fn localise(text: &str, params: &[String], options: &TranscriptOptions) -> String {
+    let template = options.tokens.get(text).map_or(text, String::as_str);
+
+    // one pass so arguments with %s in them are not expanded again
+    let mut res = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("%s") {
+        res.push_str(&rest[..start]);
+        rest = &rest[start + 2..];
+
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+
+        // the longest number that is an argument, so %s10 is not %s1 followed by 0
+        let found = (1..=digits).rev().find_map(|length| {
+            let index: usize = rest[..length].parse().ok()?;
+            let param = params.get(index.checked_sub(1)?)?;
+
+            Some((length, param))
+        });
+
+        match found {
+            Some((length, param)) => {
+                res.push_str(param);
+                rest = &rest[length..];
+            }
+            None => res.push_str("%s"),
+        }
+    }
+
+    res.push_str(rest);
+
+    clean_text(res.as_bytes())
+}
+
+fn byte_string_text(s: &ByteString) -> String {
+    clean_text(s.as_slice())
+}
+
+/// Cuts at the null terminator, replaces invalid UTF-8 and drops color codes and other control
+/// characters.
+fn clean_text(i: &[u8]) -> String {
+    let end = i.iter().position(|&c| c == 0).unwrap_or(i.len());
+
+    String::from_utf8_lossy(&i[..end])
+        .chars()
+        .filter(|c| !c.is_control() || *c == '\n')
+        .collect::<String>()
+        .trim()
+        .to_owned()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn localise_chat() {
+        let options = TranscriptOptions::default();
+        let params = ["foo".to_owned(), "hello".to_owned()];
+
+        assert_eq!(
+            localise("#Cstrike_Chat_All", &params, &options),
+            "foo :  hello"
+        );
+        assert_eq!(localise("#Unknown", &params, &options), "#Unknown");
+    }
+
+    #[test]
+    fn localise_once() {
+        let options = TranscriptOptions::default();
+        let params = ["foo".to_owned(), "%s1 and %s2".to_owned()];
+
+        assert_eq!(
+            localise("#Cstrike_Chat_All", &params, &options),
+            "foo :  %s1 and %s2"
+        );
+
+        let params: Vec<String> = (1..=10).map(|index| format!("<{index}>")).collect();
+        assert_eq!(
+            localise("%s10 %s1 %s %s11", &params, &options),
+            "<10> <1> %s <1>1"
+        );
+    }
+
+    #[test]
+    fn clean() {
+        assert_eq!(clean_text(b"\x02foo: hi\n\0\0garbage"), "foo: hi");
+        assert_eq!(clean_text(b"caf\xe9\0"), "caf\u{fffd}");
+    }
+}
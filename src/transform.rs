@@ -0,0 +1,279 @@
+//! Editing messages and frames without walking the directory by hand.
+//!
+//! The callbacks get the state the parser would have at that point, so user messages can be
+//! told apart by name and messages depending on delta decoders can be built correctly.
+//! [`NetworkMessage::message_length`], [`crate::types::DirectoryEntry::frame_count`] and
+//! [`crate::types::DirectoryEntry::track_time`] are kept up to date.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut demo = open_demo("./src/tests/demotest.dem").unwrap();
+//!
+//! // no more sounds
+//! demo.retain_messages(|_, message| {
+//!     !matches!(message.get_engine_message(), Some(EngineMessage::SvcSound(_)))
+//! });
+//!
+//! // every Damage user message twice
+//! demo.map_messages(|ctx, message| {
+//!     if ctx.user_message_name(&message) == Some("Damage") {
+//!         vec![message.clone(), message]
+//!     } else {
+//!         vec![message]
+//!     }
+//! });
+//!
+//! demo.write_to_file("./src/tests/demo2test.dem").unwrap();
+//! ```
+use crate::types::{Demo, DemoState, Frame, FrameData, MessageData, NetMessage, NetworkMessage};
+
+/// Where a message is and the state before it.
+#[derive(Debug, Clone, Copy)]
+pub struct MessageContext<'a> {
+    pub entry_index: usize,
+    /// Index of the frame in the entry before any change.
+    pub frame_index: usize,
+    /// [`Frame::time`] of the frame holding the message.
+    pub time: f32,
+    /// State after every message before this one.
+    pub state: &'a DemoState,
+}
+
+impl MessageContext<'_> {
+    /// Registered name of a user message without null terminator, [`None`] for engine messages
+    /// and user messages that are not registered.
+    pub fn user_message_name(&self, message: &NetMessage) -> Option<&str> {
+        let NetMessage::UserMessage(user_message) = message else {
+            return None;
+        };
+
+        self.state
+            .custom_messages
+            .get(&user_message.id)
+            .and_then(|registered| registered.name.to_str().ok())
+    }
+}
+
+/// Where a frame is and the state before it.
+#[derive(Debug, Clone, Copy)]
+pub struct FrameContext<'a> {
+    pub entry_index: usize,
+    /// Index of the frame in the entry before any change.
+    pub frame_index: usize,
+    /// State after every frame before this one.
+    pub state: &'a DemoState,
+}
+
+impl Demo {
+    /// Keeps the parsed network messages for which `f` returns true. `f` may also change them.
+    pub fn retain_messages(&mut self, mut f: impl FnMut(&MessageContext, &mut NetMessage) -> bool) {
+        self.map_messages(|ctx, mut message| {
+            if f(ctx, &mut message) {
+                vec![message]
+            } else {
+                vec![]
+            }
+        });
+    }
+
+    /// Replaces every parsed network message with the messages returned from `f`. Return nothing
+    /// to drop it, or more than one to insert after or before it.
+    ///
+    /// The state given to `f` includes the returned messages, not the original ones.
+    pub fn map_messages(
+        &mut self,
+        mut f: impl FnMut(&MessageContext, NetMessage) -> Vec<NetMessage>,
+    ) {
+        let mut state = DemoState::new_raw();
+
+        for (entry_index, entry) in self.directory.entries.iter_mut().enumerate() {
+            for (frame_index, frame) in entry.frames.iter_mut().enumerate() {
+                let time = frame.time;
+
+                let FrameData::NetworkMessage(box_type) = &mut frame.frame_data else {
+                    continue;
+                };
+
+                let network_message = &mut box_type.1;
+
+                let MessageData::Parsed(messages) = &mut network_message.messages else {
+                    continue;
+                };
+
+                let mut message_length = 0;
+                let mut res = Vec::with_capacity(messages.len());
+
+                for message in std::mem::take(messages) {
+                    let ctx = MessageContext {
+                        entry_index,
+                        frame_index,
+                        time,
+                        state: &state,
+                    };
+
+                    for message in f(&ctx, message) {
+                        message_length += message.write(&state).len() as u32;
+                        state.apply(&message);
+                        res.push(message);
+                    }
+                }
+
+                *messages = res;
+                network_message.message_length = message_length;
+            }
+        }
+    }
+
+    /// Replaces every frame with the frames returned from `f`. Return nothing to drop it, or more
+    /// than one to insert after or before it.
+    ///
+    /// A missing [`FrameData::NextSection`] is written at the end of the entry anyway.
+    pub fn map_frames(&mut self, mut f: impl FnMut(&FrameContext, Frame) -> Vec<Frame>) {
+        let mut state = DemoState::new_raw();
+
+        for (entry_index, entry) in self.directory.entries.iter_mut().enumerate() {
+            let mut res = Vec::with_capacity(entry.frames.len());
+
+            for (frame_index, frame) in std::mem::take(&mut entry.frames).into_iter().enumerate() {
+                let ctx = FrameContext {
+                    entry_index,
+                    frame_index,
+                    state: &state,
+                };
+
+                for mut frame in f(&ctx, frame) {
+                    if let FrameData::NetworkMessage(box_type) = &mut frame.frame_data {
+                        update_message_length(&mut box_type.1, &mut state);
+                    }
+
+                    res.push(frame);
+                }
+            }
+
+            entry.frames = res;
+            entry.frame_count = entry.frames.len() as i32;
+
+            // LOADING entries are written with no track time
+            if !entry.is_loading() {
+                entry.track_time = entry
+                    .frames
+                    .last()
+                    .map(|frame| frame.time)
+                    .unwrap_or_default();
+            }
+        }
+    }
+}
+
+/// Recomputes the length of the messages as written after `state`, which is then updated with
+/// them.
+fn update_message_length(network_message: &mut NetworkMessage, state: &mut DemoState) {
+    match &network_message.messages {
+        MessageData::Parsed(messages) => {
+            network_message.message_length = messages
+                .iter()
+                .map(|message| {
+                    let length = message.write(state).len() as u32;
+                    state.apply(message);
+                    length
+                })
+                .sum();
+        }
+        MessageData::Raw(bytes) => network_message.message_length = bytes.len() as u32,
+        MessageData::None => network_message.message_length = 0,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{open_demo, open_demo_from_bytes, types::EngineMessage};
+
+    fn count_messages(demo: &Demo, f: impl Fn(&NetMessage) -> bool) -> usize {
+        demo.directory
+            .entries
+            .iter()
+            .flat_map(|entry| &entry.frames)
+            .filter_map(|frame| match &frame.frame_data {
+                FrameData::NetworkMessage(box_type) => box_type.1.messages.get_parsed_messages(),
+                _ => None,
+            })
+            .flatten()
+            .filter(|message| f(message))
+            .count()
+    }
+
+    #[test]
+    fn retain_and_insert() {
+        let mut demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let is_sound = |message: &NetMessage| {
+            matches!(
+                message.get_engine_message(),
+                Some(EngineMessage::SvcSound(_))
+            )
+        };
+
+        assert!(count_messages(&demo, is_sound) > 0);
+
+        demo.retain_messages(|_, message| !is_sound(message));
+
+        let mut names = 0;
+
+        demo.map_messages(|ctx, message| {
+            if ctx.user_message_name(&message).is_some() {
+                names += 1;
+            }
+
+            vec![message.clone(), message]
+        });
+
+        assert!(names > 0);
+
+        let before = count_messages(&demo, |_| true);
+        let demo = open_demo_from_bytes(&demo.write_to_bytes()).unwrap();
+
+        assert_eq!(count_messages(&demo, is_sound), 0);
+        assert_eq!(count_messages(&demo, |_| true), before);
+    }
+
+    #[test]
+    fn drop_frames() {
+        let mut demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let frame_count = demo.directory.entries[1].frames.len();
+
+        demo.map_frames(|_, frame| {
+            if matches!(frame.frame_data, FrameData::Event(_)) {
+                vec![]
+            } else {
+                vec![frame]
+            }
+        });
+
+        let playback = &demo.directory.entries[1];
+
+        assert!(playback.frames.len() < frame_count);
+        assert_eq!(playback.frame_count as usize, playback.frames.len());
+    }
+
+    #[test]
+    fn drop_end() {
+        let mut demo = open_demo("./src/tests/weapon.dem").unwrap();
+        let track_time = demo.directory.entries[1].track_time;
+
+        demo.map_frames(|ctx, frame| {
+            if ctx.entry_index == 1 && frame.time > 5. {
+                vec![]
+            } else {
+                vec![frame]
+            }
+        });
+
+        let playback = &demo.directory.entries[1];
+
+        assert!(track_time > 5.);
+        assert!(playback.track_time <= 5.);
+        assert_eq!(playback.track_time, playback.frames.last().unwrap().time);
+        assert_eq!(demo.directory.entries[0].track_time, 0.);
+    }
+}
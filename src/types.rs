@@ -14,6 +14,7 @@ use crate::utils::get_initial_delta;
 ///
 /// Basically storing some global values for demo to parse
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DemoState {
     pub delta_decoders: DeltaDecoderTable,
     pub max_client: u8,
@@ -45,14 +46,17 @@ pub type DemoGlobalState = DemoState;
 
 // Everything not related to netmessage starts here
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Demo {
     pub header: Header,
     pub directory: Directory,
     /// Not part of a demo. Do not use this
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub(crate) _state: Option<DemoGlobalState>,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Header {
     /// `[u8; 8]`
     pub magic: Vec<u8>,
@@ -67,11 +71,13 @@ pub struct Header {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Directory {
     pub entries: Vec<DirectoryEntry>,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DirectoryEntry {
     pub type_: i32,
     /// `[u8; 64]`
@@ -79,13 +85,22 @@ pub struct DirectoryEntry {
     pub flags: i32,
     pub cd_track: i32,
     pub track_time: f32,
+    /// As read from the file. Computed again when writing.
     pub frame_count: i32,
     pub frame_offset: i32,
     pub file_length: i32,
     pub frames: Vec<Frame>,
 }
 
+impl DirectoryEntry {
+    /// LOADING entries hold the connection messages and are not timed like playback.
+    pub fn is_loading(&self) -> bool {
+        self.type_ == 0
+    }
+}
+
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Frame {
     pub time: f32,
     pub frame: i32,
@@ -93,6 +108,7 @@ pub struct Frame {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FrameData {
     NetworkMessage(Box<(NetworkMessageType, NetworkMessage)>),
     DemoStart,
@@ -126,6 +142,7 @@ impl FrameData {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ConsoleCommand {
     /// `[u8; 64]`
     pub command: ByteString,
@@ -135,6 +152,7 @@ pub struct ConsoleCommand {
 type Point<T> = Vec<T>;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ClientData {
     pub origin: Point<f32>,
     pub viewangles: Point<f32>,
@@ -144,6 +162,7 @@ pub struct ClientData {
 
 /// This is different from [`EventS`], which is used for event types in netmessage
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Event {
     pub flags: i32,
     pub index: i32,
@@ -152,6 +171,7 @@ pub struct Event {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EventArgs {
     pub flags: i32,
     pub entity_index: i32,
@@ -168,6 +188,7 @@ pub struct EventArgs {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Sound {
     pub channel: i32,
     /// `[u8; sample_length]`
@@ -179,12 +200,14 @@ pub struct Sound {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WeaponAnimation {
     pub sequence: i32,
     pub body: i32,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DemoBuffer {
     /// `[u8; buffer_length]`
     pub buffer: Vec<u8>,
@@ -192,6 +215,7 @@ pub struct DemoBuffer {
 
 /// <https://github.com/YaLTeR/hldemo-rs/blob/cbc1efa212a4fc49c776304058efd07e0369caa7/src/types.rs#L187>
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NetworkMessageType {
     Start,
     Normal,
@@ -212,6 +236,7 @@ impl TryFrom<u8> for NetworkMessageType {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NetworkMessage {
     pub info: DemoInfo,
     pub sequence_info: SequenceInfo,
@@ -221,6 +246,7 @@ pub struct NetworkMessage {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DemoInfo {
     pub timestamp: f32,
     pub refparams: RefParams,
@@ -231,6 +257,7 @@ pub struct DemoInfo {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RefParams {
     pub view_origin: Point<f32>,
     pub view_angles: Point<f32>,
@@ -269,6 +296,7 @@ pub struct RefParams {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UserCmd {
     pub lerp_msec: i16,
     pub msec: u8,
@@ -288,7 +316,37 @@ pub struct UserCmd {
     pub impact_position: Point<f32>,
 }
 
+bitflags::bitflags! {
+    /// `IN_` flags of [`UserCmd::buttons`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct Buttons: u16 {
+        const ATTACK = 1 << 0;
+        const JUMP = 1 << 1;
+        const DUCK = 1 << 2;
+        const FORWARD = 1 << 3;
+        const BACK = 1 << 4;
+        const USE = 1 << 5;
+        const CANCEL = 1 << 6;
+        const LEFT = 1 << 7;
+        const RIGHT = 1 << 8;
+        const MOVELEFT = 1 << 9;
+        const MOVERIGHT = 1 << 10;
+        const ATTACK2 = 1 << 11;
+        const RUN = 1 << 12;
+        const RELOAD = 1 << 13;
+        const ALT1 = 1 << 14;
+        const SCORE = 1 << 15;
+    }
+}
+
+impl UserCmd {
+    pub fn button_flags(&self) -> Buttons {
+        Buttons::from_bits_retain(self.buttons)
+    }
+}
+
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MoveVars {
     pub gravity: f32,
     pub stopspeed: f32,
@@ -316,6 +374,7 @@ pub struct MoveVars {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SequenceInfo {
     pub incoming_sequence: i32,
     pub incoming_acknowledged: i32,
@@ -327,6 +386,7 @@ pub struct SequenceInfo {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MessageData {
     Parsed(Vec<NetMessage>),
     Raw(Vec<u8>),
@@ -352,6 +412,7 @@ impl MessageData {
 }
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MessageDataParseMode {
     /// Parses network messages
     Parse,
@@ -377,6 +438,7 @@ pub type DeltaDecoder = Vec<DeltaDecoderS>;
 pub type DeltaDecoderTable = HashMap<String, DeltaDecoder>;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DeltaDecoderS {
     pub name: String,
     pub bits: u32,
@@ -398,6 +460,7 @@ pub enum DeltaType {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DeltaValue {
     ByteSigned(i8),
     ByteUnsigned(u8),
@@ -436,6 +499,7 @@ impl DeltaValue {
 
 // Main
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NetMessage {
     UserMessage(UserMessage),
     EngineMessage(Box<EngineMessage>),
@@ -461,6 +525,7 @@ impl NetMessage {
 
 pub type CustomMessage = HashMap<u8, SvcNewUserMsg>;
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UserMessage {
     pub id: u8,
     /// `[bool; 16]`
@@ -471,6 +536,7 @@ pub struct UserMessage {
 // Messages
 #[repr(u8)]
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EngineMessage {
     SvcBad = 0,
     SvcNop = 1,
@@ -533,24 +599,94 @@ pub enum EngineMessage {
     SvcSendCvarValue2(SvcSendCvarValue2) = 58,
 }
 
+impl EngineMessage {
+    /// Name of the variant, such as `SvcTime`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::SvcBad => "SvcBad",
+            Self::SvcNop => "SvcNop",
+            Self::SvcDisconnect(_) => "SvcDisconnect",
+            Self::SvcEvent(_) => "SvcEvent",
+            Self::SvcVersion(_) => "SvcVersion",
+            Self::SvcSetView(_) => "SvcSetView",
+            Self::SvcSound(_) => "SvcSound",
+            Self::SvcTime(_) => "SvcTime",
+            Self::SvcPrint(_) => "SvcPrint",
+            Self::SvcStuffText(_) => "SvcStuffText",
+            Self::SvcSetAngle(_) => "SvcSetAngle",
+            Self::SvcServerInfo(_) => "SvcServerInfo",
+            Self::SvcLightStyle(_) => "SvcLightStyle",
+            Self::SvcUpdateUserInfo(_) => "SvcUpdateUserInfo",
+            Self::SvcDeltaDescription(_) => "SvcDeltaDescription",
+            Self::SvcClientData(_) => "SvcClientData",
+            Self::SvcStopSound(_) => "SvcStopSound",
+            Self::SvcPings(_) => "SvcPings",
+            Self::SvcParticle(_) => "SvcParticle",
+            Self::SvcDamage => "SvcDamage",
+            Self::SvcSpawnStatic(_) => "SvcSpawnStatic",
+            Self::SvcEventReliable(_) => "SvcEventReliable",
+            Self::SvcSpawnBaseline(_) => "SvcSpawnBaseline",
+            Self::SvcTempEntity(_) => "SvcTempEntity",
+            Self::SvcSetPause(_) => "SvcSetPause",
+            Self::SvcSignOnNum(_) => "SvcSignOnNum",
+            Self::SvcCenterPrint(_) => "SvcCenterPrint",
+            Self::SvcKilledMonster => "SvcKilledMonster",
+            Self::SvcFoundSecret => "SvcFoundSecret",
+            Self::SvcSpawnStaticSound(_) => "SvcSpawnStaticSound",
+            Self::SvcIntermission => "SvcIntermission",
+            Self::SvcFinale(_) => "SvcFinale",
+            Self::SvcCdTrack(_) => "SvcCdTrack",
+            Self::SvcRestore(_) => "SvcRestore",
+            Self::SvcCutscene(_) => "SvcCutscene",
+            Self::SvcWeaponAnim(_) => "SvcWeaponAnim",
+            Self::SvcDecalName(_) => "SvcDecalName",
+            Self::SvcRoomType(_) => "SvcRoomType",
+            Self::SvcAddAngle(_) => "SvcAddAngle",
+            Self::SvcNewUserMsg(_) => "SvcNewUserMsg",
+            Self::SvcPacketEntities(_) => "SvcPacketEntities",
+            Self::SvcDeltaPacketEntities(_) => "SvcDeltaPacketEntities",
+            Self::SvcChoke => "SvcChoke",
+            Self::SvcResourceList(_) => "SvcResourceList",
+            Self::SvcNewMovevars(_) => "SvcNewMovevars",
+            Self::SvcResourceRequest(_) => "SvcResourceRequest",
+            Self::SvcCustomization(_) => "SvcCustomization",
+            Self::SvcCrosshairAngle(_) => "SvcCrosshairAngle",
+            Self::SvcSoundFade(_) => "SvcSoundFade",
+            Self::SvcFileTxferFailed(_) => "SvcFileTxferFailed",
+            Self::SvcHltv(_) => "SvcHltv",
+            Self::SvcDirector(_) => "SvcDirector",
+            Self::SvcVoiceInit(_) => "SvcVoiceInit",
+            Self::SvcVoiceData(_) => "SvcVoiceData",
+            Self::SvcSendExtraInfo(_) => "SvcSendExtraInfo",
+            Self::SvcTimeScale(_) => "SvcTimeScale",
+            Self::SvcResourceLocation(_) => "SvcResourceLocation",
+            Self::SvcSendCvarValue(_) => "SvcSendCvarValue",
+            Self::SvcSendCvarValue2(_) => "SvcSendCvarValue2",
+        }
+    }
+}
+
 // SVC_BAD 0
 
 // SVC_NOP 1
 
 /// SVC_DISCONNECT 2
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcDisconnect {
     pub reason: ByteVec,
 }
 
 /// SVC_EVENT 3
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcEvent {
     /// `[bool; 5]`
     pub event_count: u8,
     pub events: Vec<EventS>,
 }
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EventS {
     /// `[bool; 10]`
     pub event_index: u16,
@@ -558,6 +694,10 @@ pub struct EventS {
     /// `[bool; 11]`
     pub packet_index: Option<u16>,
     pub has_delta: Option<bool>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "sorted_delta::serialize_option")
+    )]
     pub delta: Option<Delta>,
     pub has_fire_time: bool,
     /// `[bool; 16]`
@@ -566,18 +706,21 @@ pub struct EventS {
 
 /// SVC_VERSION 4
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcVersion {
     pub protocol_version: u32,
 }
 
 /// SVC_SETVIEW 5
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcSetView {
     pub entity_index: i16,
 }
 
 /// SVC_SOUND 6
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcSound {
     /// `[bool; 9]`
     pub flags: u16,
@@ -603,6 +746,7 @@ pub struct SvcSound {
     pub pitch: u8,
 }
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OriginCoord {
     pub int_flag: bool,
     pub fraction_flag: bool,
@@ -642,24 +786,28 @@ impl OriginCoord {
 
 /// SVC_TIME 7
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcTime {
     pub time: f32,
 }
 
 /// SVC_PRINT 8
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcPrint {
     pub message: ByteString,
 }
 
 /// SVC_STUFFTEXT 9
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcStuffText {
     pub command: ByteString,
 }
 
 /// SVC_SETANGLE 10
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcSetAngle {
     pub pitch: i16,
     pub yaw: i16,
@@ -668,6 +816,7 @@ pub struct SvcSetAngle {
 
 /// SVC_SERVERINFO 11
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcServerInfo {
     pub protocol: i32,
     pub spawn_count: i32,
@@ -686,6 +835,7 @@ pub struct SvcServerInfo {
 
 /// SVC_LIGHTSTYLE 12
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcLightStyle {
     pub index: u8,
     pub light_info: ByteVec,
@@ -693,6 +843,7 @@ pub struct SvcLightStyle {
 
 /// SVC_UPDATEUSERINFO 13
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcUpdateUserInfo {
     pub index: u8,
     pub id: u32,
@@ -703,6 +854,7 @@ pub struct SvcUpdateUserInfo {
 
 /// SVC_DELTADESCRIPTION 14
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcDeltaDescription {
     pub name: ByteVec,
     pub total_fields: u16,
@@ -712,32 +864,39 @@ pub struct SvcDeltaDescription {
 
 /// SVC_CLIENTDATA 15
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcClientData {
     pub has_delta_update_mask: bool,
     /// `[bool; 8]`
     pub delta_update_mask: Option<u8>,
+    #[cfg_attr(feature = "serde", serde(serialize_with = "sorted_delta::serialize"))]
     pub client_data: Delta,
     pub weapon_data: Option<Vec<ClientDataWeaponData>>,
 }
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ClientDataWeaponData {
     /// `[bool; 6]`
     pub weapon_index: u8,
+    #[cfg_attr(feature = "serde", serde(serialize_with = "sorted_delta::serialize"))]
     pub weapon_data: Delta,
 }
 
 /// SVC_STOPSOUND 16
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcStopSound {
     pub entity_index: i16,
 }
 
 /// SVC_PINGS 17
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcPings {
     pub pings: Vec<PingS>,
 }
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PingS {
     pub has_ping_data: bool,
     pub player_id: Option<u8>,
@@ -747,6 +906,7 @@ pub struct PingS {
 
 /// SVC_PARTICLE 18
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcParticle {
     /// Vec3
     pub origin: Vec<i16>,
@@ -760,6 +920,7 @@ pub struct SvcParticle {
 
 /// SVC_SPAWNSTATIC 20
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcSpawnStatic {
     pub model_index: i16,
     pub sequence: i8,
@@ -779,9 +940,11 @@ pub struct SvcSpawnStatic {
 
 /// SVC_EVENTRELIABLE 21
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcEventReliable {
     /// `[bool; 10]`
     pub event_index: u16,
+    #[cfg_attr(feature = "serde", serde(serialize_with = "sorted_delta::serialize"))]
     pub event_args: Delta,
     pub has_fire_time: bool,
     /// `[bool; 16]`
@@ -790,14 +953,20 @@ pub struct SvcEventReliable {
 
 /// SVC_SPAWNBASELINE 22
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcSpawnBaseline {
     pub entities: Vec<EntityS>,
     // These members are not inside EntityS like cgdangelo/talent suggests.
     /// `[bool; 6]`
     pub total_extra_data: u8,
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "sorted_delta::serialize_vec")
+    )]
     pub extra_data: Vec<Delta>,
 }
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EntityS {
     // Goodies
     pub entity_index: u16,
@@ -806,11 +975,13 @@ pub struct EntityS {
     /// `[bool; 2]`
     pub type_: u8,
     // One delta for 3 types
+    #[cfg_attr(feature = "serde", serde(serialize_with = "sorted_delta::serialize"))]
     pub delta: Delta,
 }
 
 /// SVC_TEMPENTITY 23
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcTempEntity {
     pub entity_type: u8,
     pub entity: TempEntity,
@@ -818,6 +989,7 @@ pub struct SvcTempEntity {
 
 #[repr(u8)]
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TempEntity {
     /// `[u8; 24]`
     TeBeamPoints(TeBeamPoints) = 0,
@@ -1008,6 +1180,7 @@ impl TempEntity {
 
 /// TE_BEAMPOINTS 0
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeBeamPoints {
     /// `[i16; 3]`
     pub start_position: Vec<i16>,
@@ -1026,6 +1199,7 @@ pub struct TeBeamPoints {
 
 /// TE_BEAMENTPOINTS 1
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeBeamEntPoint {
     pub start_entity: i16,
     /// `[i16; 3]`
@@ -1043,6 +1217,7 @@ pub struct TeBeamEntPoint {
 
 /// TE_GUNSHOT 2
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeGunShot {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1050,6 +1225,7 @@ pub struct TeGunShot {
 
 /// TE_EXPLOSION 3
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeExplosion {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1061,6 +1237,7 @@ pub struct TeExplosion {
 
 /// TE_TAREXPLOSION 4
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeTarExplosion {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1068,6 +1245,7 @@ pub struct TeTarExplosion {
 
 /// TE_SMOKE 5
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeSmoke {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1078,6 +1256,7 @@ pub struct TeSmoke {
 
 /// TE_TRACER 6
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeTracer {
     /// `[i16; 3]`
     pub start_position: Vec<i16>,
@@ -1087,6 +1266,7 @@ pub struct TeTracer {
 
 /// TE_LIGHTNING 7
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeLightning {
     /// `[i16; 3]`
     pub start_position: Vec<i16>,
@@ -1100,6 +1280,7 @@ pub struct TeLightning {
 
 /// TE_BEAMENTS 8
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeBeamEnts {
     /// `[i16; 3]`
     pub start_entity: i16,
@@ -1116,6 +1297,7 @@ pub struct TeBeamEnts {
 
 /// TE_SPARKS 9
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeSparks {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1123,6 +1305,7 @@ pub struct TeSparks {
 
 /// TE_LAVASPLASH 10
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeLavaSplash {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1130,6 +1313,7 @@ pub struct TeLavaSplash {
 
 /// TE_TELEPORT 11
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeTeleport {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1137,6 +1321,7 @@ pub struct TeTeleport {
 
 /// TE_EXPLOSION2 12
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeExplosion2 {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1146,6 +1331,7 @@ pub struct TeExplosion2 {
 
 /// TE_BSPDECAL 13
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeBspDecal {
     /// `[u8; 8]`
     pub unknown1: ByteVec,
@@ -1156,6 +1342,7 @@ pub struct TeBspDecal {
 
 /// TE_IMPLOSION 14
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeImplosion {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1166,6 +1353,7 @@ pub struct TeImplosion {
 
 /// TE_SPRITETRAIL 15
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeSpriteTrail {
     /// `[i16; 3]`
     pub start_position: Vec<i16>,
@@ -1181,6 +1369,7 @@ pub struct TeSpriteTrail {
 
 /// TE_SPRITE 17
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeSprite {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1191,6 +1380,7 @@ pub struct TeSprite {
 
 /// TE_BEAMSPRITE 18
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeBeamSprite {
     /// `[i16; 3]`
     pub start_position: Vec<i16>,
@@ -1201,6 +1391,7 @@ pub struct TeBeamSprite {
 
 /// TE_BEAMTORUS 19
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeBeamTorus {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1218,6 +1409,7 @@ pub struct TeBeamTorus {
 
 /// TE_BEAMDISK 20
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeBeamDisk {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1235,6 +1427,7 @@ pub struct TeBeamDisk {
 
 /// TE_BEAMCYLINDER 21
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeBeamCylinder {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1252,6 +1445,7 @@ pub struct TeBeamCylinder {
 
 /// TE_BEAMFOLLOW 22
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeBeamFollow {
     pub start_entity: i16,
     pub sprite_index: i16,
@@ -1264,6 +1458,7 @@ pub struct TeBeamFollow {
 
 /// TE_GLOWSPRITE 23
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeGlowSprite {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1275,6 +1470,7 @@ pub struct TeGlowSprite {
 
 /// TE_BEAMRING 24
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeBeamRing {
     pub start_entity: i16,
     pub end_entity: i16,
@@ -1291,6 +1487,7 @@ pub struct TeBeamRing {
 
 /// TE_STREAKSPLASH 25
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeStreakSplash {
     /// `[i16; 3]`
     pub start_position: Vec<i16>,
@@ -1303,6 +1500,7 @@ pub struct TeStreakSplash {
 }
 /// TE_DLIGHT 27
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeDLight {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1315,6 +1513,7 @@ pub struct TeDLight {
 
 /// TE_ELIGHT 28
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeELight {
     pub entity_index: i16,
     /// `[i16; 3]`
@@ -1327,6 +1526,7 @@ pub struct TeELight {
 }
 /// TE_TEXTMESSAGE 29
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeTextMessage {
     pub channel: i8,
     pub x: i16,
@@ -1345,6 +1545,7 @@ pub struct TeTextMessage {
 
 /// TE_LINE 30
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeLine {
     /// `[i16; 3]`
     pub start_position: Vec<i16>,
@@ -1357,6 +1558,7 @@ pub struct TeLine {
 
 /// TE_BOX 31
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeBox {
     /// `[i16; 3]`
     pub start_position: Vec<i16>,
@@ -1369,12 +1571,14 @@ pub struct TeBox {
 
 /// TE_KILLBEAM 99
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeKillBeam {
     pub entity_index: i16,
 }
 
 /// TE_LARGEFUNNEL 100
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeLargeFunnel {
     /// `[i16; 3]`
     pub start_position: Vec<i16>,
@@ -1384,6 +1588,7 @@ pub struct TeLargeFunnel {
 
 /// TE_BLOODSTREAM 101
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeBloodStream {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1395,6 +1600,7 @@ pub struct TeBloodStream {
 
 /// TE_SHOWLINE 102
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeShowLine {
     /// `[i16; 3]`
     pub start_position: Vec<i16>,
@@ -1404,6 +1610,7 @@ pub struct TeShowLine {
 
 /// TE_BLOOD 103
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeBlood {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1415,6 +1622,7 @@ pub struct TeBlood {
 
 /// TE_DECAL 104
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeDecal {
     /// `[i16; 3]`
     pub positiion: Vec<i16>,
@@ -1424,6 +1632,7 @@ pub struct TeDecal {
 
 /// TE_FIZZ 105
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeFizz {
     pub entity_index: i16,
     pub model_index: i16,
@@ -1432,6 +1641,7 @@ pub struct TeFizz {
 
 /// TE_MODEL 106
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeModel {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1445,6 +1655,7 @@ pub struct TeModel {
 
 /// TE_EXPLODEMODEL 107
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeExplodeModel {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1457,6 +1668,7 @@ pub struct TeExplodeModel {
 
 /// TE_BREAKMODEL 108
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeBreakModel {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1473,6 +1685,7 @@ pub struct TeBreakModel {
 
 /// TE_GUNSHOTDECAL 109
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeGunshotDecal {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1482,6 +1695,7 @@ pub struct TeGunshotDecal {
 
 /// TE_SPRITESPRAY 110
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeSpriteSpray {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1495,6 +1709,7 @@ pub struct TeSpriteSpray {
 
 /// TE_ARMORRICOCHET 111
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeArmorRicochet {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1503,6 +1718,7 @@ pub struct TeArmorRicochet {
 
 /// TE_PLAYERDECAL 112
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TePlayerDecal {
     pub player_index: u8,
     /// `[i16; 3]`
@@ -1513,6 +1729,7 @@ pub struct TePlayerDecal {
 
 /// TE_BUBBLES 113
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeBubbles {
     /// `[i16; 3]`
     pub min_start_positition: Vec<i16>,
@@ -1526,6 +1743,7 @@ pub struct TeBubbles {
 
 /// TE_BUBBLETRAIL 114
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeBubbleTrail {
     /// `[i16; 3]`
     pub min_start_positition: Vec<i16>,
@@ -1539,6 +1757,7 @@ pub struct TeBubbleTrail {
 
 /// TE_BLOODSPRITE 115
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeBloodSprite {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1550,6 +1769,7 @@ pub struct TeBloodSprite {
 
 /// TE_WORLDDECAL 116
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeWorldDecal {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1558,6 +1778,7 @@ pub struct TeWorldDecal {
 
 /// TE_WORLDDECALHIGH 117
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeWorldDecalHigh {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1566,6 +1787,7 @@ pub struct TeWorldDecalHigh {
 
 /// TE_DECALHIGH 118
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeDecalHigh {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1575,6 +1797,7 @@ pub struct TeDecalHigh {
 
 /// TE_PROJECTILE 119
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeProjectile {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1587,6 +1810,7 @@ pub struct TeProjectile {
 
 /// TE_SPRAY 120
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeSpray {
     /// `[i16; 3]`
     pub position: Vec<i16>,
@@ -1600,6 +1824,7 @@ pub struct TeSpray {
 
 /// TE_PLAYERSPRITES 121
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TePlayerSprites {
     pub entity_index: i16,
     pub model_index: i16,
@@ -1609,6 +1834,7 @@ pub struct TePlayerSprites {
 
 /// TE_PARTICLEBURST 122
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeParticleBurst {
     /// `[i16; 3]`
     pub origin: Vec<i16>,
@@ -1619,6 +1845,7 @@ pub struct TeParticleBurst {
 
 /// TE_FIREFIELD 123
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeFireField {
     /// `[i16; 3]`
     pub origin: Vec<i16>,
@@ -1631,6 +1858,7 @@ pub struct TeFireField {
 
 /// TE_PLAYERATTACHMENT 124
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TePlayerAttachment {
     pub entity_index: u8,
     pub scale: i16,
@@ -1640,12 +1868,14 @@ pub struct TePlayerAttachment {
 
 /// TE_KILLPLAYERATTACHMENT 125
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeKillPlayerAttachment {
     pub entity_index: u8,
 }
 
 /// TE_MULTIGUNSHOT 126
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeMultigunShot {
     /// `[i16; 3]`
     pub origin: Vec<i16>,
@@ -1659,6 +1889,7 @@ pub struct TeMultigunShot {
 
 /// TE_USERTRACER 127
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TeUserTracer {
     /// `[i16; 3]`
     pub origin: Vec<i16>,
@@ -1671,18 +1902,21 @@ pub struct TeUserTracer {
 
 /// SVC_SETPAUSE 24
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcSetPause {
     pub is_paused: i8,
 }
 
 /// SVC_SIGNONNUM 25
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcSignOnNum {
     pub sign: i8,
 }
 
 /// SVC_CENTERPRINT 26
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcCenterPrint {
     pub message: ByteVec,
 }
@@ -1693,6 +1927,7 @@ pub struct SvcCenterPrint {
 
 /// SVC_SPAWNSTATICSOUND 29
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcSpawnStaticSound {
     // Vec3
     pub origin: Vec<i16>,
@@ -1708,12 +1943,14 @@ pub struct SvcSpawnStaticSound {
 
 /// SVC_FINALE 31
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcFinale {
     pub text: ByteVec,
 }
 
 /// SVC_CDTRACK 32
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcCdTrack {
     pub track: i8,
     pub loop_track: i8,
@@ -1721,6 +1958,7 @@ pub struct SvcCdTrack {
 
 /// SVC_RESTORE 33
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcRestore {
     pub save_name: ByteVec,
     pub map_count: u8,
@@ -1729,12 +1967,14 @@ pub struct SvcRestore {
 
 /// SVC_CUTSCENE 34
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcCutscene {
     pub text: ByteVec,
 }
 
 /// SVC_WEAPONANIM 35
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcWeaponAnim {
     pub sequence_number: i8,
     pub weapon_model_body_group: i8,
@@ -1742,6 +1982,7 @@ pub struct SvcWeaponAnim {
 
 /// SVC_DECALNAME 36
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcDecalName {
     pub position_index: u8,
     pub decal_name: ByteVec,
@@ -1749,18 +1990,21 @@ pub struct SvcDecalName {
 
 /// SVC_ROOMTYPE 37
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcRoomType {
     pub room_type: u16,
 }
 
 /// SVC_ADDANGLE 38
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcAddAngle {
     pub angle_to_add: i16,
 }
 
 /// SVC_NEWUSERMSG 39
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcNewUserMsg {
     pub index: u8,
     // weird but it's for consistency
@@ -1771,12 +2015,14 @@ pub struct SvcNewUserMsg {
 
 /// SVC_PACKETENTITIES 40
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcPacketEntities {
     /// `[bool; 16]`
     pub entity_count: u16,
     pub entity_states: Vec<EntityState>,
 }
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EntityState {
     pub entity_index: u16,
     pub increment_entity_number: bool,
@@ -1789,11 +2035,13 @@ pub struct EntityState {
     pub has_baseline_index: bool,
     /// `[bool; 6]`
     pub baseline_index: Option<u8>,
+    #[cfg_attr(feature = "serde", serde(serialize_with = "sorted_delta::serialize"))]
     pub delta: Delta,
 }
 
 /// SVC_DELTAPACKETENTITIES 41
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcDeltaPacketEntities {
     /// `[bool; 16]`
     pub entity_count: u16,
@@ -1802,6 +2050,7 @@ pub struct SvcDeltaPacketEntities {
     pub entity_states: Vec<EntityStateDelta>,
 }
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EntityStateDelta {
     /// `[bool; 11]` but do u16 because arithmetic.
     pub entity_index: u16,
@@ -1813,6 +2062,10 @@ pub struct EntityStateDelta {
     pub entity_index_difference: Option<u8>,
     // Need to be optional because if remove is true then it won't have delta.
     pub has_custom_delta: Option<bool>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "sorted_delta::serialize_option")
+    )]
     pub delta: Option<Delta>,
 }
 
@@ -1820,6 +2073,7 @@ pub struct EntityStateDelta {
 
 /// SVC_RESOURCELIST 43
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcResourceList {
     /// `[bool; 12]`
     pub resource_count: u16,
@@ -1827,6 +2081,7 @@ pub struct SvcResourceList {
     pub consistencies: Vec<Consistency>,
 }
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Resource {
     /// `[bool; 4]`
     pub type_: u8,
@@ -1845,6 +2100,7 @@ pub struct Resource {
     pub extra_info: Option<[u8; 32]>,
 }
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Consistency {
     pub is_short_index: Option<bool>,
     /// `[bool; 5]`
@@ -1855,6 +2111,7 @@ pub struct Consistency {
 
 /// SVC_NEWMOVEVARS 44
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcNewMovevars {
     pub gravity: f32,
     pub stop_speed: f32,
@@ -1884,6 +2141,7 @@ pub struct SvcNewMovevars {
 
 /// SVC_RESOURCEREQUEST 45
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcResourceRequest {
     pub spawn_count: i32,
     pub unknown: Vec<u8>,
@@ -1891,6 +2149,7 @@ pub struct SvcResourceRequest {
 
 /// SVC_CUSTOMIZATION 46
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcCustomization {
     pub player_index: u8,
     pub type_: u8,
@@ -1904,6 +2163,7 @@ pub struct SvcCustomization {
 
 /// SVC_CROSSHAIRANGLE 47
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcCrosshairAngle {
     pub pitch: i16,
     pub yaw: i16,
@@ -1911,6 +2171,7 @@ pub struct SvcCrosshairAngle {
 
 /// SVC_SOUNDFADE 48
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcSoundFade {
     pub initial_percent: u8,
     pub hold_time: u8,
@@ -1920,12 +2181,14 @@ pub struct SvcSoundFade {
 
 /// SVC_FILETXFERFAILED 49
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcFileTxferFailed {
     pub file_name: ByteVec,
 }
 
 /// SVC_HLTV 50
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcHltv {
     pub mode: u8,
 }
@@ -1936,6 +2199,7 @@ pub struct SvcHltv {
 ///
 /// https://github.com/ValveSoftware/halflife/blob/b1b5cf5892918535619b2937bb927e46cb097ba1/cl_dll/hud_spectator.cpp#L682
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcDirector {
     pub length: u8,
     pub command: u8,
@@ -1947,6 +2211,7 @@ pub struct SvcDirector {
 
 /// SVC_VOINCEINIT 52
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcVoiceInit {
     pub codec_name: ByteVec,
     pub quality: i8,
@@ -1954,6 +2219,7 @@ pub struct SvcVoiceInit {
 
 /// SVC_VOICEDATA 53
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcVoiceData {
     pub player_index: u8,
     pub size: u16,
@@ -1962,6 +2228,7 @@ pub struct SvcVoiceData {
 
 /// SVC_SENDEXTRAINFO 54
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcSendExtraInfo {
     pub fallback_dir: ByteVec,
     pub can_cheat: u8,
@@ -1969,24 +2236,28 @@ pub struct SvcSendExtraInfo {
 
 /// SVC_TIMESCALE 55
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcTimeScale {
     pub time_scale: f32,
 }
 
 /// SVC_RESOURCELOCATION 56
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcResourceLocation {
     pub download_url: ByteVec,
 }
 
 /// SVC_SENDCVARVALUE 57
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcSendCvarValue {
     pub name: ByteString,
 }
 
 /// SVC_SENDCVARVALUE2 58
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SvcSendCvarValue2 {
     pub request_id: u32,
     pub name: ByteString,
@@ -2059,3 +2330,138 @@ impl From<ByteString> for Vec<u8> {
         value.0
     }
 }
+
+/// Writes [`Delta`] with its fields in order, so the output is the same every time.
+#[cfg(feature = "serde")]
+mod sorted_delta {
+    use std::collections::BTreeMap;
+
+    use serde::{Serialize, Serializer};
+
+    use super::{Delta, DeltaValue};
+
+    fn sorted(delta: &Delta) -> BTreeMap<&String, &DeltaValue> {
+        delta.iter().collect()
+    }
+
+    pub fn serialize<S: Serializer>(delta: &Delta, serializer: S) -> Result<S::Ok, S::Error> {
+        sorted(delta).serialize(serializer)
+    }
+
+    pub fn serialize_option<S: Serializer>(
+        delta: &Option<Delta>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        delta.as_ref().map(sorted).serialize(serializer)
+    }
+
+    pub fn serialize_vec<S: Serializer>(
+        deltas: &[Delta],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let deltas: Vec<_> = deltas.iter().map(sorted).collect();
+        deltas.serialize(serializer)
+    }
+}
+
+/// In human readable formats, written as a string when the bytes are valid UTF-8, null terminator
+/// and padding included, and as bytes otherwise, so nothing is lost either way. Other formats,
+/// which cannot tell the two apart when reading, always get bytes.
+#[cfg(feature = "serde")]
+impl serde::Serialize for ByteString {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if !serializer.is_human_readable() {
+            return serializer.serialize_bytes(&self.0);
+        }
+
+        match from_utf8(&self.0) {
+            Ok(string) => serializer.serialize_str(string),
+            Err(_) => serializer.serialize_bytes(&self.0),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ByteString {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ByteStringVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for ByteStringVisitor {
+            type Value = ByteString;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a string or bytes")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<ByteString, E> {
+                Ok(value.into())
+            }
+
+            fn visit_bytes<E: serde::de::Error>(self, value: &[u8]) -> Result<ByteString, E> {
+                Ok(value.into())
+            }
+
+            fn visit_byte_buf<E: serde::de::Error>(self, value: Vec<u8>) -> Result<ByteString, E> {
+                Ok(value.into())
+            }
+
+            // formats without bytes, such as JSON, write them as a sequence
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> Result<ByteString, A::Error> {
+                let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte);
+                }
+
+                Ok(bytes.into())
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(ByteStringVisitor)
+        } else {
+            deserializer.deserialize_byte_buf(ByteStringVisitor)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn byte_string_serde() {
+        let text = ByteString::from("maps/crossfire.bsp\0\0\0");
+        let json = serde_json::to_string(&text).unwrap();
+
+        assert_eq!(json, r#""maps/crossfire.bsp\u0000\u0000\u0000""#);
+        assert_eq!(serde_json::from_str::<ByteString>(&json).unwrap().0, text.0);
+
+        let bytes = ByteString::from(vec![b'a', 0xff, 0]);
+        let json = serde_json::to_string(&bytes).unwrap();
+
+        assert_eq!(json, "[97,255,0]");
+        assert_eq!(
+            serde_json::from_str::<ByteString>(&json).unwrap().0,
+            bytes.0
+        );
+    }
+
+    #[test]
+    fn byte_string_binary() {
+        for byte_string in [
+            ByteString::from("maps/crossfire.bsp\0\0\0"),
+            ByteString::from(vec![b'a', 0xff, 0]),
+        ] {
+            // followed by another field so a misread length shows
+            let bytes = postcard::to_allocvec(&(&byte_string, 7u8)).unwrap();
+            let (read, after): (ByteString, u8) = postcard::from_bytes(&bytes).unwrap();
+
+            assert_eq!(read.0, byte_string.0);
+            assert_eq!(after, 7);
+        }
+    }
+}
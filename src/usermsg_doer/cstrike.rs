@@ -0,0 +1,188 @@
+//! Counter-Strike and Condition Zero user messages.
+//!
+//! Messages that keep the Half-Life layout are re-exported from [`super::valve`].
+use nom::{
+    combinator::map,
+    number::complete::{le_i16, le_u8},
+    sequence::tuple,
+};
+
+use crate::{byte_writer::ByteWriter, nom_helper::null_string};
+
+pub use super::valve::{SayText, ScoreInfo, TeamInfo, TextMsg};
+
+use super::*;
+
+user_msg_pack! {
+    /// Every Counter-Strike user message with a typed decoder.
+    CstrikeMessage {
+        DeathMsg,
+        TextMsg,
+        SayText,
+        SendAudio,
+        TeamScore,
+        TeamInfo,
+        ScoreInfo,
+        RoundTime,
+    }
+}
+
+/// Kill feed entry.
+#[derive(Debug, Clone)]
+pub struct DeathMsg {
+    /// Entity index, 0 is world.
+    pub killer: u8,
+    /// Entity index.
+    pub victim: u8,
+    pub headshot: u8,
+    pub weapon: ByteString,
+}
+
+impl UserMsgDoer for DeathMsg {
+    const NAME: &'static str = "DeathMsg";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(
+            tuple((le_u8, le_u8, le_u8, null_string)),
+            |(killer, victim, headshot, weapon)| Self {
+                killer,
+                victim,
+                headshot,
+                weapon: weapon.into(),
+            },
+        )(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        let mut writer = ByteWriter::new();
+
+        writer.append_u8(self.killer);
+        writer.append_u8(self.victim);
+        writer.append_u8(self.headshot);
+        writer.append_u8_slice(self.weapon.as_slice());
+
+        writer.data
+    }
+}
+
+/// Radio sound, such as `%!MRAD_BOMBPL`.
+#[derive(Debug, Clone)]
+pub struct SendAudio {
+    /// Entity index of the sender, 0 is server.
+    pub sender: u8,
+    pub code: ByteString,
+    pub pitch: i16,
+}
+
+impl UserMsgDoer for SendAudio {
+    const NAME: &'static str = "SendAudio";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(
+            tuple((le_u8, null_string, le_i16)),
+            |(sender, code, pitch)| Self {
+                sender,
+                code: code.into(),
+                pitch,
+            },
+        )(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        let mut writer = ByteWriter::new();
+
+        writer.append_u8(self.sender);
+        writer.append_u8_slice(self.code.as_slice());
+        writer.append_i16(self.pitch);
+
+        writer.data
+    }
+}
+
+/// Round score of a team, `TERRORIST` or `CT`.
+#[derive(Debug, Clone)]
+pub struct TeamScore {
+    pub team_name: ByteString,
+    pub score: i16,
+}
+
+impl UserMsgDoer for TeamScore {
+    const NAME: &'static str = "TeamScore";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(tuple((null_string, le_i16)), |(team_name, score)| Self {
+            team_name: team_name.into(),
+            score,
+        })(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        let mut writer = ByteWriter::new();
+
+        writer.append_u8_slice(self.team_name.as_slice());
+        writer.append_i16(self.score);
+
+        writer.data
+    }
+}
+
+/// Round timer in seconds. Sent when freeze time starts and again when it ends.
+#[derive(Debug, Clone)]
+pub struct RoundTime {
+    pub time: i16,
+}
+
+impl UserMsgDoer for RoundTime {
+    const NAME: &'static str = "RoundTime";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(le_i16, |time| Self { time })(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        let mut writer = ByteWriter::new();
+
+        writer.append_i16(self.time);
+
+        writer.data
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn write_decode() {
+        let death = DeathMsg {
+            killer: 1,
+            victim: 2,
+            headshot: 1,
+            weapon: "ak47\0".into(),
+        };
+
+        let message = death.to_user_message(83);
+        let decoded = message.decode::<DeathMsg>().unwrap();
+
+        assert_eq!(decoded.headshot, 1);
+        assert_eq!(decoded.weapon.to_str().unwrap(), "ak47");
+        assert_eq!(decoded.write(), message.data);
+    }
+
+    #[test]
+    fn pack() {
+        let message = SendAudio {
+            sender: 0,
+            code: "%!MRAD_BOMBPL\0".into(),
+            pitch: 100,
+        }
+        .to_user_message(100);
+
+        let Some(CstrikeMessage::SendAudio(audio)) = CstrikeMessage::parse(&message) else {
+            panic!("not a radio message");
+        };
+
+        assert_eq!(audio.code.to_str().unwrap(), "%!MRAD_BOMBPL");
+        assert!(message.decode::<DeathMsg>().is_none());
+    }
+}
@@ -0,0 +1,458 @@
+//! Day of Defeat user messages.
+//!
+//! Layouts follow the AMX Mod X Day of Defeat event reference.
+use nom::{
+    combinator::map,
+    number::complete::{le_i16, le_u8},
+    sequence::tuple,
+};
+
+use crate::{byte_writer::ByteWriter, nom_helper::null_string};
+
+use super::*;
+
+user_msg_pack! {
+    /// Every Day of Defeat user message with a typed decoder.
+    DodMessage {
+        ObjScore,
+        PClass,
+        PTeam,
+        PStatus,
+        ScoreShort,
+        Frags,
+        TeamScore,
+        RoundState,
+        CapMsg,
+        ClanTimer,
+        WaveTime,
+        CurWeapon,
+        DeathMsg,
+    }
+}
+
+/// Objective score of a player.
+#[derive(Debug, Clone)]
+pub struct ObjScore {
+    pub player_index: u8,
+    pub score: i16,
+}
+
+impl UserMsgDoer for ObjScore {
+    const NAME: &'static str = "ObjScore";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(tuple((le_u8, le_i16)), |(player_index, score)| Self {
+            player_index,
+            score,
+        })(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        let mut writer = ByteWriter::new();
+
+        writer.append_u8(self.player_index);
+        writer.append_i16(self.score);
+
+        writer.data
+    }
+}
+
+/// Class selection of a player.
+#[derive(Debug, Clone)]
+pub struct PClass {
+    pub player_index: u8,
+    pub class: u8,
+}
+
+impl UserMsgDoer for PClass {
+    const NAME: &'static str = "PClass";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(tuple((le_u8, le_u8)), |(player_index, class)| Self {
+            player_index,
+            class,
+        })(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        vec![self.player_index, self.class]
+    }
+}
+
+/// Team of a player.
+#[derive(Debug, Clone)]
+pub struct PTeam {
+    pub player_index: u8,
+    pub team: u8,
+}
+
+impl UserMsgDoer for PTeam {
+    const NAME: &'static str = "PTeam";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(tuple((le_u8, le_u8)), |(player_index, team)| Self {
+            player_index,
+            team,
+        })(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        vec![self.player_index, self.team]
+    }
+}
+
+/// Alive or dead status of a player on the scoreboard.
+#[derive(Debug, Clone)]
+pub struct PStatus {
+    pub player_index: u8,
+    pub status: u8,
+}
+
+impl UserMsgDoer for PStatus {
+    const NAME: &'static str = "PStatus";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(tuple((le_u8, le_u8)), |(player_index, status)| Self {
+            player_index,
+            status,
+        })(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        vec![self.player_index, self.status]
+    }
+}
+
+/// Scoreboard line of a player.
+#[derive(Debug, Clone)]
+pub struct ScoreShort {
+    pub player_index: u8,
+    pub score: i16,
+    pub kills: i16,
+    pub deaths: i16,
+}
+
+impl UserMsgDoer for ScoreShort {
+    const NAME: &'static str = "ScoreShort";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(
+            tuple((le_u8, le_i16, le_i16, le_i16)),
+            |(player_index, score, kills, deaths)| Self {
+                player_index,
+                score,
+                kills,
+                deaths,
+            },
+        )(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        let mut writer = ByteWriter::new();
+
+        writer.append_u8(self.player_index);
+        writer.append_i16(self.score);
+        writer.append_i16(self.kills);
+        writer.append_i16(self.deaths);
+
+        writer.data
+    }
+}
+
+/// Kill count of a player.
+#[derive(Debug, Clone)]
+pub struct Frags {
+    pub player_index: u8,
+    pub frags: i16,
+}
+
+impl UserMsgDoer for Frags {
+    const NAME: &'static str = "Frags";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(tuple((le_u8, le_i16)), |(player_index, frags)| Self {
+            player_index,
+            frags,
+        })(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        let mut writer = ByteWriter::new();
+
+        writer.append_u8(self.player_index);
+        writer.append_i16(self.frags);
+
+        writer.data
+    }
+}
+
+/// Score of a team. Allies are 1 and Axis are 2.
+#[derive(Debug, Clone)]
+pub struct TeamScore {
+    pub team: u8,
+    pub score: i16,
+}
+
+impl UserMsgDoer for TeamScore {
+    const NAME: &'static str = "TeamScore";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(tuple((le_u8, le_i16)), |(team, score)| Self { team, score })(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        let mut writer = ByteWriter::new();
+
+        writer.append_u8(self.team);
+        writer.append_i16(self.score);
+
+        writer.data
+    }
+}
+
+/// Round state. 0 is reset, 1 is Allies win and 2 is Axis win.
+#[derive(Debug, Clone)]
+pub struct RoundState {
+    pub state: u8,
+}
+
+impl UserMsgDoer for RoundState {
+    const NAME: &'static str = "RoundState";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(le_u8, |state| Self { state })(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        vec![self.state]
+    }
+}
+
+/// A player captured a control point.
+#[derive(Debug, Clone)]
+pub struct CapMsg {
+    pub player_index: u8,
+    pub point_name: ByteString,
+    pub team: u8,
+}
+
+impl UserMsgDoer for CapMsg {
+    const NAME: &'static str = "CapMsg";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(
+            tuple((le_u8, null_string, le_u8)),
+            |(player_index, point_name, team)| Self {
+                player_index,
+                point_name: point_name.into(),
+                team,
+            },
+        )(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        let mut writer = ByteWriter::new();
+
+        writer.append_u8(self.player_index);
+        writer.append_u8_slice(self.point_name.as_slice());
+        writer.append_u8(self.team);
+
+        writer.data
+    }
+}
+
+/// Clan match timer in seconds.
+#[derive(Debug, Clone)]
+pub struct ClanTimer {
+    pub time: u8,
+}
+
+impl UserMsgDoer for ClanTimer {
+    const NAME: &'static str = "ClanTimer";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(le_u8, |time| Self { time })(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        vec![self.time]
+    }
+}
+
+/// Seconds until the next respawn wave.
+#[derive(Debug, Clone)]
+pub struct WaveTime {
+    pub time: u8,
+}
+
+impl UserMsgDoer for WaveTime {
+    const NAME: &'static str = "WaveTime";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(le_u8, |time| Self { time })(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        vec![self.time]
+    }
+}
+
+/// Weapon currently held by the recording player.
+#[derive(Debug, Clone)]
+pub struct CurWeapon {
+    pub is_active: u8,
+    pub weapon_id: u8,
+    pub clip: u8,
+}
+
+impl UserMsgDoer for CurWeapon {
+    const NAME: &'static str = "CurWeapon";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(
+            tuple((le_u8, le_u8, le_u8)),
+            |(is_active, weapon_id, clip)| Self {
+                is_active,
+                weapon_id,
+                clip,
+            },
+        )(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        vec![self.is_active, self.weapon_id, self.clip]
+    }
+}
+
+/// Kill feed entry. Unlike other mods, the weapon is an id instead of a name.
+#[derive(Debug, Clone)]
+pub struct DeathMsg {
+    pub killer: u8,
+    pub victim: u8,
+    pub weapon_id: u8,
+}
+
+impl UserMsgDoer for DeathMsg {
+    const NAME: &'static str = "DeathMsg";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(
+            tuple((le_u8, le_u8, le_u8)),
+            |(killer, victim, weapon_id)| Self {
+                killer,
+                victim,
+                weapon_id,
+            },
+        )(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        vec![self.killer, self.victim, self.weapon_id]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Decodes the message through the pack and checks it writes the same bytes.
+    fn round_trip<T: UserMsgDoer>(message: T) -> DodMessage {
+        let user_message = message.to_user_message(70);
+        let parsed = DodMessage::parse(&user_message).unwrap();
+
+        assert_eq!(parsed.name(), T::NAME);
+        assert_eq!(parsed.write(), user_message.data);
+
+        parsed
+    }
+
+    #[test]
+    fn parse_bytes() {
+        let message = UserMessage {
+            id: 70,
+            name: ByteString::from("ObjScore").padded(16),
+            data: vec![3, 0x10, 0x01],
+        };
+
+        let score = message.decode::<ObjScore>().unwrap();
+
+        assert_eq!(score.player_index, 3);
+        assert_eq!(score.score, 0x110);
+
+        // trailing bytes do not belong to the message
+        let message = UserMessage {
+            data: vec![3, 0x10, 0x01, 0],
+            ..message
+        };
+
+        assert!(message.decode::<ObjScore>().is_none());
+    }
+
+    #[test]
+    fn round_trips() {
+        round_trip(ObjScore {
+            player_index: 1,
+            score: -2,
+        });
+        round_trip(PClass {
+            player_index: 1,
+            class: 4,
+        });
+        round_trip(PTeam {
+            player_index: 2,
+            team: 1,
+        });
+        round_trip(PStatus {
+            player_index: 2,
+            status: 1,
+        });
+        round_trip(ScoreShort {
+            player_index: 5,
+            score: 10,
+            kills: 7,
+            deaths: 3,
+        });
+        round_trip(Frags {
+            player_index: 5,
+            frags: 7,
+        });
+        round_trip(RoundState { state: 2 });
+        round_trip(ClanTimer { time: 60 });
+        round_trip(WaveTime { time: 12 });
+        round_trip(CurWeapon {
+            is_active: 1,
+            weapon_id: 9,
+            clip: 30,
+        });
+
+        let DodMessage::TeamScore(score) = round_trip(TeamScore {
+            team: 2,
+            score: 300,
+        }) else {
+            panic!("not a team score");
+        };
+
+        assert_eq!((score.team, score.score), (2, 300));
+
+        let DodMessage::CapMsg(capture) = round_trip(CapMsg {
+            player_index: 4,
+            point_name: "flag_bridge\0".into(),
+            team: 1,
+        }) else {
+            panic!("not a capture");
+        };
+
+        assert_eq!(capture.point_name.to_str().unwrap(), "flag_bridge");
+        assert_eq!(capture.team, 1);
+
+        let DodMessage::DeathMsg(death) = round_trip(DeathMsg {
+            killer: 1,
+            victim: 2,
+            weapon_id: 8,
+        }) else {
+            panic!("not a kill");
+        };
+
+        assert_eq!(death.weapon_id, 8);
+    }
+}
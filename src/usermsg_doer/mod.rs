@@ -0,0 +1,160 @@
+//! Typed decoders for user messages.
+//!
+//! User messages are registered by the game dll through [`SvcNewUserMsg`] so their layout depends
+//! on the mod. [`UserMessage`] only stores the raw bytes, these decoders interpret them by name.
+//!
+//! Mod specific decoders are grouped in packs and some of them are behind cargo features.
+//!
+//! # Example
+//!
+//! ```ignore
+//! if let NetMessage::UserMessage(message) = netmsg {
+//!     if let Some(dod::DodMessage::ObjScore(score)) = dod::DodMessage::parse(message) {
+//!         println!("{} {}", score.player_index, score.score);
+//!     }
+//! }
+//! ```
+use nom::combinator::all_consuming;
+
+use crate::{
+    nom_helper::NomResult,
+    types::{ByteString, ByteVec, DemoState, SvcNewUserMsg, UserMessage},
+};
+
+pub mod cstrike;
+#[cfg(feature = "dod")]
+pub mod dod;
+#[cfg(feature = "tfc")]
+pub mod tfc;
+pub mod valve;
+
+/// Same idea as [`crate::netmsg_doer::Doer`] but for user messages.
+///
+/// The message id is not known until the server registers it so it is not part of the message.
+pub trait UserMsgDoer {
+    /// Name registered with [`SvcNewUserMsg`], without null terminator.
+    const NAME: &'static str;
+
+    /// Parses [`UserMessage::data`], which does not include id and length.
+    fn parse(i: &[u8]) -> NomResult<'_, Self>
+    where
+        Self: Sized;
+
+    /// Writes the message data, without id and length.
+    fn write(&self) -> ByteVec;
+
+    /// Wraps the message into a [`UserMessage`] with the id registered for it.
+    ///
+    /// The id can be looked up with [`DemoState::find_user_message`].
+    fn to_user_message(&self, id: u8) -> UserMessage {
+        UserMessage {
+            id,
+            name: ByteString::from(Self::NAME).padded(16),
+            data: self.write(),
+        }
+    }
+}
+
+impl UserMessage {
+    /// Registered name of the message without null terminator.
+    ///
+    /// Returns an empty string if the message was never registered.
+    pub fn name_str(&self) -> &str {
+        self.name.to_str().unwrap_or_default()
+    }
+
+    /// Decodes the message data as `T` if the message is registered as [`UserMsgDoer::NAME`].
+    pub fn decode<T: UserMsgDoer>(&self) -> Option<T> {
+        if self.name_str() != T::NAME {
+            return None;
+        }
+
+        all_consuming(T::parse)(&self.data).ok().map(|(_, res)| res)
+    }
+}
+
+impl DemoState {
+    /// Finds the registration of a user message by its name.
+    pub fn find_user_message(&self, name: &str) -> Option<&SvcNewUserMsg> {
+        self.custom_messages
+            .values()
+            .find(|message| message.name.to_str().unwrap_or_default() == name)
+    }
+}
+
+/// Generates an enum over a set of [`UserMsgDoer`] so a pack can be decoded in one go.
+macro_rules! user_msg_pack {
+    ($(#[$meta:meta])* $pack:ident { $($msg:ident),* $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone)]
+        pub enum $pack {
+            $($msg($msg),)*
+        }
+
+        impl $pack {
+            /// Decodes the message if its registered name belongs to this pack.
+            pub fn parse(message: &$crate::types::UserMessage) -> Option<Self> {
+                let name = message.name_str();
+
+                $(
+                    if name == <$msg as $crate::usermsg_doer::UserMsgDoer>::NAME {
+                        return message.decode::<$msg>().map(Self::$msg);
+                    }
+                )*
+
+                None
+            }
+
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Self::$msg(_) => <$msg as $crate::usermsg_doer::UserMsgDoer>::NAME,)*
+                }
+            }
+
+            pub fn write(&self) -> $crate::types::ByteVec {
+                match self {
+                    $(Self::$msg(what) => $crate::usermsg_doer::UserMsgDoer::write(what),)*
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use user_msg_pack;
+
+#[cfg(test)]
+mod test {
+    use super::{
+        UserMsgDoer,
+        valve::{DeathMsg, TeamScore},
+    };
+
+    #[test]
+    fn write_decode() {
+        let death = DeathMsg {
+            killer: 1,
+            victim: 2,
+            weapon: "crowbar\0".into(),
+        };
+
+        let message = death.to_user_message(83);
+        let decoded = message.decode::<DeathMsg>().unwrap();
+
+        assert_eq!(message.name_str(), "DeathMsg");
+        assert_eq!(decoded.victim, 2);
+        assert_eq!(decoded.weapon.to_str().unwrap(), "crowbar");
+        assert_eq!(decoded.write(), message.data);
+    }
+
+    #[test]
+    fn decode_wrong_name() {
+        let message = TeamScore {
+            team_name: "Blue\0".into(),
+            frags: 1,
+            deaths: 0,
+        }
+        .to_user_message(100);
+
+        assert!(message.decode::<DeathMsg>().is_none());
+    }
+}
@@ -0,0 +1,299 @@
+//! Team Fortress Classic user messages.
+//!
+//! Layouts follow the AMX Mod X Team Fortress Classic event reference. Messages that keep the
+//! Half-Life layout are re-exported from [`super::valve`].
+use nom::{
+    combinator::map,
+    multi::{count, many0},
+    number::complete::{le_i16, le_u8},
+    sequence::tuple,
+};
+
+use crate::{byte_writer::ByteWriter, nom_helper::null_string};
+
+pub use super::valve::{DeathMsg, ScoreInfo, TeamInfo, TeamScore};
+
+use super::*;
+
+user_msg_pack! {
+    /// Every Team Fortress Classic user message with a typed decoder.
+    TfcMessage {
+        ScoreInfo,
+        TeamScore,
+        TeamNames,
+        TeamInfo,
+        ValClass,
+        Spectator,
+        Feign,
+        Detpack,
+        Concuss,
+        SecAmmoVal,
+        SecAmmoIcon,
+        DeathMsg,
+    }
+}
+
+/// Names of the teams playing on the map.
+#[derive(Debug, Clone)]
+pub struct TeamNames {
+    pub team_count: u8,
+    pub names: Vec<ByteString>,
+}
+
+impl UserMsgDoer for TeamNames {
+    const NAME: &'static str = "TeamNames";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        let (i, team_count) = le_u8(i)?;
+
+        map(
+            count(map(null_string, ByteString::from), team_count as usize),
+            move |names| Self { team_count, names },
+        )(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        let mut writer = ByteWriter::new();
+
+        writer.append_u8(self.team_count);
+        self.names
+            .iter()
+            .for_each(|name| writer.append_u8_slice(name.as_slice()));
+
+        writer.data
+    }
+}
+
+/// Bit field of selectable classes for each team, opening the class selection menu.
+#[derive(Debug, Clone)]
+pub struct ValClass {
+    pub classes: Vec<i16>,
+}
+
+impl UserMsgDoer for ValClass {
+    const NAME: &'static str = "ValClass";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(many0(le_i16), |classes| Self { classes })(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        let mut writer = ByteWriter::new();
+
+        self.classes
+            .iter()
+            .for_each(|class| writer.append_i16(*class));
+
+        writer.data
+    }
+}
+
+/// A player joins or leaves spectators.
+#[derive(Debug, Clone)]
+pub struct Spectator {
+    pub player_index: u8,
+    pub is_spectator: u8,
+}
+
+impl UserMsgDoer for Spectator {
+    const NAME: &'static str = "Spectator";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(tuple((le_u8, le_u8)), |(player_index, is_spectator)| Self {
+            player_index,
+            is_spectator,
+        })(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        vec![self.player_index, self.is_spectator]
+    }
+}
+
+/// Spy feigning death.
+#[derive(Debug, Clone)]
+pub struct Feign {
+    pub is_feigning: u8,
+}
+
+impl UserMsgDoer for Feign {
+    const NAME: &'static str = "Feign";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(le_u8, |is_feigning| Self { is_feigning })(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        vec![self.is_feigning]
+    }
+}
+
+/// Demoman setting a detpack.
+#[derive(Debug, Clone)]
+pub struct Detpack {
+    pub is_detpacking: u8,
+}
+
+impl UserMsgDoer for Detpack {
+    const NAME: &'static str = "Detpack";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(le_u8, |is_detpacking| Self { is_detpacking })(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        vec![self.is_detpacking]
+    }
+}
+
+/// Concussion effect strength.
+#[derive(Debug, Clone)]
+pub struct Concuss {
+    pub amount: u8,
+}
+
+impl UserMsgDoer for Concuss {
+    const NAME: &'static str = "Concuss";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(le_u8, |amount| Self { amount })(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        vec![self.amount]
+    }
+}
+
+/// Secondary ammo (grenades) count on the HUD.
+#[derive(Debug, Clone)]
+pub struct SecAmmoVal {
+    pub ammo_type: u8,
+    pub amount: u8,
+}
+
+impl UserMsgDoer for SecAmmoVal {
+    const NAME: &'static str = "SecAmmoVal";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(tuple((le_u8, le_u8)), |(ammo_type, amount)| Self {
+            ammo_type,
+            amount,
+        })(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        vec![self.ammo_type, self.amount]
+    }
+}
+
+/// Secondary ammo icon on the HUD.
+#[derive(Debug, Clone)]
+pub struct SecAmmoIcon {
+    pub icon: ByteString,
+}
+
+impl UserMsgDoer for SecAmmoIcon {
+    const NAME: &'static str = "SecAmmoIcon";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(null_string, |icon| Self { icon: icon.into() })(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        self.icon.0.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Decodes the message through the pack and checks it writes the same bytes.
+    fn round_trip<T: UserMsgDoer>(message: T) -> TfcMessage {
+        let user_message = message.to_user_message(80);
+        let parsed = TfcMessage::parse(&user_message).unwrap();
+
+        assert_eq!(parsed.name(), T::NAME);
+        assert_eq!(parsed.write(), user_message.data);
+
+        parsed
+    }
+
+    #[test]
+    fn parse_bytes() {
+        let message = UserMessage {
+            id: 80,
+            name: ByteString::from("TeamNames").padded(16),
+            data: b"\x02#Blue\0#Red\0".to_vec(),
+        };
+
+        let names = message.decode::<TeamNames>().unwrap();
+
+        assert_eq!(names.team_count, 2);
+        assert_eq!(names.names[1].to_str().unwrap(), "#Red");
+
+        // fewer names than the count
+        let message = UserMessage {
+            data: b"\x03#Blue\0#Red\0".to_vec(),
+            ..message
+        };
+
+        assert!(message.decode::<TeamNames>().is_none());
+    }
+
+    #[test]
+    fn round_trips() {
+        round_trip(ScoreInfo {
+            player_index: 1,
+            frags: 4,
+            deaths: 2,
+            class: 5,
+            team: 1,
+        });
+        round_trip(TeamScore {
+            team_name: "Blue\0".into(),
+            frags: 12,
+            deaths: 9,
+        });
+        round_trip(TeamInfo {
+            player_index: 2,
+            team_name: "Red\0".into(),
+        });
+        round_trip(Spectator {
+            player_index: 3,
+            is_spectator: 1,
+        });
+        round_trip(Feign { is_feigning: 1 });
+        round_trip(Detpack { is_detpacking: 1 });
+        round_trip(Concuss { amount: 100 });
+        round_trip(SecAmmoVal {
+            ammo_type: 1,
+            amount: 4,
+        });
+        round_trip(SecAmmoIcon {
+            icon: "grenade\0".into(),
+        });
+        round_trip(DeathMsg {
+            killer: 1,
+            victim: 2,
+            weapon: "rocket\0".into(),
+        });
+
+        let TfcMessage::TeamNames(names) = round_trip(TeamNames {
+            team_count: 2,
+            names: vec!["#Blue\0".into(), "#Red\0".into()],
+        }) else {
+            panic!("not team names");
+        };
+
+        assert_eq!(names.names.len(), 2);
+
+        let TfcMessage::ValClass(classes) = round_trip(ValClass {
+            classes: vec![0, -1, 0x7f, 0],
+        }) else {
+            panic!("not classes");
+        };
+
+        assert_eq!(classes.classes, [0, -1, 0x7f, 0]);
+    }
+}
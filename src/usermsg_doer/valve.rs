@@ -0,0 +1,234 @@
+//! Half-Life user messages that most mods inherit from the SDK.
+use nom::{
+    combinator::map,
+    multi::many0,
+    number::complete::{le_i16, le_u8},
+    sequence::tuple,
+};
+
+use crate::{byte_writer::ByteWriter, nom_helper::null_string};
+
+use super::*;
+
+user_msg_pack! {
+    /// Every Half-Life user message with a typed decoder.
+    ValveMessage {
+        DeathMsg,
+        TextMsg,
+        SayText,
+        TeamScore,
+        TeamInfo,
+        ScoreInfo,
+    }
+}
+
+/// Kill feed entry.
+#[derive(Debug, Clone)]
+pub struct DeathMsg {
+    /// Entity index, 0 is world.
+    pub killer: u8,
+    /// Entity index.
+    pub victim: u8,
+    pub weapon: ByteString,
+}
+
+impl UserMsgDoer for DeathMsg {
+    const NAME: &'static str = "DeathMsg";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(
+            tuple((le_u8, le_u8, null_string)),
+            |(killer, victim, weapon)| Self {
+                killer,
+                victim,
+                weapon: weapon.into(),
+            },
+        )(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        let mut writer = ByteWriter::new();
+
+        writer.append_u8(self.killer);
+        writer.append_u8(self.victim);
+        writer.append_u8_slice(self.weapon.as_slice());
+
+        writer.data
+    }
+}
+
+/// Text to print somewhere, usually a localisation token with up to 4 arguments.
+#[derive(Debug, Clone)]
+pub struct TextMsg {
+    /// 1 is notify, 2 is console, 3 is chat and 4 is center.
+    pub destination: u8,
+    pub message: ByteString,
+    pub params: Vec<ByteString>,
+}
+
+impl UserMsgDoer for TextMsg {
+    const NAME: &'static str = "TextMsg";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(
+            tuple((le_u8, null_string, many0(null_string))),
+            |(destination, message, params)| Self {
+                destination,
+                message: message.into(),
+                params: params.into_iter().map(ByteString::from).collect(),
+            },
+        )(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        let mut writer = ByteWriter::new();
+
+        writer.append_u8(self.destination);
+        writer.append_u8_slice(self.message.as_slice());
+        self.params
+            .iter()
+            .for_each(|param| writer.append_u8_slice(param.as_slice()));
+
+        writer.data
+    }
+}
+
+/// Chat message.
+///
+/// Half-Life sends the formatted line directly while Counter-Strike sends a format token with
+/// arguments in `params`.
+#[derive(Debug, Clone)]
+pub struct SayText {
+    /// Entity index of the speaker, 0 is server.
+    pub sender: u8,
+    pub message: ByteString,
+    pub params: Vec<ByteString>,
+}
+
+impl UserMsgDoer for SayText {
+    const NAME: &'static str = "SayText";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(
+            tuple((le_u8, null_string, many0(null_string))),
+            |(sender, message, params)| Self {
+                sender,
+                message: message.into(),
+                params: params.into_iter().map(ByteString::from).collect(),
+            },
+        )(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        let mut writer = ByteWriter::new();
+
+        writer.append_u8(self.sender);
+        writer.append_u8_slice(self.message.as_slice());
+        self.params
+            .iter()
+            .for_each(|param| writer.append_u8_slice(param.as_slice()));
+
+        writer.data
+    }
+}
+
+/// Score of a team.
+#[derive(Debug, Clone)]
+pub struct TeamScore {
+    pub team_name: ByteString,
+    pub frags: i16,
+    pub deaths: i16,
+}
+
+impl UserMsgDoer for TeamScore {
+    const NAME: &'static str = "TeamScore";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(
+            tuple((null_string, le_i16, le_i16)),
+            |(team_name, frags, deaths)| Self {
+                team_name: team_name.into(),
+                frags,
+                deaths,
+            },
+        )(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        let mut writer = ByteWriter::new();
+
+        writer.append_u8_slice(self.team_name.as_slice());
+        writer.append_i16(self.frags);
+        writer.append_i16(self.deaths);
+
+        writer.data
+    }
+}
+
+/// Team of a player.
+#[derive(Debug, Clone)]
+pub struct TeamInfo {
+    pub player_index: u8,
+    pub team_name: ByteString,
+}
+
+impl UserMsgDoer for TeamInfo {
+    const NAME: &'static str = "TeamInfo";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(tuple((le_u8, null_string)), |(player_index, team_name)| {
+            Self {
+                player_index,
+                team_name: team_name.into(),
+            }
+        })(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        let mut writer = ByteWriter::new();
+
+        writer.append_u8(self.player_index);
+        writer.append_u8_slice(self.team_name.as_slice());
+
+        writer.data
+    }
+}
+
+/// Scoreboard line of a player.
+#[derive(Debug, Clone)]
+pub struct ScoreInfo {
+    pub player_index: u8,
+    pub frags: i16,
+    pub deaths: i16,
+    pub class: i16,
+    pub team: i16,
+}
+
+impl UserMsgDoer for ScoreInfo {
+    const NAME: &'static str = "ScoreInfo";
+
+    fn parse(i: &[u8]) -> NomResult<'_, Self> {
+        map(
+            tuple((le_u8, le_i16, le_i16, le_i16, le_i16)),
+            |(player_index, frags, deaths, class, team)| Self {
+                player_index,
+                frags,
+                deaths,
+                class,
+                team,
+            },
+        )(i)
+    }
+
+    fn write(&self) -> ByteVec {
+        let mut writer = ByteWriter::new();
+
+        writer.append_u8(self.player_index);
+        writer.append_i16(self.frags);
+        writer.append_i16(self.deaths);
+        writer.append_i16(self.class);
+        writer.append_i16(self.team);
+
+        writer.data
+    }
+}
@@ -0,0 +1,266 @@
+//! Voice communication in a demo.
+//!
+//! [`SvcVoiceData`] packets are grouped per player into [`VoiceStream`]. A [`VoiceDecoder`] turns
+//! the codec payload into PCM which is then laid out on the demo time and written as WAV.
+//!
+//! The Speex decoder is behind the `speex` feature and builds the bundled libspeex 1.2.1 with the
+//! C compiler. SILK from Steam voice is not supported, it can be plugged in by implementing
+//! [`VoiceDecoder`].
+//!
+//! # Example
+//!
+//! ```ignore
+//! let demo = open_demo("./src/tests/demotest.dem").unwrap();
+//!
+//! for stream in demo.voice_streams() {
+//!     let mut decoder = SpeexDecoder::new(stream.quality).unwrap();
+//!     let wav = stream.to_wav(&mut decoder);
+//!
+//!     std::fs::write(format!("player{}.wav", stream.player_index), wav).unwrap();
+//! }
+//! ```
+use std::collections::BTreeMap;
+
+use crate::{
+    byte_writer::ByteWriter,
+    roster::PlayerRoster,
+    types::{ByteVec, Demo, EngineMessage, FrameData, SvcVoiceData, SvcVoiceInit},
+};
+
+#[cfg(feature = "speex")]
+mod speex;
+#[cfg(feature = "speex")]
+pub use speex::SpeexDecoder;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VoiceCodec {
+    /// `voice_speex`
+    Speex,
+    /// `voice_miles`
+    Miles,
+    Other(String),
+}
+
+impl VoiceCodec {
+    pub fn from_init(init: &SvcVoiceInit) -> Self {
+        let end = init
+            .codec_name
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(init.codec_name.len());
+
+        match String::from_utf8_lossy(&init.codec_name[..end]).as_ref() {
+            "voice_speex" => Self::Speex,
+            "voice_miles" => Self::Miles,
+            other => Self::Other(other.to_owned()),
+        }
+    }
+}
+
+/// Decodes one voice packet into mono 16-bit PCM.
+pub trait VoiceDecoder {
+    fn sample_rate(&self) -> u32;
+    /// Returns None if the packet cannot be decoded. It is then left as silence.
+    fn decode(&mut self, data: &[u8]) -> Option<Vec<i16>>;
+}
+
+#[derive(Debug, Clone)]
+pub struct VoicePacket {
+    /// Time since the start of the demo.
+    ///
+    /// Unlike [`crate::types::Frame::time`], this does not restart with each directory entry.
+    pub time: f32,
+    pub entry_index: usize,
+    pub frame_index: usize,
+    pub data: ByteVec,
+}
+
+#[derive(Debug, Clone)]
+pub struct VoiceStream {
+    /// [`SvcVoiceData::player_index`], the 0-based player slot.
+    pub player_index: u8,
+    /// Name of the player when they first speak.
+    pub name: Option<String>,
+    /// From the latest [`SvcVoiceInit`] before the first packet.
+    pub codec: Option<VoiceCodec>,
+    pub quality: i8,
+    pub packets: Vec<VoicePacket>,
+}
+
+impl VoiceStream {
+    /// Decodes every packet and lays them out on the demo time, starting from the first packet.
+    ///
+    /// Gaps between packets are filled with silence. Packets running over the next one are kept
+    /// whole so nothing is cut.
+    pub fn to_pcm(&self, decoder: &mut impl VoiceDecoder) -> Vec<i16> {
+        let Some(start) = self.packets.first().map(|packet| packet.time) else {
+            return vec![];
+        };
+
+        let sample_rate = decoder.sample_rate() as f32;
+        let mut res: Vec<i16> = vec![];
+
+        for packet in &self.packets {
+            let Some(samples) = decoder.decode(&packet.data) else {
+                continue;
+            };
+
+            let position = ((packet.time - start) * sample_rate) as usize;
+
+            if position > res.len() {
+                res.resize(position, 0);
+            }
+
+            res.extend(samples);
+        }
+
+        res
+    }
+
+    /// Decodes the stream and writes it as a mono 16-bit WAV file.
+    pub fn to_wav(&self, decoder: &mut impl VoiceDecoder) -> ByteVec {
+        let samples = self.to_pcm(decoder);
+
+        write_wav(&samples, decoder.sample_rate())
+    }
+}
+
+impl Demo {
+    /// Groups every [`SvcVoiceData`] by player in the order they are sent.
+    ///
+    /// Only network messages parsed with [`crate::types::MessageDataParseMode::Parse`] are read.
+    pub fn voice_streams(&self) -> Vec<VoiceStream> {
+        let mut streams: BTreeMap<u8, VoiceStream> = BTreeMap::new();
+        let mut roster = PlayerRoster::new();
+        let mut init: Option<&SvcVoiceInit> = None;
+
+        // frame time restarts with each entry so keep adding the end time of the previous entries
+        let mut entry_offset = 0.;
+
+        for (entry_index, entry) in self.directory.entries.iter().enumerate() {
+            for (frame_index, frame) in entry.frames.iter().enumerate() {
+                let FrameData::NetworkMessage(box_type) = &frame.frame_data else {
+                    continue;
+                };
+
+                let Some(messages) = box_type.1.messages.get_parsed_messages() else {
+                    continue;
+                };
+
+                for message in messages.iter().filter_map(|msg| msg.get_engine_message()) {
+                    roster.apply(message, frame.time, entry_index, frame_index);
+
+                    match message {
+                        EngineMessage::SvcVoiceInit(voice_init) => init = Some(voice_init),
+                        EngineMessage::SvcVoiceData(SvcVoiceData {
+                            player_index, data, ..
+                        }) => {
+                            let stream =
+                                streams.entry(*player_index).or_insert_with(|| VoiceStream {
+                                    player_index: *player_index,
+                                    name: roster
+                                        .by_slot(*player_index)
+                                        .map(|player| player.name.clone()),
+                                    codec: init.map(VoiceCodec::from_init),
+                                    quality: init.map(|init| init.quality).unwrap_or_default(),
+                                    packets: vec![],
+                                });
+
+                            stream.packets.push(VoicePacket {
+                                time: entry_offset + frame.time,
+                                entry_index,
+                                frame_index,
+                                data: data.clone(),
+                            });
+                        }
+                        _ => (),
+                    }
+                }
+            }
+
+            entry_offset += entry.frames.last().map(|frame| frame.time).unwrap_or(0.);
+        }
+
+        streams.into_values().collect()
+    }
+}
+
+/// Writes mono 16-bit PCM as a WAV file.
+pub fn write_wav(samples: &[i16], sample_rate: u32) -> ByteVec {
+    let mut writer = ByteWriter::new();
+    let data_size = samples.len() as u32 * 2;
+
+    writer.append_u8_slice(b"RIFF");
+    writer.append_u32(36 + data_size);
+    writer.append_u8_slice(b"WAVE");
+
+    writer.append_u8_slice(b"fmt ");
+    writer.append_u32(16);
+    // PCM
+    writer.append_u16(1);
+    // channels
+    writer.append_u16(1);
+    writer.append_u32(sample_rate);
+    // byte rate
+    writer.append_u32(sample_rate * 2);
+    // block align
+    writer.append_u16(2);
+    // bits per sample
+    writer.append_u16(16);
+
+    writer.append_u8_slice(b"data");
+    writer.append_u32(data_size);
+    writer.append_i16_slice(samples);
+
+    writer.data
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Every packet decodes to as many samples as it has bytes.
+    struct Passthrough;
+
+    impl VoiceDecoder for Passthrough {
+        fn sample_rate(&self) -> u32 {
+            10
+        }
+
+        fn decode(&mut self, data: &[u8]) -> Option<Vec<i16>> {
+            Some(data.iter().map(|&x| x as i16).collect())
+        }
+    }
+
+    fn packet(time: f32, data: &[u8]) -> VoicePacket {
+        VoicePacket {
+            time,
+            entry_index: 1,
+            frame_index: 0,
+            data: data.to_vec(),
+        }
+    }
+
+    #[test]
+    fn silence_gap() {
+        let stream = VoiceStream {
+            player_index: 0,
+            name: None,
+            codec: Some(VoiceCodec::Speex),
+            quality: 5,
+            packets: vec![packet(1., &[1, 2]), packet(1.5, &[3]), packet(1.55, &[4])],
+        };
+
+        assert_eq!(stream.to_pcm(&mut Passthrough), [1, 2, 0, 0, 0, 3, 4]);
+    }
+
+    #[test]
+    fn wav_header() {
+        let wav = write_wav(&[0, 1, -1], 8000);
+
+        assert_eq!(wav.len(), 44 + 6);
+        assert_eq!(&wav[..4], b"RIFF");
+        assert_eq!(&wav[36..40], b"data");
+        assert_eq!(u32::from_le_bytes(wav[24..28].try_into().unwrap()), 8000);
+    }
+}
@@ -0,0 +1,143 @@
+//! `voice_speex` through the libspeex sources bundled in `vendor/speex`.
+use std::ffi::{c_char, c_int, c_void};
+
+use super::VoiceDecoder;
+
+/// Bytes of one encoded frame, indexed by `quality * 2` like the engine does.
+const ENCODED_FRAME_SIZE: [usize; 11] = [6, 6, 15, 15, 20, 20, 28, 28, 38, 38, 38];
+const FRAME_SAMPLES: usize = 160;
+const SAMPLE_RATE: u32 = 8000;
+
+const SPEEX_MODEID_NB: c_int = 0;
+const SPEEX_SET_ENH: c_int = 0;
+
+#[repr(C)]
+struct SpeexBits {
+    chars: *mut c_char,
+    nb_bits: c_int,
+    char_ptr: c_int,
+    bit_ptr: c_int,
+    owner: c_int,
+    overflow: c_int,
+    buf_size: c_int,
+    reserved1: c_int,
+    reserved2: *mut c_void,
+}
+
+unsafe extern "C" {
+    fn speex_lib_get_mode(mode: c_int) -> *const c_void;
+    fn speex_decoder_init(mode: *const c_void) -> *mut c_void;
+    fn speex_decoder_destroy(state: *mut c_void);
+    fn speex_decoder_ctl(state: *mut c_void, request: c_int, ptr: *mut c_void) -> c_int;
+    fn speex_decode_int(state: *mut c_void, bits: *mut SpeexBits, out: *mut i16) -> c_int;
+    fn speex_bits_init(bits: *mut SpeexBits);
+    fn speex_bits_destroy(bits: *mut SpeexBits);
+    fn speex_bits_read_from(bits: *mut SpeexBits, bytes: *const c_char, len: c_int);
+}
+
+/// Narrowband Speex decoder for GoldSrc voice.
+pub struct SpeexDecoder {
+    state: *mut c_void,
+    bits: Box<SpeexBits>,
+    frame_size: usize,
+}
+
+impl SpeexDecoder {
+    /// `quality` is [`crate::types::SvcVoiceInit::quality`].
+    ///
+    /// Returns None if the quality is out of range or the decoder cannot be created.
+    pub fn new(quality: i8) -> Option<Self> {
+        let frame_size = *ENCODED_FRAME_SIZE.get(usize::try_from(quality).ok()? * 2)?;
+
+        // SAFETY: the mode is a static from libspeex and the bits are initialized before use.
+        unsafe {
+            let state = speex_decoder_init(speex_lib_get_mode(SPEEX_MODEID_NB));
+
+            if state.is_null() {
+                return None;
+            }
+
+            let mut enhance: c_int = 1;
+            speex_decoder_ctl(
+                state,
+                SPEEX_SET_ENH,
+                &mut enhance as *mut c_int as *mut c_void,
+            );
+
+            let mut bits = Box::new(std::mem::zeroed::<SpeexBits>());
+            speex_bits_init(bits.as_mut());
+
+            Some(Self {
+                state,
+                bits,
+                frame_size,
+            })
+        }
+    }
+}
+
+impl VoiceDecoder for SpeexDecoder {
+    fn sample_rate(&self) -> u32 {
+        SAMPLE_RATE
+    }
+
+    fn decode(&mut self, data: &[u8]) -> Option<Vec<i16>> {
+        let mut res = vec![];
+
+        // trailing bytes short of a frame are dropped like the engine does
+        for frame in data.chunks_exact(self.frame_size) {
+            let mut samples = [0i16; FRAME_SAMPLES];
+
+            // SAFETY: frame is alive for the call and samples fits one narrowband frame.
+            let status = unsafe {
+                speex_bits_read_from(
+                    self.bits.as_mut(),
+                    frame.as_ptr() as *const c_char,
+                    frame.len() as c_int,
+                );
+                speex_decode_int(self.state, self.bits.as_mut(), samples.as_mut_ptr())
+            };
+
+            if status != 0 {
+                return None;
+            }
+
+            res.extend(samples);
+        }
+
+        Some(res)
+    }
+}
+
+impl Drop for SpeexDecoder {
+    fn drop(&mut self) {
+        // SAFETY: both are created in `new` and only destroyed here.
+        unsafe {
+            speex_bits_destroy(self.bits.as_mut());
+            speex_decoder_destroy(self.state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn invalid_quality() {
+        assert!(SpeexDecoder::new(-1).is_none());
+        assert!(SpeexDecoder::new(6).is_none());
+    }
+
+    #[test]
+    fn decode_frames() {
+        let mut decoder = SpeexDecoder::new(5).unwrap();
+        let frame_size = ENCODED_FRAME_SIZE[10];
+
+        // two frames and a partial one
+        let samples = decoder.decode(&vec![0; frame_size * 2 + 3]).unwrap();
+
+        assert_eq!(samples.len(), FRAME_SAMPLES * 2);
+        assert_eq!(decoder.sample_rate(), 8000);
+    }
+}